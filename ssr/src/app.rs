@@ -1,39 +1,43 @@
 use crate::{
-    component::{base_route::BaseRoute, nav::NavBar},
-    error_template::{AppError, ErrorTemplate},
+    component::{
+        base_route::BaseRoute,
+        nav::NavBar,
+        toast::{ToastHost, Toasts},
+    },
     page::{
         account_transfer::AccountTransfer,
         err::ServerErrorPage,
         leaderboard::Leaderboard,
         logout::Logout,
         menu::{AuthorizedUserToSeedContent, Menu},
+        not_found::NotFound,
         post_view::{single_post::SinglePost, PostView, PostViewCtx},
         privacy::PrivacyPolicy,
         profile::{profile_post::ProfilePost, ProfilePostsContext, ProfileView},
         refer_earn::ReferEarn,
         root::RootPage,
-        settings::Settings,
+        settings::{Sessions, Settings},
         terms::TermsOfService,
         upload::UploadPostPage,
-        wallet::{transactions::Transactions, Wallet},
+        wallet::{
+            transactions::{TransactionDetails, Transactions},
+            Wallet,
+        },
     },
     state::{
-        audio_state::AudioState, canisters::Canisters, content_seed_client::ContentSeedClient,
+        audio_state::AudioState,
+        canisters::Canisters,
+        cloudflare::{CloudflareConfig, CloudflareConfigScript},
+        content_seed_client::ContentSeedClient,
+        dev_mode::DevModeScript,
         history::HistoryCtx,
     },
-    utils::event_streaming::EventHistory,
+    utils::{event_streaming::EventHistory, i18n::Lang},
 };
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
 
-#[component]
-fn NotFound() -> impl IntoView {
-    let mut outside_errors = Errors::default();
-    outside_errors.insert_with_default_key(AppError::NotFound);
-    view! { <ErrorTemplate outside_errors /> }
-}
-
 #[component(transparent)]
 fn GoogleAuthRedirectHandlerRoute() -> impl IntoView {
     let path = "/auth/google_redirect";
@@ -72,6 +76,17 @@ pub fn App() -> impl IntoView {
     provide_context(ProfilePostsContext::default());
     provide_context(AuthorizedUserToSeedContent::default());
     provide_context(AudioState::default());
+    provide_context(Toasts::default());
+    let lang = Lang::detect();
+    provide_context(lang);
+    // Server provides the configuration from the environment
+    #[cfg(not(feature = "ssr"))]
+    {
+        use crate::state::auth::config::AuthConfig;
+        provide_context(CloudflareConfig::from_document());
+        provide_context(AuthConfig::default());
+    }
+    provide_context(CloudflareConfig::get().in_app_variant);
 
     #[cfg(feature = "hydrate")]
     {
@@ -98,6 +113,10 @@ pub fn App() -> impl IntoView {
 
     view! {
         <Stylesheet id="leptos" href="/pkg/hot-or-not-leptos-ssr.css" />
+        <CloudflareConfigScript />
+        <DevModeScript />
+        // the client hydrates the language from here, see [Lang::detect]
+        <Html lang=lang.code() />
 
         // sets the document title
         <Title text="Yral" />
@@ -140,10 +159,14 @@ pub fn App() -> impl IntoView {
                         <Route path="/error" view=ServerErrorPage/>
                         <Route path="/menu" view=Menu/>
                         <Route path="/settings" view=Settings/>
+                        <Route path="/settings/sessions" view=Sessions/>
                         <Route path="/refer-earn" view=ReferEarn/>
                         <Route path="/terms-of-service" view=TermsOfService/>
                         <Route path="/privacy-policy" view=PrivacyPolicy/>
                         <Route path="/wallet" view=Wallet/>
+                        <Route path="/wallet/transactions" view=Transactions/>
+                        <Route path="/wallet/txn/:id" view=TransactionDetails/>
+                        // Kept for old links
                         <Route path="/transactions" view=Transactions/>
                         <Route path="/leaderboard" view=Leaderboard/>
                         <Route path="/account-transfer" view=AccountTransfer/>
@@ -155,6 +178,7 @@ pub fn App() -> impl IntoView {
             <nav>
                 <NavBar/>
             </nav>
+            <ToastHost/>
         </Router>
     }
 }
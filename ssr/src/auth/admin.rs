@@ -0,0 +1,26 @@
+//! Support tooling, restricted to the principals in `SUPPORT_ADMINS` (comma separated)
+use candid::Principal;
+use leptos::{server, ServerFnError};
+use yral_metadata_types::UserMetadata;
+
+use super::signature::RequestSignature;
+
+/// Stored metadata of `principal`, `None` if the user has none
+/// `signature` must be over `principal` and by a principal in `SUPPORT_ADMINS`
+/// every access is logged along with the accessed principal
+#[server]
+pub async fn admin_user_metadata(
+    principal: Principal,
+    signature: RequestSignature,
+) -> Result<Option<UserMetadata>, ServerFnError> {
+    use crate::{auth::is_env_admin, state::canisters::unauth_canisters};
+
+    let admin = signature.verify("admin_user_metadata", &principal).await?;
+    if !is_env_admin("SUPPORT_ADMINS", admin) {
+        log::warn!("{admin} is not allowed to read the metadata of {principal}");
+        return Err(ServerFnError::new("Not authorized to read user metadata"));
+    }
+    log::info!("support admin {admin} read the metadata of {principal}");
+
+    unauth_canisters().get_user_metadata(principal).await
+}
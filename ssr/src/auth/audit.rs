@@ -0,0 +1,145 @@
+//! Audit log of sensitive auth events
+//! entries are atomically appended to a list per principal in the KV store,
+//! oldest entries are dropped past [MAX_AUDIT_ENTRIES]
+//! NEVER record secret keys, JWKs, delegations or cookies here
+use candid::Principal;
+use leptos::{server, ServerFnError};
+use serde::{Deserialize, Serialize};
+
+use super::signature::RequestSignature;
+
+/// Maximum number of entries kept per principal
+pub const MAX_AUDIT_ENTRIES: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditEventKind {
+    /// A new base identity was generated and saved
+    IdentityGenerated,
+    /// The temporary identity of an anonymous visitor was persisted
+    AnonymousIdentitySaved,
+    /// A delegated identity was issued for the base identity on login
+    /// or when rotating the refresh token
+    DelegationMinted,
+    /// A google account was linked to the base identity
+    GoogleAccountLinked,
+    /// The user logged out of the base identity
+    LoggedOut,
+    /// A session was revoked from another device
+    SessionRevoked,
+}
+
+/// Non-sensitive metadata of the request that caused an event
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AuditRequestMeta {
+    pub user_agent: Option<String>,
+    pub client_ip: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub kind: AuditEventKind,
+    pub principal: Principal,
+    /// Epoch (in milliseconds) at which the event occured
+    pub at_ms: u128,
+    pub request: AuditRequestMeta,
+}
+
+/// Recent audit events for `principal`, newest first
+/// `signature` must be over `principal` and by a principal in `AUDIT_LOG_ADMINS`
+#[server]
+pub async fn recent_audit_events(
+    principal: Principal,
+    signature: RequestSignature,
+) -> Result<Vec<AuditEntry>, ServerFnError> {
+    use crate::auth::server_impl::store::KVStoreImpl;
+
+    let admin = signature.verify("recent_audit_events", &principal).await?;
+    if !record::is_audit_admin(admin) {
+        return Err(ServerFnError::new("Not authorized to read audit logs"));
+    }
+
+    let kv: KVStoreImpl = leptos::expect_context();
+    let mut entries = record::read_entries(&kv, principal).await?;
+    entries.reverse();
+    Ok(entries)
+}
+
+#[cfg(feature = "ssr")]
+pub mod record {
+    use axum::http::request::Parts;
+    use leptos::{expect_context, use_context};
+
+    use crate::{
+        auth::{
+            is_env_admin,
+            server_impl::{
+                client_ip::client_ip,
+                store::{KVError, KVStore, KVStoreImpl},
+            },
+        },
+        utils::current_epoch,
+    };
+
+    use super::*;
+
+    fn audit_key(principal: Principal) -> String {
+        format!("audit-log-{}", principal.to_text())
+    }
+
+    /// Principals allowed to read audit logs, from `AUDIT_LOG_ADMINS` (comma separated)
+    pub(super) fn is_audit_admin(principal: Principal) -> bool {
+        is_env_admin("AUDIT_LOG_ADMINS", principal)
+    }
+
+    pub(crate) fn request_meta() -> AuditRequestMeta {
+        let Some(parts) = use_context::<Parts>() else {
+            return AuditRequestMeta::default();
+        };
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(str::to_string)
+        };
+        AuditRequestMeta {
+            user_agent: header("User-Agent"),
+            client_ip: client_ip(&parts).map(|ip| ip.to_string()),
+        }
+    }
+
+    pub(super) async fn read_entries(
+        kv: &KVStoreImpl,
+        principal: Principal,
+    ) -> Result<Vec<AuditEntry>, KVError> {
+        kv.read_list(audit_key(principal))
+            .await?
+            .iter()
+            .map(|raw| serde_json::from_str(raw).map_err(KVError::from))
+            .collect()
+    }
+
+    async fn append(kv: &KVStoreImpl, entry: AuditEntry) -> Result<(), KVError> {
+        kv.append_capped(
+            audit_key(entry.principal),
+            serde_json::to_string(&entry)?,
+            MAX_AUDIT_ENTRIES,
+        )
+        .await
+    }
+
+    /// Record an audit event for `principal`
+    /// failures are logged, auth flows must not fail because of the audit log
+    pub async fn record_audit_event(kind: AuditEventKind, principal: Principal) {
+        let kv: KVStoreImpl = expect_context();
+        let entry = AuditEntry {
+            kind,
+            principal,
+            at_ms: current_epoch().as_millis(),
+            request: request_meta(),
+        };
+        if let Err(e) = append(&kv, entry).await {
+            log::error!("failed to record audit event {kind:?} for {principal}: {e}");
+        }
+    }
+}
@@ -1,5 +1,9 @@
+pub mod admin;
+pub mod audit;
 #[cfg(feature = "ssr")]
 pub mod server_impl;
+pub mod session;
+pub mod signature;
 
 use candid::Principal;
 use ic_agent::{
@@ -12,7 +16,7 @@ use rand_chacha::rand_core::OsRng;
 use serde::{Deserialize, Serialize};
 use web_time::Duration;
 
-use crate::{consts::auth::DELEGATION_MAX_AGE, utils::current_epoch};
+use crate::{state::auth::config::AuthConfig, utils::current_epoch};
 
 /// Delegated identity that can be serialized over the wire
 #[derive(Serialize, Deserialize, Clone)]
@@ -28,7 +32,11 @@ pub struct DelegatedIdentityWire {
 }
 
 impl DelegatedIdentityWire {
-    fn delegate_with_max_age(from: &impl Identity, max_age: Duration) -> Self {
+    fn delegate_with_max_age(
+        from: &impl Identity,
+        max_age: Duration,
+        targets: Option<Vec<Principal>>,
+    ) -> Self {
         let to_secret = k256::SecretKey::random(&mut OsRng);
         let to_identity = Secp256k1Identity::from_private_key(to_secret.clone());
         let expiry = current_epoch() + max_age;
@@ -36,7 +44,7 @@ impl DelegatedIdentityWire {
         let delegation = Delegation {
             pubkey: to_identity.public_key().unwrap(),
             expiration: expiry_ns,
-            targets: None,
+            targets,
         };
         let sig = from.sign_delegation(&delegation).unwrap();
         let signed_delegation = SignedDelegation {
@@ -54,13 +62,33 @@ impl DelegatedIdentityWire {
         }
     }
 
+    /// Unscoped delegation, valid for calls to any canister
+    ///
+    /// Required for the user session, which talks to the individual user canister,
+    /// the user index, the post cache and other canisters that are only discovered
+    /// at runtime (e.g. the individual canisters of other users)
     pub fn delegate(from: &impl Identity) -> Self {
-        Self::delegate_with_max_age(from, DELEGATION_MAX_AGE)
+        Self::delegate_with_max_age(from, AuthConfig::get().delegation_max_age, None)
     }
 
-    pub fn delegate_short_lived_identity(from: &impl Identity) -> Self {
+    /// Delegation restricted to `targets`, valid for `max_age`
+    /// calls to any other canister are rejected by the replica
+    ///
+    /// Use for flows that only talk to a known set of canisters, e.g.
+    /// a third party service acting on the user's individual canister (and ledger)
+    pub fn delegate_scoped(
+        from: &impl Identity,
+        targets: Vec<Principal>,
+        max_age: Duration,
+    ) -> Self {
+        Self::delegate_with_max_age(from, max_age, Some(targets))
+    }
+
+    /// Delegation valid for a day, restricted to the user's individual canister
+    /// handed to the upload service, which creates the post on behalf of the user
+    pub fn delegate_short_lived_identity(from: &impl Identity, user_canister: Principal) -> Self {
         let max_age = Duration::from_secs(24 * 60 * 60); // 1 day
-        Self::delegate_with_max_age(from, max_age)
+        Self::delegate_scoped(from, vec![user_canister], max_age)
     }
 }
 
@@ -74,14 +102,80 @@ impl std::fmt::Debug for DelegatedIdentityWire {
 pub struct RefreshToken {
     principal: Principal,
     expiry_epoch_ms: u128,
+    /// Generation of the token, bumped every time a token is issued
+    /// tokens older than the latest generation are rejected
+    #[serde(default)]
+    rotation: u64,
+    /// Session the token belongs to, `None` for tokens issued before sessions
+    #[serde(default)]
+    session: Option<u64>,
+}
+
+/// Reasons a [DelegatedIdentityWire] can't be turned into a usable identity
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum InvalidIdentityWire {
+    #[error("malformed secret key: {0}")]
+    SecretKey(#[from] k256::elliptic_curve::Error),
+    #[error("empty delegation chain")]
+    EmptyChain,
+    #[error("delegation chain does not end with the secret key")]
+    KeyMismatch,
+    #[error("delegation expired")]
+    Expired,
+    #[error("delegation chain is not signed by the delegating keys")]
+    BadSignature,
+}
+
+impl DelegatedIdentityWire {
+    /// Whether the delegation chain is still usable at `now` (since the unix epoch)
+    /// i.e no delegation expired and every link is signed by the previous key
+    ///
+    /// Only secp256k1 chains are supported, as minted by the server
+    pub fn is_valid(&self, now: Duration) -> bool {
+        self.validate(now).is_ok()
+    }
+
+    /// Reconstruct the secret key and check that the delegation chain
+    /// actually delegates to it
+    fn validated_identity(&self) -> Result<Secp256k1Identity, InvalidIdentityWire> {
+        self.validate(current_epoch())
+    }
+
+    fn validate(&self, now: Duration) -> Result<Secp256k1Identity, InvalidIdentityWire> {
+        let to_secret = k256::SecretKey::from_jwk(&self.to_secret)?;
+        let to_identity = Secp256k1Identity::from_private_key(to_secret);
+
+        let last = self
+            .delegation_chain
+            .last()
+            .ok_or(InvalidIdentityWire::EmptyChain)?;
+        if to_identity.public_key().as_ref() != Some(&last.delegation.pubkey) {
+            return Err(InvalidIdentityWire::KeyMismatch);
+        }
+        let now_ns = now.as_nanos() as u64;
+        let mut signer_key = self.from_key.as_slice();
+        for signed in &self.delegation_chain {
+            if signed.delegation.expiration < now_ns {
+                return Err(InvalidIdentityWire::Expired);
+            }
+            signature::verify_secp256k1(
+                signer_key,
+                &signed.delegation.signable(),
+                &signed.signature,
+            )
+            .map_err(|_| InvalidIdentityWire::BadSignature)?;
+            signer_key = &signed.delegation.pubkey;
+        }
+
+        Ok(to_identity)
+    }
 }
 
 impl TryFrom<DelegatedIdentityWire> for DelegatedIdentity {
-    type Error = k256::elliptic_curve::Error;
+    type Error = InvalidIdentityWire;
 
     fn try_from(identity: DelegatedIdentityWire) -> Result<Self, Self::Error> {
-        let to_secret = k256::SecretKey::from_jwk(&identity.to_secret)?;
-        let to_identity = Secp256k1Identity::from_private_key(to_secret);
+        let to_identity = identity.validated_identity()?;
         Ok(Self::new(
             identity.from_key,
             Box::new(to_identity),
@@ -90,6 +184,45 @@ impl TryFrom<DelegatedIdentityWire> for DelegatedIdentity {
     }
 }
 
+/// Whether `principal` is listed in the env var `var` (comma separated principals)
+#[cfg(feature = "ssr")]
+pub(crate) fn is_env_admin(var: &str, principal: Principal) -> bool {
+    let Ok(admins) = std::env::var(var) else {
+        return false;
+    };
+    admins
+        .split(',')
+        .filter_map(|p| Principal::from_text(p.trim()).ok())
+        .any(|admin| admin == principal)
+}
+
+/// Error message of server functions that failed to persist an identity
+const STORAGE_UNAVAILABLE_ERR: &str = "identity storage unavailable";
+
+#[cfg(feature = "ssr")]
+pub(crate) fn storage_unavailable_err() -> ServerFnError {
+    ServerFnError::new(STORAGE_UNAVAILABLE_ERR)
+}
+
+/// Error message of server functions rejected for creating too many identities
+const RATE_LIMITED_ERR: &str = "too many new identities, try again later";
+
+#[cfg(feature = "ssr")]
+pub(crate) fn rate_limited_err() -> ServerFnError {
+    ServerFnError::new(RATE_LIMITED_ERR)
+}
+
+/// Whether `err` was caused by the client creating too many identities
+pub fn is_rate_limited(err: &ServerFnError) -> bool {
+    matches!(err, ServerFnError::ServerError(msg) if msg == RATE_LIMITED_ERR)
+}
+
+/// Whether `err` was caused by the identity storage being unavailable
+/// such requests are safe to retry later
+pub fn is_storage_unavailable(err: &ServerFnError) -> bool {
+    matches!(err, ServerFnError::ServerError(msg) if msg == STORAGE_UNAVAILABLE_ERR)
+}
+
 /// Generate an anonymous identity if refresh token is not set
 #[server]
 pub async fn generate_anonymous_identity_if_required() -> Result<Option<JwkEcKey>, ServerFnError> {
@@ -106,16 +239,42 @@ pub async fn set_anonymous_identity_cookie(
 
 /// Extract the identity from refresh token,
 /// returns None if refresh token doesn't exist
+/// the refresh token is not rotated, use [refresh_identity] from the client
 #[server]
 pub async fn extract_identity() -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
     server_impl::extract_identity_impl().await
 }
 
+/// Rotate the refresh token and extract a new delegation of its identity
+/// returns None if there is no (valid) refresh token
+///
+/// Only call from the client, a cookie set while rendering on the server may be dropped
+#[server]
+pub async fn refresh_identity() -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
+    server_impl::refresh_identity_impl().await
+}
+
 #[server]
 pub async fn logout_identity() -> Result<DelegatedIdentityWire, ServerFnError> {
     server_impl::logout_identity_impl().await
 }
 
+/// Sign out of the current session, without issuing a new identity
+/// the refresh token is cleared and invalidated, the base identity is kept
+/// so that a linked login can restore it
+/// no-op if there is no session
+#[server]
+pub async fn logout() -> Result<(), ServerFnError> {
+    server_impl::logout_impl().await
+}
+
+/// Expiry of the refresh token (epoch, in milliseconds)
+/// only meant for debugging and support
+#[server]
+pub async fn refresh_token_expiry() -> Result<Option<u128>, ServerFnError> {
+    server_impl::refresh_token_expiry_impl().await
+}
+
 #[cfg(feature = "oauth-ssr")]
 pub mod core_clients {
     #[derive(Clone)]
@@ -134,3 +293,103 @@ pub mod core_clients {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_identity() -> Secp256k1Identity {
+        Secp256k1Identity::from_private_key(k256::SecretKey::random(&mut OsRng))
+    }
+
+    #[test]
+    fn fresh_delegation_is_valid() {
+        let wire = DelegatedIdentityWire::delegate(&base_identity());
+        assert!(wire.is_valid(current_epoch()));
+    }
+
+    #[test]
+    fn expired_delegation_is_invalid() {
+        let wire = DelegatedIdentityWire::delegate(&base_identity());
+        let after_expiry =
+            current_epoch() + AuthConfig::get().delegation_max_age + Duration::from_secs(1);
+        assert!(!wire.is_valid(after_expiry));
+    }
+
+    #[test]
+    fn scoped_delegation_is_restricted_to_its_targets() {
+        let targets = vec![
+            Principal::from_text("rdmx6-jaaaa-aaaaa-aaadq-cai").unwrap(),
+            Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap(),
+        ];
+        let wire = DelegatedIdentityWire::delegate_scoped(
+            &base_identity(),
+            targets.clone(),
+            Duration::from_secs(60),
+        );
+        let chain = &wire.delegation_chain;
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain[0].delegation.targets, Some(targets));
+        assert!(wire.is_valid(current_epoch()));
+
+        let unscoped = DelegatedIdentityWire::delegate(&base_identity());
+        assert_eq!(unscoped.delegation_chain[0].delegation.targets, None);
+    }
+
+    #[test]
+    fn upload_delegation_is_scoped_to_the_user_canister() {
+        let user_canister = Principal::from_text("rdmx6-jaaaa-aaaaa-aaadq-cai").unwrap();
+        let wire =
+            DelegatedIdentityWire::delegate_short_lived_identity(&base_identity(), user_canister);
+        let targets = &wire.delegation_chain[0].delegation.targets;
+        assert_eq!(targets.as_deref(), Some(&[user_canister][..]));
+    }
+
+    #[test]
+    fn tampered_delegation_is_invalid() {
+        let wire = DelegatedIdentityWire::delegate(&base_identity());
+        let now = current_epoch();
+
+        let mut extended = wire.clone();
+        extended.delegation_chain[0].delegation.expiration += 1;
+        assert!(!extended.is_valid(now));
+
+        let mut resigned = wire.clone();
+        resigned.from_key = base_identity().public_key().unwrap();
+        assert!(!resigned.is_valid(now));
+
+        let mut swapped = wire;
+        swapped.to_secret = k256::SecretKey::random(&mut OsRng).to_jwk();
+        assert!(!swapped.is_valid(now));
+    }
+
+    /// Replace the private scalar of the wire's JWK, as a corruption in transit would
+    fn with_corrupt_secret(wire: &DelegatedIdentityWire, d: &str) -> DelegatedIdentityWire {
+        let mut raw = serde_json::to_value(wire).unwrap();
+        raw["to_secret"]["d"] = d.into();
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn corrupt_secret_is_rejected() {
+        let wire = DelegatedIdentityWire::delegate(&base_identity());
+        for d in ["", "AAAA", "not base64!"] {
+            let corrupt = with_corrupt_secret(&wire, d);
+            assert!(!corrupt.is_valid(current_epoch()), "accepted d = `{d}`");
+            assert!(matches!(
+                DelegatedIdentity::try_from(corrupt),
+                Err(InvalidIdentityWire::SecretKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn empty_chain_is_rejected() {
+        let mut wire = DelegatedIdentityWire::delegate(&base_identity());
+        wire.delegation_chain.clear();
+        assert!(matches!(
+            DelegatedIdentity::try_from(wire),
+            Err(InvalidIdentityWire::EmptyChain)
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+//! IP of the client making a request
+//! fly.io's proxy forwards it in `Fly-Client-IP`, which is only honoured for trusted proxies
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::ConnectInfo;
+use http::{request::Parts, HeaderMap};
+use ipnet::IpNet;
+use leptos::use_context;
+
+const FORWARDED_IP_HEADER: &str = "fly-client-ip";
+
+/// Proxies trusted to set `Fly-Client-IP`, from `TRUSTED_PROXIES` (comma separated CIDRs)
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies(Vec<IpNet>);
+
+impl TrustedProxies {
+    /// # Panics
+    /// If `TRUSTED_PROXIES` is invalid, or unset while running on fly.io
+    /// where every peer is the proxy and all clients would be indistinguishable
+    pub fn from_env() -> Self {
+        let proxies = std::env::var("TRUSTED_PROXIES").unwrap_or_default();
+        let on_fly = std::env::var("FLY_APP_NAME").is_ok();
+        Self::parse(&proxies, on_fly).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn parse(proxies: &str, on_fly: bool) -> Result<Self, String> {
+        let proxies = proxies
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                p.parse()
+                    .map_err(|_| format!("Invalid `TRUSTED_PROXIES` entry `{p}`, expected a CIDR"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if on_fly && proxies.is_empty() {
+            return Err("`TRUSTED_PROXIES` must be set when running on fly.io".into());
+        }
+        Ok(Self(proxies))
+    }
+
+    /// Proxies provided in context
+    /// trusts nothing outside a reactive owner
+    pub fn get() -> Self {
+        use_context().unwrap_or_default()
+    }
+
+    fn trusts(&self, peer: IpAddr) -> bool {
+        self.0.iter().any(|net| net.contains(&peer))
+    }
+}
+
+/// `Fly-Client-IP` is only honoured if the peer is a trusted proxy,
+/// otherwise any client could pick the IP it's identified by
+fn resolve_client_ip(peer: IpAddr, forwarded: Option<IpAddr>, trusted: &TrustedProxies) -> IpAddr {
+    match forwarded {
+        Some(ip) if trusted.trusts(peer) => ip,
+        _ => peer,
+    }
+}
+
+fn forwarded_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get(FORWARDED_IP_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// IP of the client that sent `parts`
+/// `None` if the server wasn't started with connection info
+pub fn client_ip(parts: &Parts) -> Option<IpAddr> {
+    let ConnectInfo(peer) = parts.extensions.get::<ConnectInfo<SocketAddr>>()?;
+    Some(resolve_client_ip(
+        peer.ip(),
+        forwarded_ip(&parts.headers),
+        &TrustedProxies::get(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(proxies: &str) -> TrustedProxies {
+        TrustedProxies::parse(proxies, false).unwrap()
+    }
+
+    #[test]
+    fn forwarded_ip_requires_trusted_peer() {
+        let trusted = parse("172.16.0.0/12, fdaa::/16");
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let proxy: IpAddr = "172.19.0.1".parse().unwrap();
+        let stranger: IpAddr = "198.51.100.1".parse().unwrap();
+
+        assert_eq!(resolve_client_ip(proxy, Some(client), &trusted), client);
+        assert_eq!(resolve_client_ip(proxy, None, &trusted), proxy);
+        assert_eq!(
+            resolve_client_ip(stranger, Some(client), &trusted),
+            stranger
+        );
+        assert_eq!(
+            resolve_client_ip(proxy, Some(client), &TrustedProxies::default()),
+            proxy
+        );
+    }
+
+    #[test]
+    fn empty_trusted_proxies_trust_nothing() {
+        assert!(parse("").0.is_empty());
+        assert!(parse(" , ").0.is_empty());
+    }
+
+    #[test]
+    fn invalid_trusted_proxies_are_rejected() {
+        let err = TrustedProxies::parse("172.16.0.0/12, proxy", false).unwrap_err();
+        assert!(err.contains("`proxy`"), "{err}");
+    }
+
+    #[test]
+    fn trusted_proxies_are_required_on_fly() {
+        assert!(TrustedProxies::parse("", true).is_err());
+        assert!(TrustedProxies::parse("172.16.0.0/12", true).is_ok());
+    }
+
+    #[test]
+    fn forwarded_ip_is_read_from_the_fly_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(forwarded_ip(&headers), None);
+        headers.insert("Fly-Client-IP", " 203.0.113.7".parse().unwrap());
+        assert_eq!(forwarded_ip(&headers), Some("203.0.113.7".parse().unwrap()));
+        headers.insert("Fly-Client-IP", "garbage".parse().unwrap());
+        assert_eq!(forwarded_ip(&headers), None);
+    }
+}
@@ -14,6 +14,7 @@ use openidconnect::{
 use web_time::Duration;
 
 use crate::auth::{
+    audit::{record::record_audit_event, AuditEventKind},
     server_impl::{
         fetch_identity_from_kv, store::KVStore, try_extract_identity,
         update_user_identity_and_delegate,
@@ -98,6 +99,7 @@ async fn extract_identity_and_associate_with_google_sub(
     let principal = identity.sender().unwrap();
     kv.write(principal_lookup_key(sub_id), principal.to_text())
         .await?;
+    record_audit_event(AuditEventKind::GoogleAccountLinked, principal).await;
 
     Ok(identity)
 }
@@ -153,7 +155,7 @@ pub async fn perform_google_auth_impl(
         extract_identity_and_associate_with_google_sub(&kv, &jar, sub_id).await?
     };
 
-    let delegated = update_user_identity_and_delegate(&resp, jar, identity)?;
+    let delegated = update_user_identity_and_delegate(&resp, jar, &kv, identity).await?;
 
     Ok(delegated)
 }
@@ -1,10 +1,13 @@
+pub mod client_ip;
 #[cfg(feature = "oauth-ssr")]
 pub mod google;
+mod rate_limit;
+mod session;
 pub mod store;
 
 use axum::response::IntoResponse;
 use axum_extra::extract::{
-    cookie::{Cookie, Key, SameSite},
+    cookie::{Cookie, CookieBuilder, Key, SameSite},
     SignedCookieJar,
 };
 use candid::Principal;
@@ -14,15 +17,26 @@ use k256::elliptic_curve::JwkEcKey;
 use leptos::{expect_context, ServerFnError};
 use leptos_axum::{extract_with_state, ResponseOptions};
 use rand_chacha::rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
 
 use crate::{
-    consts::auth::{REFRESH_MAX_AGE, REFRESH_TOKEN_COOKIE},
+    auth::session::SessionInfo,
+    consts::auth::REFRESH_TOKEN_COOKIE,
+    state::{auth::config::AuthConfig, dev_mode::DevMode},
     utils::current_epoch,
 };
 
-use self::store::{KVStore, KVStoreImpl};
+use self::{
+    rate_limit::limit_identity_generation,
+    session::{read_session, read_sessions, remove_session, rotate_session, start_session},
+    store::{update, KVStore, KVStoreImpl},
+};
 
-use super::{DelegatedIdentityWire, RefreshToken};
+use super::{
+    audit::{record::record_audit_event, AuditEventKind},
+    storage_unavailable_err, DelegatedIdentityWire, RefreshToken,
+};
 
 fn set_cookies(resp: &ResponseOptions, jar: impl IntoResponse) {
     let resp_jar = jar.into_response();
@@ -36,9 +50,7 @@ fn set_cookies(resp: &ResponseOptions, jar: impl IntoResponse) {
     }
 }
 
-fn extract_principal_from_cookie(
-    jar: &SignedCookieJar,
-) -> Result<Option<Principal>, ServerFnError> {
+fn extract_token_from_cookie(jar: &SignedCookieJar) -> Result<Option<RefreshToken>, ServerFnError> {
     let Some(cookie) = jar.get(REFRESH_TOKEN_COOKIE) else {
         return Ok(None);
     };
@@ -46,7 +58,92 @@ fn extract_principal_from_cookie(
     if current_epoch().as_millis() > token.expiry_epoch_ms {
         return Ok(None);
     }
-    Ok(Some(token.principal))
+    Ok(Some(token))
+}
+
+fn extract_principal_from_cookie(
+    jar: &SignedCookieJar,
+) -> Result<Option<Principal>, ServerFnError> {
+    Ok(extract_token_from_cookie(jar)?.map(|t| t.principal))
+}
+
+/// Requests made concurrently with a rotation (e.g from multiple tabs)
+/// may still present the previous token for this long
+const ROTATION_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Latest refresh token generation of a principal
+/// only tracked for tokens issued before sessions
+#[derive(Serialize, Deserialize, Default)]
+struct RotationState {
+    rotation: u64,
+    rotated_at_ms: u128,
+}
+
+fn rotation_key(principal: Principal) -> String {
+    format!("refresh-rotation-{principal}")
+}
+
+async fn read_rotation(
+    kv: &KVStoreImpl,
+    principal: Principal,
+) -> Result<RotationState, ServerFnError> {
+    let Some(state) = kv.read(rotation_key(principal)).await? else {
+        return Ok(RotationState::default());
+    };
+    Ok(serde_json::from_str(&state)?)
+}
+
+/// Start a new token generation, invalidating the previous tokens
+/// that were issued before sessions
+async fn rotate(kv: &KVStoreImpl, principal: Principal) -> Result<u64, ServerFnError> {
+    let rotation = update(kv, rotation_key(principal), |raw| {
+        let state: RotationState = raw
+            .map(serde_json::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        let state = RotationState {
+            rotation: state.rotation + 1,
+            rotated_at_ms: current_epoch().as_millis(),
+        };
+        Ok((Some(serde_json::to_string(&state)?), state.rotation))
+    })
+    .await?;
+    Ok(rotation)
+}
+
+/// Whether `token` belongs to the latest generation of its session
+/// tokens of revoked sessions are rejected
+/// Tokens without a session are checked against the principal's generation,
+/// tokens without a generation predate rotation and are accepted until the first rotation
+async fn is_token_current(kv: &KVStoreImpl, token: &RefreshToken) -> Result<bool, ServerFnError> {
+    let (rotation, rotated_at_ms) = if let Some(id) = token.session {
+        let Some(session) = read_session(kv, token.principal, id).await? else {
+            return Ok(false);
+        };
+        (session.rotation, session.rotated_at_ms)
+    } else {
+        let state = read_rotation(kv, token.principal).await?;
+        (state.rotation, state.rotated_at_ms)
+    };
+    if token.rotation == rotation {
+        return Ok(true);
+    }
+    let in_grace = current_epoch().as_millis() < rotated_at_ms + ROTATION_GRACE_PERIOD.as_millis();
+    Ok(token.rotation + 1 == rotation && in_grace)
+}
+
+/// Refresh token of the caller, if it's still accepted
+async fn current_token(
+    jar: &SignedCookieJar,
+    kv: &KVStoreImpl,
+) -> Result<Option<RefreshToken>, ServerFnError> {
+    let Some(token) = extract_token_from_cookie(jar)? else {
+        return Ok(None);
+    };
+    if !is_token_current(kv, &token).await? {
+        return Ok(None);
+    }
+    Ok(Some(token))
 }
 
 async fn fetch_identity_from_kv(
@@ -64,10 +161,46 @@ pub async fn try_extract_identity(
     jar: &SignedCookieJar,
     kv: &KVStoreImpl,
 ) -> Result<Option<k256::SecretKey>, ServerFnError> {
-    let Some(principal) = extract_principal_from_cookie(jar)? else {
+    let Some(token) = extract_token_from_cookie(jar)? else {
         return Ok(None);
     };
-    fetch_identity_from_kv(kv, principal).await
+    // A superseded token was replayed, the cookie may have been stolen
+    // force a new session instead of restoring the identity
+    if !is_token_current(kv, &token).await? {
+        log::warn!("rejecting superseded refresh token for {}", token.principal);
+        return Ok(None);
+    }
+    fetch_identity_from_kv(kv, token.principal).await
+}
+
+/// Persist the base identity of `principal`
+/// the write is retried once and read back, so that identities
+/// that were never persisted are not handed out
+async fn persist_identity(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    jwk: String,
+) -> Result<(), ServerFnError> {
+    let key = principal.to_text();
+    if let Err(e) = kv.write(key.clone(), jwk.clone()).await {
+        log::warn!("failed to persist identity for {principal}, retrying: {e}");
+        kv.write(key.clone(), jwk.clone()).await.map_err(|e| {
+            log::error!("failed to persist identity for {principal}: {e}");
+            storage_unavailable_err()
+        })?;
+    }
+
+    match kv.read(key).await {
+        Ok(Some(stored)) if stored == jwk => Ok(()),
+        Ok(_) => {
+            log::error!("identity for {principal} missing after write");
+            Err(storage_unavailable_err())
+        }
+        Err(e) => {
+            log::error!("failed to verify identity for {principal}: {e}");
+            Err(storage_unavailable_err())
+        }
+    }
 }
 
 async fn generate_and_save_identity(kv: &KVStoreImpl) -> Result<Secp256k1Identity, ServerFnError> {
@@ -76,7 +209,8 @@ async fn generate_and_save_identity(kv: &KVStoreImpl) -> Result<Secp256k1Identit
     let principal = base_identity.sender().unwrap();
 
     let base_jwk = base_identity_key.to_jwk_string();
-    kv.write(principal.to_text(), base_jwk.to_string()).await?;
+    persist_identity(kv, principal, base_jwk.to_string()).await?;
+    record_audit_event(AuditEventKind::IdentityGenerated, principal).await;
     Ok(base_identity)
 }
 
@@ -85,7 +219,8 @@ async fn save_identity(kv: &KVStoreImpl, id: JwkEcKey) -> Result<Secp256k1Identi
     let principal = base_identity.sender().unwrap();
 
     let base_jwk = id.to_string();
-    kv.write(principal.to_text(), base_jwk).await?;
+    persist_identity(kv, principal, base_jwk).await?;
+    record_audit_event(AuditEventKind::AnonymousIdentitySaved, principal).await;
     Ok(base_identity)
 }
 
@@ -96,65 +231,190 @@ fn identity_from_jwk(id: &JwkEcKey) -> Result<Secp256k1Identity, ServerFnError>
     Ok(base_identity)
 }
 
-fn update_user_identity(
-    response_opts: &ResponseOptions,
-    mut jar: SignedCookieJar,
-    identity: &impl Identity,
-) -> Result<(), ServerFnError> {
-    let refresh_max_age = REFRESH_MAX_AGE;
-    let refresh_token = RefreshToken {
-        principal: identity.sender().unwrap(),
-        expiry_epoch_ms: (current_epoch() + refresh_max_age).as_millis(),
-    };
-    let refresh_token_enc = serde_json::to_string(&refresh_token)?;
-
-    let refresh_cookie = Cookie::build((REFRESH_TOKEN_COOKIE, refresh_token_enc))
-        .http_only(true)
+fn with_refresh_cookie_attrs(cookie: CookieBuilder<'static>) -> CookieBuilder<'static> {
+    let cookie = cookie.http_only(true).path("/");
+    // Local builds and developer mode are served over plain http
+    // where `SameSite=None` and partitioned cookies are rejected
+    if cfg!(feature = "local-bin") || DevMode::get().is_some() {
+        return cookie.same_site(SameSite::Lax);
+    }
+    cookie
         .secure(true)
-        .path("/")
         .same_site(SameSite::None)
         .partitioned(true)
-        .max_age(refresh_max_age.try_into().unwrap());
+}
+
+fn set_refresh_cookie(
+    response_opts: &ResponseOptions,
+    jar: SignedCookieJar,
+    token: &RefreshToken,
+) -> Result<(), ServerFnError> {
+    let refresh_max_age = AuthConfig::get().refresh_max_age;
+    let refresh_token_enc = serde_json::to_string(token)?;
+    let refresh_cookie =
+        with_refresh_cookie_attrs(Cookie::build((REFRESH_TOKEN_COOKIE, refresh_token_enc)))
+            .max_age(refresh_max_age.try_into().unwrap());
 
-    jar = jar.add(refresh_cookie);
-    set_cookies(response_opts, jar);
+    set_cookies(response_opts, jar.add(refresh_cookie));
     Ok(())
 }
 
-pub fn update_user_identity_and_delegate(
+fn issue_token(principal: Principal, session: u64, rotation: u64) -> RefreshToken {
+    RefreshToken {
+        principal,
+        expiry_epoch_ms: (current_epoch() + AuthConfig::get().refresh_max_age).as_millis(),
+        rotation,
+        session: Some(session),
+    }
+}
+
+/// Issue a new refresh token for `identity`
+/// previously issued tokens of the same session are invalidated
+/// the session of the current token is continued if it belongs to `identity`
+async fn update_user_identity(
+    response_opts: &ResponseOptions,
+    jar: SignedCookieJar,
+    kv: &KVStoreImpl,
+    identity: &impl Identity,
+) -> Result<(), ServerFnError> {
+    let principal = identity.sender().unwrap();
+    let prev_session = extract_token_from_cookie(&jar)?
+        .filter(|t| t.principal == principal)
+        .and_then(|t| t.session);
+    let continued = match prev_session {
+        Some(id) => rotate_session(kv, principal, id)
+            .await?
+            .map(|rotation| (id, rotation)),
+        None => None,
+    };
+    let (session, rotation) = match continued {
+        Some(continued) => continued,
+        None => start_session(kv, principal).await?,
+    };
+
+    let token = issue_token(principal, session, rotation);
+    set_refresh_cookie(response_opts, jar, &token)
+}
+
+/// Issue a new refresh token and delegation for `identity`, e.g on login
+pub async fn update_user_identity_and_delegate(
     response_opts: &ResponseOptions,
     jar: SignedCookieJar,
+    kv: &KVStoreImpl,
     identity: impl Identity,
 ) -> Result<DelegatedIdentityWire, ServerFnError> {
-    update_user_identity(response_opts, jar, &identity)?;
+    update_user_identity(response_opts, jar, kv, &identity).await?;
+    record_audit_event(AuditEventKind::DelegationMinted, identity.sender().unwrap()).await;
     Ok(DelegatedIdentityWire::delegate(&identity))
 }
 
+/// Delegate the identity of the refresh token, the token is left as is
+///
+/// Safe to call while rendering on the server, where the response headers
+/// (and any new refresh token) may already be sent
 pub async fn extract_identity_impl() -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
     let key: Key = expect_context();
     let jar: SignedCookieJar = extract_with_state(&key).await?;
     let kv: KVStoreImpl = expect_context();
 
-    let base_identity = if let Some(identity) = try_extract_identity(&jar, &kv).await? {
-        Secp256k1Identity::from_private_key(identity)
-    } else {
+    let Some(identity) = try_extract_identity(&jar, &kv).await? else {
         return Ok(None);
     };
+    let base_identity = Secp256k1Identity::from_private_key(identity);
+    Ok(Some(DelegatedIdentityWire::delegate(&base_identity)))
+}
+
+/// Rotate the refresh token and delegate its identity
+/// the previous token is only accepted for [ROTATION_GRACE_PERIOD]
+///
+/// MUST only be called by the client, never while rendering on the server
+pub async fn refresh_identity_impl() -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let kv: KVStoreImpl = expect_context();
 
+    let Some(token) = current_token(&jar, &kv).await? else {
+        return Ok(None);
+    };
+    let Some(identity) = fetch_identity_from_kv(&kv, token.principal).await? else {
+        return Ok(None);
+    };
+    let Some(token) = rotate_token(&kv, &token).await? else {
+        log::warn!("session of {} was revoked during rotation", token.principal);
+        return Ok(None);
+    };
+
+    let resp: ResponseOptions = expect_context();
+    set_refresh_cookie(&resp, jar, &token)?;
+    record_audit_event(AuditEventKind::DelegationMinted, token.principal).await;
+    let base_identity = Secp256k1Identity::from_private_key(identity);
     Ok(Some(DelegatedIdentityWire::delegate(&base_identity)))
 }
 
+/// Successor of `token`, `None` if its session was revoked
+/// tokens issued before sessions are moved to a new session
+async fn rotate_token(
+    kv: &KVStoreImpl,
+    token: &RefreshToken,
+) -> Result<Option<RefreshToken>, ServerFnError> {
+    let principal = token.principal;
+    let (session, rotation) = if let Some(id) = token.session {
+        let Some(rotation) = rotate_session(kv, principal, id).await? else {
+            return Ok(None);
+        };
+        (id, rotation)
+    } else {
+        rotate(kv, principal).await?;
+        start_session(kv, principal).await?
+    };
+    Ok(Some(issue_token(principal, session, rotation)))
+}
+
 pub async fn logout_identity_impl() -> Result<DelegatedIdentityWire, ServerFnError> {
     let key: Key = expect_context();
     let kv: KVStoreImpl = expect_context();
     let jar: SignedCookieJar = extract_with_state(&key).await?;
+    if let Some(principal) = extract_principal_from_cookie(&jar)? {
+        record_audit_event(AuditEventKind::LoggedOut, principal).await;
+    } else {
+        limit_identity_generation()?;
+    }
     let base_identity = generate_and_save_identity(&kv).await?;
 
     let resp: ResponseOptions = expect_context();
-    let delegated = update_user_identity_and_delegate(&resp, jar, base_identity)?;
+    let delegated = update_user_identity_and_delegate(&resp, jar, &kv, base_identity).await?;
     Ok(delegated)
 }
 
+pub async fn logout_impl() -> Result<(), ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let Some(cookie) = jar.get(REFRESH_TOKEN_COOKIE) else {
+        return Ok(());
+    };
+    // Expired tokens still identify the session to tear down
+    let token: RefreshToken = serde_json::from_str(cookie.value())?;
+    let principal = token.principal;
+
+    // Only copies of the token are invalidated, identities are never deleted
+    // as they may be restored by a login (e.g google) that was linked to them
+    let kv: KVStoreImpl = expect_context();
+    if let Some(id) = token.session {
+        remove_session(&kv, principal, id).await?;
+    } else {
+        rotate(&kv, principal).await?;
+    }
+    record_audit_event(AuditEventKind::LoggedOut, principal).await;
+
+    // The removal cookie must carry the same attributes to replace the original
+    let jar = jar.remove(with_refresh_cookie_attrs(Cookie::build(
+        REFRESH_TOKEN_COOKIE,
+    )));
+    let resp: ResponseOptions = expect_context();
+    set_cookies(&resp, jar);
+    Ok(())
+}
+
 pub async fn generate_anonymous_identity_if_required_impl(
 ) -> Result<Option<JwkEcKey>, ServerFnError> {
     let key: Key = expect_context();
@@ -174,10 +434,181 @@ pub async fn set_anonymous_identity_cookie_impl(
     let jar: SignedCookieJar = extract_with_state(&key).await?;
 
     let kv: KVStoreImpl = expect_context();
+    // Clients with a valid session aren't creating identities in a loop
+    let has_session = match extract_token_from_cookie(&jar)? {
+        Some(token) => is_token_current(&kv, &token).await?,
+        None => false,
+    };
+    if !has_session {
+        limit_identity_generation()?;
+    }
     let base_identity = save_identity(&kv, anonymous_identity).await?;
 
     let resp: ResponseOptions = expect_context();
-    update_user_identity(&resp, jar, &base_identity)?;
+    update_user_identity(&resp, jar, &kv, &base_identity).await?;
+
+    Ok(())
+}
+
+/// Principal of the caller's refresh token, if it's still accepted
+pub async fn caller_principal() -> Result<Option<Principal>, ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let kv: KVStoreImpl = expect_context();
+    let token = current_token(&jar, &kv).await?;
+    Ok(token.map(|t| t.principal))
+}
+
+pub async fn refresh_token_expiry_impl() -> Result<Option<u128>, ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let Some(cookie) = jar.get(REFRESH_TOKEN_COOKIE) else {
+        return Ok(None);
+    };
+    let token: RefreshToken = serde_json::from_str(cookie.value())?;
+    Ok(Some(token.expiry_epoch_ms))
+}
+
+pub async fn list_sessions_impl() -> Result<Vec<SessionInfo>, ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let kv: KVStoreImpl = expect_context();
+    let Some(token) = current_token(&jar, &kv).await? else {
+        return Ok(vec![]);
+    };
+
+    let mut sessions: Vec<_> = read_sessions(&kv, token.principal)
+        .await?
+        .iter()
+        .map(|s| s.info(token.session))
+        .collect();
+    sessions.sort_by(|a, b| b.last_active_ms.cmp(&a.last_active_ms));
+    Ok(sessions)
+}
 
+pub async fn revoke_session_impl(id: u64, confirm_current: bool) -> Result<(), ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let kv: KVStoreImpl = expect_context();
+    let Some(token) = current_token(&jar, &kv).await? else {
+        return Err(ServerFnError::new("Not signed in"));
+    };
+
+    if token.session == Some(id) {
+        if !confirm_current {
+            return Err(ServerFnError::new(
+                "Signing out of the current session must be confirmed",
+            ));
+        }
+        return logout_impl().await;
+    }
+
+    remove_session(&kv, token.principal, id).await?;
+    record_audit_event(AuditEventKind::SessionRevoked, token.principal).await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::server_impl::store::redb_kv::ReDBKV;
+
+    fn kv() -> KVStoreImpl {
+        KVStoreImpl::ReDB(ReDBKV::in_memory().unwrap())
+    }
+
+    fn principal() -> Principal {
+        Principal::self_authenticating([7u8; 32])
+    }
+
+    fn token(session: Option<u64>, rotation: u64) -> RefreshToken {
+        RefreshToken {
+            principal: principal(),
+            expiry_epoch_ms: (current_epoch() + Duration::from_secs(60)).as_millis(),
+            rotation,
+            session,
+        }
+    }
+
+    #[tokio::test]
+    async fn previous_token_is_accepted_during_grace_period() {
+        let kv = kv();
+        let (id, first) = start_session(&kv, principal()).await.unwrap();
+        assert!(is_token_current(&kv, &token(Some(id), first))
+            .await
+            .unwrap());
+
+        let second = rotate_session(&kv, principal(), id).await.unwrap().unwrap();
+        assert_eq!(second, first + 1);
+        assert!(is_token_current(&kv, &token(Some(id), second))
+            .await
+            .unwrap());
+        assert!(is_token_current(&kv, &token(Some(id), first))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn stale_token_is_rejected() {
+        let kv = kv();
+        let (id, first) = start_session(&kv, principal()).await.unwrap();
+        rotate_session(&kv, principal(), id).await.unwrap();
+        rotate_session(&kv, principal(), id).await.unwrap();
+
+        assert!(!is_token_current(&kv, &token(Some(id), first))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn previous_token_is_rejected_after_grace_period() {
+        let kv = kv();
+        let rotated_at = current_epoch() - ROTATION_GRACE_PERIOD - Duration::from_secs(1);
+        let state = RotationState {
+            rotation: 2,
+            rotated_at_ms: rotated_at.as_millis(),
+        };
+        kv.write(
+            rotation_key(principal()),
+            serde_json::to_string(&state).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert!(is_token_current(&kv, &token(None, 2)).await.unwrap());
+        assert!(!is_token_current(&kv, &token(None, 1)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn revoked_session_token_is_rejected() {
+        let kv = kv();
+        let (id, rotation) = start_session(&kv, principal()).await.unwrap();
+        let (other, _) = start_session(&kv, principal()).await.unwrap();
+        remove_session(&kv, principal(), id).await.unwrap();
+
+        assert!(!is_token_current(&kv, &token(Some(id), rotation))
+            .await
+            .unwrap());
+        // reusing the token must not bring the session back
+        assert_eq!(rotate_session(&kv, principal(), id).await.unwrap(), None);
+        assert!(rotate_token(&kv, &token(Some(id), rotation))
+            .await
+            .unwrap()
+            .is_none());
+        let sessions = read_sessions(&kv, principal()).await.unwrap();
+        assert_eq!(sessions.iter().map(|s| s.id).collect::<Vec<_>>(), [other]);
+    }
+
+    #[tokio::test]
+    async fn legacy_token_is_moved_to_a_session() {
+        let kv = kv();
+        let legacy = token(None, 0);
+        let rotated = rotate_token(&kv, &legacy).await.unwrap().unwrap();
+
+        assert!(rotated.session.is_some());
+        assert!(is_token_current(&kv, &rotated).await.unwrap());
+        assert!(is_token_current(&kv, &legacy).await.unwrap());
+        rotate(&kv, principal()).await.unwrap();
+        assert!(!is_token_current(&kv, &legacy).await.unwrap());
+    }
+}
@@ -0,0 +1,158 @@
+//! Per IP limit on persisting new identities
+//! keeps abusive clients from flooding the KV store
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    num::NonZeroUsize,
+    sync::Mutex,
+};
+
+use http::request::Parts;
+use leptos::{use_context, ServerFnError};
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use web_time::Instant;
+
+use super::client_ip::client_ip;
+use crate::{
+    auth::rate_limited_err,
+    consts::auth::{IDENTITY_GEN_BURST, IDENTITY_GEN_REFILL_INTERVAL},
+};
+
+/// Least recently limited IPs are forgotten past this many tracked IPs
+const MAX_TRACKED_IPS: usize = 10_000;
+/// Token bucket, refilled by one token every [IDENTITY_GEN_REFILL_INTERVAL]
+/// up to [IDENTITY_GEN_BURST] tokens
+struct Bucket {
+    tokens: u32,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn full(now: Instant) -> Self {
+        Self {
+            tokens: IDENTITY_GEN_BURST,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        let refills = (elapsed.as_millis() / IDENTITY_GEN_REFILL_INTERVAL.as_millis()) as u32;
+        if refills == 0 {
+            return;
+        }
+        self.tokens = self.tokens.saturating_add(refills).min(IDENTITY_GEN_BURST);
+        self.refilled_at += IDENTITY_GEN_REFILL_INTERVAL * refills;
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens == 0 {
+            return false;
+        }
+        self.tokens -= 1;
+        true
+    }
+}
+
+static BUCKETS: Lazy<Mutex<LruCache<IpAddr, Bucket>>> = Lazy::new(|| {
+    Mutex::new(LruCache::new(
+        NonZeroUsize::new(MAX_TRACKED_IPS).expect("`MAX_TRACKED_IPS` must be non zero"),
+    ))
+});
+
+fn try_acquire(ip: IpAddr) -> bool {
+    let now = Instant::now();
+    let mut buckets = BUCKETS.lock().unwrap();
+    buckets
+        .get_or_insert_mut(bucket_key(ip), || Bucket::full(now))
+        .try_take(now)
+}
+
+/// Key of the bucket an IP is limited by
+/// IPv6 clients are usually assigned a whole /64, so they share a bucket
+fn bucket_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => ip,
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return IpAddr::V4(v4);
+            }
+            let prefix = u128::from(v6) & (u128::MAX << 64);
+            IpAddr::V6(Ipv6Addr::from(prefix))
+        }
+    }
+}
+
+/// Consume a token for persisting a new identity for the current client
+pub fn limit_identity_generation() -> Result<(), ServerFnError> {
+    let ip = use_context::<Parts>()
+        .as_ref()
+        .and_then(client_ip)
+        .ok_or_else(|| ServerFnError::new("Client address unavailable"))?;
+    if try_acquire(ip) {
+        return Ok(());
+    }
+    log::warn!("rate limited identity generation for {ip}");
+    Err(rate_limited_err())
+}
+
+#[cfg(test)]
+mod tests {
+    use web_time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_limits() {
+        let now = Instant::now();
+        let mut bucket = Bucket::full(now);
+        for _ in 0..IDENTITY_GEN_BURST {
+            assert!(bucket.try_take(now));
+        }
+        assert!(!bucket.try_take(now));
+    }
+
+    #[test]
+    fn bucket_refills_one_token_per_interval() {
+        let now = Instant::now();
+        let mut bucket = Bucket::full(now);
+        while bucket.try_take(now) {}
+
+        let almost = now + IDENTITY_GEN_REFILL_INTERVAL - Duration::from_millis(1);
+        assert!(!bucket.try_take(almost));
+
+        let later = now + IDENTITY_GEN_REFILL_INTERVAL;
+        assert!(bucket.try_take(later));
+        assert!(!bucket.try_take(later));
+    }
+
+    #[test]
+    fn bucket_refill_is_capped_at_burst() {
+        let now = Instant::now();
+        let mut bucket = Bucket::full(now);
+        assert!(bucket.try_take(now));
+
+        let much_later = now + IDENTITY_GEN_REFILL_INTERVAL * (IDENTITY_GEN_BURST * 4);
+        bucket.refill(much_later);
+        assert_eq!(bucket.tokens, IDENTITY_GEN_BURST);
+    }
+
+    #[test]
+    fn ipv6_clients_share_their_64() {
+        let a: IpAddr = "2001:db8:1:2:aaaa::1".parse().unwrap();
+        let b: IpAddr = "2001:db8:1:2:bbbb::2".parse().unwrap();
+        let other: IpAddr = "2001:db8:1:3::1".parse().unwrap();
+        assert_eq!(bucket_key(a), bucket_key(b));
+        assert_ne!(bucket_key(a), bucket_key(other));
+        assert_eq!(bucket_key(a), "2001:db8:1:2::".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn ipv4_clients_are_keyed_by_address() {
+        let v4: IpAddr = "203.0.113.7".parse().unwrap();
+        let mapped: IpAddr = "::ffff:203.0.113.7".parse().unwrap();
+        assert_eq!(bucket_key(v4), v4);
+        assert_eq!(bucket_key(mapped), v4);
+    }
+}
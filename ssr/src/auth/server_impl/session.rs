@@ -0,0 +1,194 @@
+//! Sessions of a principal, one per signed in device
+//! every refresh token belongs to a session, tokens are only accepted
+//! while the record of their session exists in the KV store
+//!
+//! Each session is stored under its own key and only ever updated atomically,
+//! so that a revoked session can't be written back by a concurrent rotation
+use std::collections::HashSet;
+
+use candid::Principal;
+use leptos::ServerFnError;
+use rand_chacha::rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{audit::record::request_meta, session::SessionInfo},
+    state::auth::config::AuthConfig,
+    utils::current_epoch,
+};
+
+use super::store::{update, KVError, KVStore, KVStoreImpl};
+
+/// Oldest sessions are dropped past this many sessions per principal
+const MAX_SESSIONS: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(super) struct SessionRecord {
+    pub id: u64,
+    pub created_at_ms: u128,
+    pub user_agent: Option<String>,
+    /// Generation of the latest refresh token issued for the session
+    pub rotation: u64,
+    pub rotated_at_ms: u128,
+}
+
+impl SessionRecord {
+    pub fn info(&self, current: Option<u64>) -> SessionInfo {
+        SessionInfo {
+            id: self.id,
+            created_at_ms: self.created_at_ms,
+            last_active_ms: self.rotated_at_ms,
+            user_agent: self.user_agent.clone(),
+            current: current == Some(self.id),
+        }
+    }
+
+    /// Whether the refresh token of the session expired
+    fn expired(&self) -> bool {
+        let expired_before = current_epoch()
+            .saturating_sub(AuthConfig::get().refresh_max_age)
+            .as_millis();
+        self.rotated_at_ms < expired_before
+    }
+}
+
+fn session_key(principal: Principal, id: u64) -> String {
+    format!("session-{principal}-{id}")
+}
+
+/// Ids of the sessions of a principal, oldest first
+/// may list sessions that were since revoked, the session records are authoritative
+fn session_ids_key(principal: Principal) -> String {
+    format!("session-ids-{principal}")
+}
+
+async fn read_session_ids(kv: &KVStoreImpl, principal: Principal) -> Result<Vec<u64>, KVError> {
+    let Some(raw) = kv.read(session_ids_key(principal)).await? else {
+        return Ok(vec![]);
+    };
+    Ok(serde_json::from_str(&raw)?)
+}
+
+/// Session `id` of `principal`, `None` if it was revoked or expired
+pub(super) async fn read_session(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    id: u64,
+) -> Result<Option<SessionRecord>, ServerFnError> {
+    let Some(raw) = kv.read(session_key(principal, id)).await? else {
+        return Ok(None);
+    };
+    let session: SessionRecord = serde_json::from_str(&raw)?;
+    Ok((!session.expired()).then_some(session))
+}
+
+/// Sessions of `principal`, oldest first
+pub(super) async fn read_sessions(
+    kv: &KVStoreImpl,
+    principal: Principal,
+) -> Result<Vec<SessionRecord>, ServerFnError> {
+    let mut sessions = vec![];
+    for id in read_session_ids(kv, principal).await? {
+        if let Some(session) = read_session(kv, principal, id).await? {
+            sessions.push(session);
+        }
+    }
+    Ok(sessions)
+}
+
+/// Start a new session for `principal`
+/// the oldest sessions are dropped past [MAX_SESSIONS]
+///
+/// returns the session id and its first generation
+pub(super) async fn start_session(
+    kv: &KVStoreImpl,
+    principal: Principal,
+) -> Result<(u64, u64), ServerFnError> {
+    let now_ms = current_epoch().as_millis();
+    let session = SessionRecord {
+        id: OsRng.next_u64(),
+        created_at_ms: now_ms,
+        user_agent: request_meta().user_agent,
+        rotation: 1,
+        rotated_at_ms: now_ms,
+    };
+    kv.write(
+        session_key(principal, session.id),
+        serde_json::to_string(&session)?,
+    )
+    .await?;
+
+    // Ids of sessions that were revoked or expired are dropped from the index
+    let mut dead = HashSet::new();
+    for id in read_session_ids(kv, principal).await? {
+        if read_session(kv, principal, id).await?.is_none() {
+            dead.insert(id);
+        }
+    }
+    let evicted = update(kv, session_ids_key(principal), |raw| {
+        let mut ids: Vec<u64> = raw
+            .map(serde_json::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        ids.retain(|id| !dead.contains(id));
+        ids.push(session.id);
+        let overflow = ids.len().saturating_sub(MAX_SESSIONS);
+        let evicted: Vec<_> = ids.drain(..overflow).collect();
+        Ok((Some(serde_json::to_string(&ids)?), evicted))
+    })
+    .await?;
+    for id in evicted {
+        kv.delete(session_key(principal, id)).await?;
+    }
+
+    Ok((session.id, session.rotation))
+}
+
+/// Start a new token generation for session `id`
+/// returns the new generation, `None` if the session was revoked or expired
+pub(super) async fn rotate_session(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    id: u64,
+) -> Result<Option<u64>, ServerFnError> {
+    let rotation = update(kv, session_key(principal, id), |raw| {
+        let Some(raw) = raw else {
+            return Ok((None, None));
+        };
+        let mut session: SessionRecord = serde_json::from_str(raw)?;
+        if session.expired() {
+            return Ok((Some(raw.to_string()), None));
+        }
+        session.rotation += 1;
+        session.rotated_at_ms = current_epoch().as_millis();
+        Ok((
+            Some(serde_json::to_string(&session)?),
+            Some(session.rotation),
+        ))
+    })
+    .await?;
+    Ok(rotation)
+}
+
+/// Revoke session `id` of `principal`, no-op if it doesn't exist
+pub(super) async fn remove_session(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    id: u64,
+) -> Result<(), ServerFnError> {
+    // Rotations only update existing records, deleting the record is enough to revoke it
+    kv.delete(session_key(principal, id)).await?;
+    update(kv, session_ids_key(principal), |raw| {
+        let Some(raw) = raw else {
+            return Ok((None, ()));
+        };
+        let mut ids: Vec<u64> = serde_json::from_str(raw)?;
+        ids.retain(|s| *s != id);
+        if ids.is_empty() {
+            return Ok((None, ()));
+        }
+        Ok((Some(serde_json::to_string(&ids)?), ()))
+    })
+    .await?;
+    Ok(())
+}
@@ -4,6 +4,7 @@ pub mod redis_kv;
 use enum_dispatch::enum_dispatch;
 use redis::RedisError;
 use thiserror::Error;
+use web_time::Duration;
 
 #[derive(Error, Debug)]
 pub enum KVError {
@@ -15,12 +16,70 @@ pub enum KVError {
     Redis(#[from] RedisError),
     #[error("{0}")]
     Bb8(#[from] bb8::RunError<RedisError>),
+    #[error("too many concurrent updates")]
+    Contended,
 }
 
 #[enum_dispatch]
 pub(crate) trait KVStore: Send {
     async fn read(&self, key: String) -> Result<Option<String>, KVError>;
     async fn write(&self, key: String, value: String) -> Result<(), KVError>;
+    /// Write `value` to `key`, the key is removed after `ttl`
+    /// keys written with a ttl must only be written with this method
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError>;
+    /// Write `value` to `key` with a ttl, unless the key already exists
+    /// returns whether the value was written
+    async fn insert_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<bool, KVError>;
+    /// Deleting a missing key is a no-op
+    async fn delete(&self, key: String) -> Result<(), KVError>;
+    /// Atomically replace the value of `key` with `new` if it's still `current`
+    /// `None` stands for a missing key, i.e `new = None` deletes the key
+    ///
+    /// returns whether the value was replaced
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: Option<String>,
+        new: Option<String>,
+    ) -> Result<bool, KVError>;
+    /// Atomically append `value` to the list at `key`
+    /// only the last `cap` values are kept
+    async fn append_capped(&self, key: String, value: String, cap: usize) -> Result<(), KVError>;
+    /// Values of the list at `key`, oldest first
+    async fn read_list(&self, key: String) -> Result<Vec<String>, KVError>;
+}
+
+/// Attempts of [update] before giving up on a contended key
+const MAX_UPDATE_ATTEMPTS: usize = 8;
+
+/// Atomically read-modify-write the value of `key`
+/// `f` receives the current value and returns the new value (`None` deletes the key)
+/// along with the result
+///
+/// `f` is called again if `key` was modified concurrently
+pub(crate) async fn update<R>(
+    kv: &KVStoreImpl,
+    key: String,
+    mut f: impl FnMut(Option<&str>) -> Result<(Option<String>, R), KVError>,
+) -> Result<R, KVError> {
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+        let current = kv.read(key.clone()).await?;
+        let (new, res) = f(current.as_deref())?;
+        if new == current || kv.compare_and_swap(key.clone(), current, new).await? {
+            return Ok(res);
+        }
+    }
+    Err(KVError::Contended)
 }
 
 #[derive(Clone)]
@@ -1,23 +1,52 @@
 use std::sync::Arc;
 
-use redb::{Database, TableDefinition};
+use redb::{Database, ReadableTable, TableDefinition};
 use tokio::task::spawn_blocking;
+use web_time::Duration;
+
+use crate::utils::current_epoch;
 
 use super::{KVError, KVStore};
 
 const TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv");
 const RAW_METADATA_TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv-meta");
+/// Lists, stored as JSON arrays
+const LIST_TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv-list");
+/// Expiry (in ms since the epoch) of keys written with a ttl
+const EXPIRY_TABLE: TableDefinition<&str, u64> = TableDefinition::new("kv-expiry");
+/// Keys written with a ttl, ordered by expiry
+const EXPIRY_INDEX_TABLE: TableDefinition<(u64, &str), ()> =
+    TableDefinition::new("kv-expiry-index");
+/// Expired keys removed by every write with a ttl
+const EXPIRED_SWEEP_BATCH: usize = 32;
+
+fn now_ms() -> u64 {
+    current_epoch().as_millis() as u64
+}
 
 #[derive(Clone)]
 pub struct ReDBKV(Arc<Database>);
 
 impl ReDBKV {
     pub fn new() -> Result<Self, redb::Error> {
-        let db = Database::create("./redb-kv.db")?;
+        Self::init(Database::create("./redb-kv.db")?)
+    }
+
+    /// Store that is dropped with the last clone
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self, redb::Error> {
+        let db = Database::builder().create_with_backend(redb::backends::InMemoryBackend::new())?;
+        Self::init(db)
+    }
+
+    fn init(db: Database) -> Result<Self, redb::Error> {
         let write_txn = db.begin_write()?;
         {
             write_txn.open_table(TABLE)?;
             write_txn.open_table(RAW_METADATA_TABLE)?;
+            write_txn.open_table(LIST_TABLE)?;
+            write_txn.open_table(EXPIRY_TABLE)?;
+            write_txn.open_table(EXPIRY_INDEX_TABLE)?;
         }
         write_txn.commit()?;
         Ok(Self(Arc::new(db)))
@@ -31,12 +60,78 @@ impl ReDBKV {
         let db = self.0.clone();
         spawn_blocking(move || f(&db).map_err(|e| e.into()))
     }
+
+    /// Write `value` to `key` with a ttl, existing keys are only replaced if `overwrite` is set
+    /// returns whether the value was written
+    async fn put_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+        overwrite: bool,
+    ) -> Result<bool, KVError> {
+        self.spawn_blocking(move |db| {
+            let now_ms = now_ms();
+            let expires_at_ms = now_ms + ttl.as_millis() as u64;
+            let write_txn = db.begin_write()?;
+            let written = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let mut index = write_txn.open_table(EXPIRY_INDEX_TABLE)?;
+
+                // nothing else removes expired keys, sweep a few of them on every write
+                let expired = index
+                    .range(..(now_ms, ""))?
+                    .take(EXPIRED_SWEEP_BATCH)
+                    .map(|entry| {
+                        let (k, _) = entry?;
+                        let (expires_at_ms, key) = k.value();
+                        Ok((expires_at_ms, key.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, redb::StorageError>>()?;
+                for (expired_at_ms, expired) in expired {
+                    index.remove((expired_at_ms, expired.as_str()))?;
+                    if expiry.get(expired.as_str())?.map(|ag| ag.value()) == Some(expired_at_ms) {
+                        expiry.remove(expired.as_str())?;
+                        table.remove(expired.as_str())?;
+                    }
+                }
+
+                let live = match expiry.get(key.as_str())?.map(|ag| ag.value()) {
+                    Some(expires_at_ms) => expires_at_ms > now_ms,
+                    None => table.get(key.as_str())?.is_some(),
+                };
+                if live && !overwrite {
+                    false
+                } else {
+                    if let Some(prev) = expiry.insert(key.as_str(), expires_at_ms)? {
+                        index.remove((prev.value(), key.as_str()))?;
+                    }
+                    index.insert((expires_at_ms, key.as_str()), ())?;
+                    table.insert(key.as_str(), value.as_str())?;
+                    true
+                }
+            };
+            write_txn.commit()?;
+            Ok::<_, redb::Error>(written)
+        })
+        .await
+        .unwrap()
+    }
 }
 
 impl KVStore for ReDBKV {
     async fn read(&self, key: String) -> Result<Option<String>, KVError> {
         self.spawn_blocking(move |db| {
             let read_txn = db.begin_read()?;
+            let expired = {
+                let expiry = read_txn.open_table(EXPIRY_TABLE)?;
+                let v = expiry.get(key.as_str())?;
+                v.is_some_and(|ag| ag.value() <= now_ms())
+            };
+            if expired {
+                return Ok(None);
+            }
             let value = {
                 let table = read_txn.open_table(TABLE)?;
                 let v = table.get(key.as_str())?;
@@ -61,4 +156,113 @@ impl KVStore for ReDBKV {
         .await
         .unwrap()
     }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        self.put_with_ttl(key, value, ttl, true).await?;
+        Ok(())
+    }
+
+    async fn insert_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<bool, KVError> {
+        self.put_with_ttl(key, value, ttl, false).await
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            {
+                let mut table = write_txn.open_table(TABLE)?;
+                table.remove(key.as_str())?;
+            }
+            write_txn.commit()?;
+            Ok::<_, redb::Error>(())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: Option<String>,
+        new: Option<String>,
+    ) -> Result<bool, KVError> {
+        // write transactions are exclusive, nothing can be written between the read and the write
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            let swapped = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let stored = table.get(key.as_str())?.map(|v| v.value().to_string());
+                if stored != current {
+                    false
+                } else {
+                    if let Some(new) = new {
+                        table.insert(key.as_str(), new.as_str())?;
+                    } else {
+                        table.remove(key.as_str())?;
+                    }
+                    true
+                }
+            };
+            write_txn.commit()?;
+            Ok::<_, redb::Error>(swapped)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn append_capped(&self, key: String, value: String, cap: usize) -> Result<(), KVError> {
+        // (de)serialization errors are returned as the inner result
+        let res = self
+            .spawn_blocking(move |db| {
+                let write_txn = db.begin_write()?;
+                {
+                    let mut table = write_txn.open_table(LIST_TABLE)?;
+                    let stored = table.get(key.as_str())?.map(|v| v.value().to_string());
+                    let mut list: Vec<String> = match stored.as_deref().map(serde_json::from_str) {
+                        Some(Ok(list)) => list,
+                        Some(Err(e)) => return Ok(Err(e)),
+                        None => vec![],
+                    };
+                    list.push(value);
+                    let overflow = list.len().saturating_sub(cap);
+                    list.drain(..overflow);
+                    let raw = match serde_json::to_string(&list) {
+                        Ok(raw) => raw,
+                        Err(e) => return Ok(Err(e)),
+                    };
+                    table.insert(key.as_str(), raw.as_str())?;
+                }
+                write_txn.commit()?;
+                Ok::<_, redb::Error>(Ok(()))
+            })
+            .await
+            .unwrap()?;
+        Ok(res?)
+    }
+
+    async fn read_list(&self, key: String) -> Result<Vec<String>, KVError> {
+        let raw = self
+            .spawn_blocking(move |db| {
+                let read_txn = db.begin_read()?;
+                let table = read_txn.open_table(LIST_TABLE)?;
+                let v = table.get(key.as_str())?;
+                Ok(v.map(|ag| ag.value().to_string()))
+            })
+            .await
+            .unwrap()?;
+        let Some(raw) = raw else {
+            return Ok(vec![]);
+        };
+        Ok(serde_json::from_str(&raw)?)
+    }
 }
@@ -1,5 +1,6 @@
 use bb8_redis::RedisConnectionManager;
 use redis::{AsyncCommands, RedisError};
+use web_time::Duration;
 
 use super::{KVError, KVStore};
 
@@ -15,6 +16,24 @@ impl RedisKV {
 
 const AUTH_FIELD: &str = "auth";
 
+/// Compare and swap of the [AUTH_FIELD] of `KEYS[1]`
+/// `ARGV[1]`: field, `ARGV[2]`: whether the field is expected to be set, `ARGV[3]`: expected value
+/// `ARGV[4]`: whether to set (or delete) the field, `ARGV[5]`: new value
+const CAS_SCRIPT: &str = r#"
+local cur = redis.call('HGET', KEYS[1], ARGV[1])
+if ARGV[2] == '1' then
+    if cur ~= ARGV[3] then return 0 end
+elseif cur then
+    return 0
+end
+if ARGV[4] == '1' then
+    redis.call('HSET', KEYS[1], ARGV[1], ARGV[5])
+else
+    redis.call('HDEL', KEYS[1], ARGV[1])
+end
+return 1
+"#;
+
 impl KVStore for RedisKV {
     async fn read(&self, key: String) -> Result<Option<String>, KVError> {
         let mut con = self.0.get().await?;
@@ -27,4 +46,86 @@ impl KVStore for RedisKV {
         con.hset::<_, _, _, ()>(key, AUTH_FIELD, value).await?;
         Ok(())
     }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        redis::pipe()
+            .atomic()
+            .hset(&key, AUTH_FIELD, value)
+            .ignore()
+            .pexpire(&key, ttl.as_millis() as i64)
+            .ignore()
+            .query_async::<_, ()>(&mut *con)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        let (inserted,): (bool,) = redis::pipe()
+            .atomic()
+            .hset_nx(&key, AUTH_FIELD, value)
+            // the ttl of existing keys is extended as well, at worst they're kept longer
+            .pexpire(&key, ttl.as_millis() as i64)
+            .ignore()
+            .query_async(&mut *con)
+            .await?;
+        Ok(inserted)
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        con.hdel::<_, _, ()>(key, AUTH_FIELD).await?;
+        Ok(())
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: Option<String>,
+        new: Option<String>,
+    ) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        let swapped: i32 = redis::cmd("EVAL")
+            .arg(CAS_SCRIPT)
+            .arg(1)
+            .arg(key)
+            .arg(AUTH_FIELD)
+            .arg(current.is_some() as u8)
+            .arg(current.unwrap_or_default())
+            .arg(new.is_some() as u8)
+            .arg(new.unwrap_or_default())
+            .query_async(&mut *con)
+            .await?;
+        Ok(swapped == 1)
+    }
+
+    async fn append_capped(&self, key: String, value: String, cap: usize) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        redis::pipe()
+            .atomic()
+            .rpush(&key, value)
+            .ignore()
+            .ltrim(&key, -(cap as isize), -1)
+            .ignore()
+            .query_async::<_, ()>(&mut *con)
+            .await?;
+        Ok(())
+    }
+
+    async fn read_list(&self, key: String) -> Result<Vec<String>, KVError> {
+        let mut con = self.0.get().await?;
+        let values: Vec<String> = con.lrange(key, 0, -1).await?;
+        Ok(values)
+    }
 }
@@ -0,0 +1,68 @@
+//! Devices signed in to the user's account
+//! sessions can be revoked remotely, their refresh token is rejected on its next use
+use leptos::{server, ServerFnError};
+use serde::{Deserialize, Serialize};
+
+/// A signed in device
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub id: u64,
+    /// Epoch (in milliseconds) at which the device signed in
+    pub created_at_ms: u128,
+    /// Epoch (in milliseconds) at which the session was last refreshed
+    pub last_active_ms: u128,
+    /// User agent of the request that started the session
+    pub user_agent: Option<String>,
+    /// Whether this is the session of the caller
+    pub current: bool,
+}
+
+impl SessionInfo {
+    /// Short, human readable description of the device (e.g "Chrome on Android")
+    pub fn device(&self) -> String {
+        let Some(ua) = self.user_agent.as_deref() else {
+            return "Unknown device".into();
+        };
+        let browser = [
+            ("Edg/", "Edge"),
+            ("OPR/", "Opera"),
+            ("Firefox/", "Firefox"),
+            ("Chrome/", "Chrome"),
+            ("Safari/", "Safari"),
+        ]
+        .into_iter()
+        .find_map(|(token, name)| ua.contains(token).then_some(name))
+        .unwrap_or("Browser");
+        let os = [
+            ("Android", "Android"),
+            ("iPhone", "iOS"),
+            ("iPad", "iPadOS"),
+            ("Windows", "Windows"),
+            ("Mac OS", "macOS"),
+            ("CrOS", "ChromeOS"),
+            ("Linux", "Linux"),
+        ]
+        .into_iter()
+        .find_map(|(token, name)| ua.contains(token).then_some(name));
+
+        match os {
+            Some(os) => format!("{browser} on {os}"),
+            None => browser.into(),
+        }
+    }
+}
+
+/// Sessions of the caller's principal, most recently active first
+/// empty if the caller isn't signed in
+#[server]
+pub async fn list_sessions() -> Result<Vec<SessionInfo>, ServerFnError> {
+    super::server_impl::list_sessions_impl().await
+}
+
+/// Revoke session `id` of the caller's principal
+/// revoking the caller's own session signs it out, and must be confirmed
+/// with `confirm_current`
+#[server]
+pub async fn revoke_session(id: u64, confirm_current: bool) -> Result<(), ServerFnError> {
+    super::server_impl::revoke_session_impl(id, confirm_current).await
+}
@@ -0,0 +1,278 @@
+//! Proof of ownership for server function calls
+//! Server functions can be called by anyone, sensitive actions (rewards, claims)
+//! must be signed by the delegated identity of the caller
+//!
+//! Signatures are bound to the server function they were made for
+//! and can only be used once
+use candid::Principal;
+use ic_agent::{identity::SignedDelegation, Identity};
+use leptos::ServerFnError;
+use rand_chacha::rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::utils::current_epoch;
+
+/// Domain separator for server function payloads
+/// prevents signatures from being reused as IC requests
+const SERVER_FN_DOMAIN: &[u8] = b"\x13yral-server-fn-auth";
+
+/// Signatures older than this are rejected
+const MAX_SIGNATURE_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// Allowed clock drift between the client and the server
+const MAX_CLOCK_DRIFT: Duration = Duration::from_secs(30);
+
+/// Signature over a server function payload
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RequestSignature {
+    /// DER encoded public key of the principal
+    #[serde(with = "serde_bytes")]
+    public_key: Vec<u8>,
+    /// Proof of delegation
+    /// connecting `public_key` to the key that signed the payload
+    delegations: Vec<SignedDelegation>,
+    #[serde(with = "serde_bytes")]
+    signature: Vec<u8>,
+    /// Epoch (in milliseconds) at which the payload was signed
+    signed_at_ms: u64,
+    /// Random value, the server rejects nonces it has already seen
+    nonce: u64,
+}
+
+fn signable_payload(
+    endpoint: &str,
+    nonce: u64,
+    signed_at_ms: u64,
+    payload: &impl Serialize,
+) -> Result<Vec<u8>, ServerFnError> {
+    let mut buf = SERVER_FN_DOMAIN.to_vec();
+    buf.extend_from_slice(&(endpoint.len() as u32).to_be_bytes());
+    buf.extend_from_slice(endpoint.as_bytes());
+    buf.extend_from_slice(&nonce.to_be_bytes());
+    buf.extend_from_slice(&signed_at_ms.to_be_bytes());
+    serde_json::to_writer(&mut buf, payload)?;
+    Ok(buf)
+}
+
+impl RequestSignature {
+    /// Sign `payload` of a call to the server function `endpoint` with the given identity
+    pub fn sign(
+        identity: &impl Identity,
+        endpoint: &str,
+        payload: &impl Serialize,
+    ) -> Result<Self, ServerFnError> {
+        let signed_at_ms = current_epoch().as_millis() as u64;
+        let nonce = OsRng.next_u64();
+        let signable = signable_payload(endpoint, nonce, signed_at_ms, payload)?;
+        let sig = identity
+            .sign_arbitrary(&signable)
+            .map_err(ServerFnError::new)?;
+
+        Ok(Self {
+            public_key: sig
+                .public_key
+                .ok_or_else(|| ServerFnError::new("Anonymous identity can't sign requests"))?,
+            delegations: sig.delegations.unwrap_or_default(),
+            signature: sig
+                .signature
+                .ok_or_else(|| ServerFnError::new("Identity did not produce a signature"))?,
+            signed_at_ms,
+            nonce,
+        })
+    }
+}
+
+/// Verify a signature by a DER encoded secp256k1 public key
+pub(crate) fn verify_secp256k1(
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), ServerFnError> {
+    use k256::{
+        ecdsa::{signature::Verifier, Signature, VerifyingKey},
+        pkcs8::DecodePublicKey,
+    };
+
+    let key = VerifyingKey::from_public_key_der(public_key)
+        .map_err(|_| ServerFnError::new("Invalid public key"))?;
+    let sig =
+        Signature::try_from(signature).map_err(|_| ServerFnError::new("Malformed signature"))?;
+    key.verify(message, &sig)
+        .map_err(|_| ServerFnError::new("Signature mismatch"))
+}
+
+#[cfg(feature = "ssr")]
+mod verify {
+    use crate::auth::server_impl::store::{KVStore, KVStoreImpl};
+
+    use super::*;
+
+    impl RequestSignature {
+        /// Verify the signature over `payload` of a call to `endpoint` at `now`
+        /// the nonce is not checked
+        fn verify_at(
+            &self,
+            endpoint: &str,
+            payload: &impl Serialize,
+            now: Duration,
+        ) -> Result<Principal, ServerFnError> {
+            let signed_at = Duration::from_millis(self.signed_at_ms);
+            if signed_at > now + MAX_CLOCK_DRIFT
+                || now.saturating_sub(signed_at) > MAX_SIGNATURE_AGE
+            {
+                return Err(ServerFnError::new("Signature expired"));
+            }
+
+            let now_ns = now.as_nanos() as u64;
+            let mut signer_key = self.public_key.as_slice();
+            for signed in &self.delegations {
+                if signed.delegation.expiration < now_ns {
+                    return Err(ServerFnError::new("Delegation expired"));
+                }
+                verify_secp256k1(signer_key, &signed.delegation.signable(), &signed.signature)?;
+                signer_key = &signed.delegation.pubkey;
+            }
+
+            let signable = signable_payload(endpoint, self.nonce, self.signed_at_ms, payload)?;
+            verify_secp256k1(signer_key, &signable, &self.signature)?;
+
+            Ok(Principal::self_authenticating(&self.public_key))
+        }
+
+        /// Record the nonce of the signature by `signer`, fails if it was already used
+        async fn consume_nonce(
+            &self,
+            kv: &KVStoreImpl,
+            signer: Principal,
+        ) -> Result<(), ServerFnError> {
+            // Expired signatures are rejected anyway, the nonce can be forgotten by then
+            let ttl = MAX_SIGNATURE_AGE + MAX_CLOCK_DRIFT;
+            let key = format!("signature-nonce-{signer}-{}", self.nonce);
+            if !kv.insert_with_ttl(key, String::new(), ttl).await? {
+                return Err(ServerFnError::new("Signature was already used"));
+            }
+            Ok(())
+        }
+
+        /// Verify the signature over `payload` of a call to the server function `endpoint`
+        /// returns the principal of the signer
+        pub async fn verify(
+            &self,
+            endpoint: &str,
+            payload: &impl Serialize,
+        ) -> Result<Principal, ServerFnError> {
+            let kv: KVStoreImpl = leptos::expect_context();
+            let signer = self.verify_at(endpoint, payload, current_epoch())?;
+            self.consume_nonce(&kv, signer).await?;
+            Ok(signer)
+        }
+
+        /// Verify the signature over `payload` of a call to the server function `endpoint`
+        /// and ensure it was signed by `principal`
+        pub async fn verify_for(
+            &self,
+            principal: Principal,
+            endpoint: &str,
+            payload: &impl Serialize,
+        ) -> Result<(), ServerFnError> {
+            if self.verify(endpoint, payload).await? != principal {
+                return Err(ServerFnError::new(
+                    "Signer does not match the claimed principal",
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ic_agent::identity::{DelegatedIdentity, Secp256k1Identity};
+
+        use crate::auth::{server_impl::store::redb_kv::ReDBKV, DelegatedIdentityWire};
+
+        use super::*;
+
+        const ENDPOINT: &str = "claim_reward";
+
+        fn identity() -> Secp256k1Identity {
+            Secp256k1Identity::from_private_key(k256::SecretKey::random(&mut OsRng))
+        }
+
+        fn delegated(base: &Secp256k1Identity) -> DelegatedIdentity {
+            DelegatedIdentityWire::delegate(base).try_into().unwrap()
+        }
+
+        #[test]
+        fn signature_is_verified() {
+            let base = identity();
+            let principal = base.sender().unwrap();
+            let sig = RequestSignature::sign(&delegated(&base), ENDPOINT, &principal).unwrap();
+            let signer = sig
+                .verify_at(ENDPOINT, &principal, current_epoch())
+                .unwrap();
+            assert_eq!(signer, principal);
+        }
+
+        #[test]
+        fn tampered_payload_is_rejected() {
+            let base = identity();
+            let principal = base.sender().unwrap();
+            let sig = RequestSignature::sign(&base, ENDPOINT, &principal).unwrap();
+            let other = identity().sender().unwrap();
+            assert!(sig.verify_at(ENDPOINT, &other, current_epoch()).is_err());
+        }
+
+        #[test]
+        fn signature_for_another_endpoint_is_rejected() {
+            let base = identity();
+            let principal = base.sender().unwrap();
+            let sig = RequestSignature::sign(&base, "mark_user_registered", &principal).unwrap();
+            assert!(sig
+                .verify_at(ENDPOINT, &principal, current_epoch())
+                .is_err());
+        }
+
+        #[test]
+        fn tampered_signature_fields_are_rejected() {
+            let base = identity();
+            let principal = base.sender().unwrap();
+            let sig = RequestSignature::sign(&base, ENDPOINT, &principal).unwrap();
+            let now = current_epoch();
+
+            let mut replayed = sig.clone();
+            replayed.nonce += 1;
+            assert!(replayed.verify_at(ENDPOINT, &principal, now).is_err());
+
+            let mut backdated = sig.clone();
+            backdated.signed_at_ms -= 1;
+            assert!(backdated.verify_at(ENDPOINT, &principal, now).is_err());
+
+            let mut impersonated = sig;
+            impersonated.public_key = identity().public_key().unwrap();
+            assert!(impersonated.verify_at(ENDPOINT, &principal, now).is_err());
+        }
+
+        #[test]
+        fn expired_signature_is_rejected() {
+            let base = identity();
+            let principal = base.sender().unwrap();
+            let sig = RequestSignature::sign(&base, ENDPOINT, &principal).unwrap();
+            let now = current_epoch();
+            let later = now + MAX_SIGNATURE_AGE + Duration::from_secs(1);
+            assert!(sig.verify_at(ENDPOINT, &principal, later).is_err());
+            let earlier = now.saturating_sub(MAX_CLOCK_DRIFT + Duration::from_secs(1));
+            assert!(sig.verify_at(ENDPOINT, &principal, earlier).is_err());
+        }
+
+        #[tokio::test]
+        async fn nonce_is_only_accepted_once() {
+            let kv = KVStoreImpl::ReDB(ReDBKV::in_memory().unwrap());
+            let base = identity();
+            let principal = base.sender().unwrap();
+            let sig = RequestSignature::sign(&base, ENDPOINT, &principal).unwrap();
+            sig.consume_nonce(&kv, principal).await.unwrap();
+            assert!(sig.consume_nonce(&kv, principal).await.is_err());
+        }
+    }
+}
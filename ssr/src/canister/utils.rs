@@ -1,15 +1,118 @@
-use std::fmt::Display;
+use thiserror::Error;
 
-use crate::consts::CF_STREAM_BASE;
+use crate::state::cloudflare::{CloudflareConfig, StreamVariant};
 
-pub fn bg_url(uid: impl Display) -> String {
-    format!("{CF_STREAM_BASE}/{uid}/thumbnails/thumbnail.jpg")
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid cloudflare stream uid `{0}`")]
+pub struct InvalidStreamUid(pub String);
+
+/// Validate and normalize a Cloudflare stream UID
+/// UIDs are 32 character hex strings
+pub fn normalize_stream_uid(uid: &str) -> Result<String, InvalidStreamUid> {
+    let uid = uid.trim().to_ascii_lowercase();
+    if uid.len() != 32 || !uid.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(InvalidStreamUid(uid));
+    }
+    Ok(uid)
+}
+
+pub fn bg_url(uid: impl AsRef<str>) -> Result<String, InvalidStreamUid> {
+    let uid = normalize_stream_uid(uid.as_ref())?;
+    let config = CloudflareConfig::get();
+    let base = &config.stream_base;
+    Ok(format!("{base}/{uid}/thumbnails/thumbnail.jpg"))
+}
+
+pub fn stream_url(
+    uid: impl AsRef<str>,
+    variant: StreamVariant,
+) -> Result<String, InvalidStreamUid> {
+    let uid = normalize_stream_uid(uid.as_ref())?;
+    let config = CloudflareConfig::get();
+    let base = config.stream_base_for(variant);
+    Ok(format!("{base}/{uid}/manifest/video.m3u8"))
+}
+
+pub fn mp4_url(uid: impl AsRef<str>, variant: StreamVariant) -> Result<String, InvalidStreamUid> {
+    let uid = normalize_stream_uid(uid.as_ref())?;
+    let config = CloudflareConfig::get();
+    let base = config.stream_base_for(variant);
+    Ok(format!("{base}/{uid}/downloads/default.mp4"))
 }
 
-pub fn stream_url(uid: impl Display) -> String {
-    format!("{CF_STREAM_BASE}/{uid}/manifest/video.m3u8")
+/// Thumbnail of the video at `time` (in seconds), scaled to `height` pixels
+pub fn thumbnail_at_url(
+    uid: impl AsRef<str>,
+    time: f64,
+    height: u32,
+) -> Result<String, InvalidStreamUid> {
+    let uid = normalize_stream_uid(uid.as_ref())?;
+    let config = CloudflareConfig::get();
+    let base = &config.stream_base;
+    Ok(format!(
+        "{base}/{uid}/thumbnails/thumbnail.jpg?time={time:.1}s&height={height}"
+    ))
 }
 
-pub fn mp4_url(uid: impl Display) -> String {
-    format!("{CF_STREAM_BASE}/{uid}/downloads/default.mp4")
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::cloudflare::CloudflareSettings;
+
+    const UID: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn valid_uid_is_normalized() {
+        assert_eq!(normalize_stream_uid(UID).unwrap(), UID);
+        assert_eq!(
+            normalize_stream_uid(&format!("  {}\n", UID.to_uppercase())).unwrap(),
+            UID
+        );
+    }
+
+    #[test]
+    fn invalid_uids_are_rejected() {
+        let too_long = format!("{UID}0");
+        for uid in [
+            "",
+            &UID[1..],
+            too_long.as_str(),
+            "0123456789abcdef0123456789abcdeg",
+            "../../0123456789abcdef0123456789",
+            "0123456789abcdef 123456789abcdef",
+        ] {
+            assert!(normalize_stream_uid(uid).is_err(), "accepted `{uid}`");
+        }
+    }
+
+    #[test]
+    fn urls_are_built_from_the_normalized_uid() {
+        let url = bg_url(UID.to_uppercase()).unwrap();
+        assert!(url.ends_with(&format!("/{UID}/thumbnails/thumbnail.jpg")));
+        assert!(mp4_url("not a uid", StreamVariant::Watermarked).is_err());
+    }
+
+    #[test]
+    fn clean_variant_falls_back_to_watermarked() {
+        let mut settings = CloudflareSettings::default();
+        assert_eq!(
+            settings.in_app_variant,
+            StreamVariant::Watermarked,
+            "existing embeds must stay watermarked"
+        );
+        assert_eq!(
+            settings.stream_base_for(StreamVariant::Clean),
+            settings.stream_base
+        );
+
+        settings.clean_stream_base = Some("https://clean.example".into());
+        assert_eq!(
+            settings.stream_base_for(StreamVariant::Clean),
+            "https://clean.example"
+        );
+        assert_eq!(
+            settings.stream_base_for(StreamVariant::Watermarked),
+            settings.stream_base
+        );
+    }
 }
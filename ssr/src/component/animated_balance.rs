@@ -0,0 +1,113 @@
+use gloo::timers::callback::Timeout;
+use leptos::*;
+use leptos_use::use_media_query;
+use web_time::{Duration, Instant};
+
+use crate::utils::token::Balance;
+
+/// Duration (in milliseconds) of the count animation
+const COUNT_ANIMATION_MS: f64 = 400.0;
+/// Interval between animation frames
+const COUNT_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// Duration (in milliseconds) of the direction flash
+const FLASH_MS: u32 = 600;
+
+#[derive(Clone, Copy)]
+struct CountAnimation {
+    from: u64,
+    to: u64,
+    started: Instant,
+}
+
+impl CountAnimation {
+    /// Value at `now`, `None` once the animation is complete
+    fn value_at(&self, now: Instant) -> Option<u64> {
+        let progress = (now - self.started).as_secs_f64() * 1000.0 / COUNT_ANIMATION_MS;
+        if progress >= 1.0 {
+            return None;
+        }
+        // ease out cubic
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        let delta = (self.to as f64 - self.from as f64) * eased;
+        Some((self.from as f64 + delta).round() as u64)
+    }
+}
+
+/// Balance that counts up (or down) to new values
+/// flashing green (or red) to indicate the direction
+/// changes are instant if the user prefers reduced motion
+#[component]
+pub fn AnimatedBalance(
+    #[prop(into)] balance: Signal<u64>,
+    /// Previously shown value, animated from on mount
+    #[prop(optional)]
+    from: Option<u64>,
+    /// Token decimals used to format the (raw) balance
+    #[prop(optional)]
+    decimals: u8,
+    #[prop(into, optional)] class: String,
+) -> impl IntoView {
+    let reduced_motion = use_media_query("(prefers-reduced-motion: reduce)");
+    let displayed = create_rw_signal(from.unwrap_or_else(|| balance.get_untracked()));
+    let flash = create_rw_signal(None::<bool>);
+    let animation = store_value(None::<CountAnimation>);
+    let interval = store_value(None::<IntervalHandle>);
+
+    let stop = move || {
+        if let Some(handle) = interval.try_update_value(|i| i.take()).flatten() {
+            handle.clear();
+        }
+    };
+    on_cleanup(stop);
+
+    create_effect(move |_| {
+        let to = balance();
+        let from = displayed.get_untracked();
+        if from == to {
+            return;
+        }
+        flash.set(Some(to > from));
+        Timeout::new(FLASH_MS, move || _ = flash.try_set(None)).forget();
+
+        stop();
+        if reduced_motion.get_untracked() {
+            displayed.set(to);
+            return;
+        }
+        animation.set_value(Some(CountAnimation {
+            from,
+            to,
+            started: Instant::now(),
+        }));
+        let handle = set_interval_with_handle(
+            move || {
+                let Some(anim) = animation.try_get_value().flatten() else {
+                    return;
+                };
+                match anim.value_at(Instant::now()) {
+                    Some(value) => _ = displayed.try_set(value),
+                    None => {
+                        _ = displayed.try_set(anim.to);
+                        animation.set_value(None);
+                        stop();
+                    }
+                }
+            },
+            COUNT_FRAME_INTERVAL,
+        );
+        match handle {
+            Ok(handle) => interval.set_value(Some(handle)),
+            Err(_) => displayed.set(to),
+        }
+    });
+
+    view! {
+        <div
+            class=format!("transition-colors duration-300 {class}")
+            class=("text-green-500", move || flash() == Some(true))
+            class=("text-red-500", move || flash() == Some(false))
+        >
+            {move || Balance::new(displayed(), decimals).to_string()}
+        </div>
+    }
+}
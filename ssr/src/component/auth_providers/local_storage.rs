@@ -32,7 +32,7 @@ async fn perform_local_storage_auth(
     let base_identity = Secp256k1Identity::from_private_key(base_key);
 
     let resp: ResponseOptions = expect_context();
-    let delegated = update_user_identity_and_delegate(&resp, jar, base_identity)?;
+    let delegated = update_user_identity_and_delegate(&resp, jar, &kv, base_identity).await?;
     Ok((delegated, jwk))
 }
 
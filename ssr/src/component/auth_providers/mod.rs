@@ -10,10 +10,10 @@ use leptos::*;
 use leptos_use::storage::use_local_storage;
 
 use crate::{
-    auth::DelegatedIdentityWire,
+    auth::{signature::RequestSignature, DelegatedIdentityWire},
     consts::ACCOUNT_CONNECTED_STORE,
     state::{
-        auth::auth_state,
+        auth::{anonymous_browse, auth_state},
         canisters::{do_canister_auth, Canisters},
         local_storage::use_referrer_store,
     },
@@ -24,16 +24,41 @@ use crate::{
 };
 
 #[server]
-async fn issue_referral_rewards(referee_canister: Principal) -> Result<(), ServerFnError> {
+async fn issue_referral_rewards(
+    referee_canister: Principal,
+    signature: RequestSignature,
+) -> Result<(), ServerFnError> {
     use self::server_fn_impl::issue_referral_rewards_impl;
+    use crate::state::canisters::unauth_canisters;
+
+    let referee_principal = signature
+        .verify("issue_referral_rewards", &referee_canister)
+        .await?;
+    let canisters = unauth_canisters();
+    let user_canister = canisters
+        .get_individual_canister_by_user_principal(referee_principal)
+        .await?;
+    if user_canister != Some(referee_canister) {
+        return Err(ServerFnError::new(
+            "Signer does not own the referee canister",
+        ));
+    }
+
     issue_referral_rewards_impl(referee_canister).await
 }
 
 #[server]
-async fn mark_user_registered(user_principal: Principal) -> Result<bool, ServerFnError> {
+async fn mark_user_registered(
+    user_principal: Principal,
+    signature: RequestSignature,
+) -> Result<bool, ServerFnError> {
     use self::server_fn_impl::mark_user_registered_impl;
     use crate::state::canisters::unauth_canisters;
 
+    signature
+        .verify_for(user_principal, "mark_user_registered", &user_principal)
+        .await?;
+
     // TODO: verify that user principal is registered
     let canisters = unauth_canisters();
     let user_canister = canisters
@@ -48,11 +73,22 @@ async fn handle_user_login(
     referrer: Option<Principal>,
 ) -> Result<(), ServerFnError> {
     let user_principal = canisters.identity().sender().unwrap();
-    let first_time_login = mark_user_registered(user_principal).await?;
+    let signature = RequestSignature::sign(
+        canisters.identity(),
+        "mark_user_registered",
+        &user_principal,
+    )?;
+    let first_time_login = mark_user_registered(user_principal, signature).await?;
 
     match referrer {
         Some(_referee_principal) if first_time_login => {
-            issue_referral_rewards(canisters.user_canister()).await?;
+            let referee_canister = canisters.user_canister();
+            let signature = RequestSignature::sign(
+                canisters.identity(),
+                "issue_referral_rewards",
+                &referee_canister,
+            )?;
+            issue_referral_rewards(referee_canister, signature).await?;
             Ok(())
         }
         _ => Ok(()),
@@ -114,6 +150,9 @@ pub fn LoginProviders(show_modal: RwSignal<bool>, lock_closing: RwSignal<bool>)
     let (_, write_account_connected, _) =
         use_local_storage::<bool, FromToStringCodec>(ACCOUNT_CONNECTED_STORE);
     let auth = auth_state();
+    // Login providers require a persisted identity
+    let anon_browse = anonymous_browse();
+    create_effect(move |_| anon_browse.request_identity());
 
     let new_identity = create_rw_signal::<Option<DelegatedIdentityWire>>(None);
 
@@ -0,0 +1,53 @@
+use leptos::*;
+
+/// Round avatar, a pulsing placeholder is shown until the image loads
+/// broken images are replaced with `fallback` (e.g a generated avatar)
+#[component]
+pub fn Avatar(
+    #[prop(into)] src: MaybeSignal<String>,
+    #[prop(into)] fallback: String,
+    #[prop(into, optional)] class: String,
+) -> impl IntoView {
+    let loaded = create_rw_signal(false);
+    let image_error = create_rw_signal(false);
+    let img_ref = create_node_ref::<html::Img>();
+
+    let src = Signal::derive(move || {
+        if image_error() {
+            fallback.clone()
+        } else {
+            src.get()
+        }
+    });
+    create_effect(move |_| {
+        src.track();
+        let img = img_ref()?;
+        // Images cached by the browser may finish loading before hydration
+        loaded.set(img.complete() && img.natural_width() > 0);
+        Some(())
+    });
+    let on_error = move |_| {
+        if image_error.get_untracked() {
+            // The fallback is broken as well, stop pulsing
+            loaded.set(true);
+        } else {
+            image_error.set(true);
+        }
+    };
+
+    view! {
+        <div class=format!("relative aspect-square overflow-clip rounded-full {class}")>
+            <Show when=move || !loaded()>
+                <div class="absolute inset-0 bg-white/40 animate-pulse"></div>
+            </Show>
+            <img
+                _ref=img_ref
+                class="h-full w-full object-cover transition-opacity duration-300"
+                class=("opacity-0", move || !loaded())
+                on:load=move |_| loaded.set(true)
+                on:error=on_error
+                src=src
+            />
+        </div>
+    }
+}
@@ -10,18 +10,23 @@ use crate::{
         extract_identity, generate_anonymous_identity_if_required, set_anonymous_identity_cookie,
         DelegatedIdentityWire,
     },
-    component::spinner::FullScreenSpinner,
+    component::{delegation_refresh::DelegationRefresher, spinner::FullScreenSpinner},
     state::{
-        auth::AuthState,
-        canisters::{do_canister_auth, AuthCansResource, Canisters},
+        app_events::AppEvents,
+        auth::{AnonymousBrowse, AuthState},
+        canisters::{do_canister_auth, AuthCans, AuthCansError, AuthCansResource, Canisters},
+        like_queue::LikeQueue,
         local_storage::use_referrer_store,
+        profile_details::ProfileDetailsInvalidator,
     },
-    try_or_redirect,
-    utils::MockPartialEq,
+    utils::{route::failure_redirect, MockPartialEq},
 };
 use codee::string::JsonSerdeCodec;
 use leptos_use::storage::use_local_storage;
 
+/// Number of times a fresh identity is requested before giving up
+const MAX_IDENTITY_RECOVERY_ATTEMPTS: u32 = 2;
+
 #[derive(Params, PartialEq, Clone)]
 struct Referrer {
     user_refer: String,
@@ -35,18 +40,28 @@ fn CtxProvider(temp_identity: Option<JwkEcKey>, children: ChildrenFn) -> impl In
     let canisters_store = create_rw_signal(None::<Canisters<true>>);
     provide_context(canisters_store);
 
-    let temp_identity_c = temp_identity.clone();
+    let anon_browse = AnonymousBrowse::new(temp_identity);
+    provide_context(anon_browse);
+
+    provide_context(ProfileDetailsInvalidator::default());
+    provide_context(AppEvents::default());
+    provide_context(LikeQueue::new());
+
+    // Incremented whenever the received identity fails to reconstruct on the client
+    let identity_recovery = create_rw_signal(0u32);
+
+    // The temporary identity is only persisted once an authenticated action is performed
     create_local_resource(
-        || (),
-        move |_| {
-            let temp_identity = temp_identity_c.clone();
-            async move {
-                let Some(id) = temp_identity else {
-                    return;
-                };
-                if let Err(e) = set_anonymous_identity_cookie(id).await {
-                    log::error!("Failed to set anonymous identity as cookie?! err {e}");
-                }
+        move || anon_browse.requested(),
+        move |requested| async move {
+            if !requested {
+                return;
+            }
+            let Some(id) = anon_browse.temp_identity() else {
+                return;
+            };
+            if let Err(e) = set_anonymous_identity_cookie(id).await {
+                log::error!("Failed to set anonymous identity as cookie?! err {e}");
             }
         },
     );
@@ -66,39 +81,75 @@ fn CtxProvider(temp_identity: Option<JwkEcKey>, children: ChildrenFn) -> impl In
     });
 
     let canisters_res: AuthCansResource = create_resource(
-        move || MockPartialEq(auth()),
-        move |auth_id| {
-            let temp_identity = temp_identity.clone();
-            async move {
-                let ref_principal = referrer_principal.get_untracked();
-
-                if let Some(id_wire) = auth_id.0 {
-                    return do_canister_auth(id_wire, ref_principal).await;
+        move || {
+            // refetch once the temporary identity is requested
+            anon_browse.requested();
+            (MockPartialEq(auth()), identity_recovery())
+        },
+        move |(auth_id, recovery)| async move {
+            let ref_principal = referrer_principal.get_untracked();
+
+            // The previous identity was corrupt, request a fresh one from the server
+            if recovery > 0 {
+                if let Some(id_wire) = extract_identity().await? {
+                    return do_canister_auth(id_wire, ref_principal).await.map(Some);
                 }
+            }
 
-                let Some(jwk_key) = temp_identity else {
-                    let id_wire = extract_identity().await?.expect("No refresh cookie set?!");
-                    return do_canister_auth(id_wire, ref_principal).await;
-                };
+            if let Some(id_wire) = auth_id.0 {
+                return do_canister_auth(id_wire, ref_principal).await.map(Some);
+            }
 
-                let key = k256::SecretKey::from_jwk(&jwk_key)?;
-                let id = Secp256k1Identity::from_private_key(key);
-                let id_wire = DelegatedIdentityWire::delegate(&id);
+            let Some(jwk_key) = anon_browse.temp_identity() else {
+                let id_wire = extract_identity().await?.expect("No refresh cookie set?!");
+                return do_canister_auth(id_wire, ref_principal).await.map(Some);
+            };
 
-                do_canister_auth(id_wire, ref_principal).await
+            // Anonymous browsing, avoid creating a canister for the user
+            if anon_browse.is_active_untracked() {
+                return Ok(None);
             }
+
+            let key = k256::SecretKey::from_jwk(&jwk_key)?;
+            let id = Secp256k1Identity::from_private_key(key);
+            let id_wire = DelegatedIdentityWire::delegate(&id);
+
+            do_canister_auth(id_wire, ref_principal).await.map(Some)
         },
     );
-    provide_context(canisters_res);
+    let auth_cans = AuthCans::new(canisters_res);
+    provide_context(auth_cans);
+
+    let reconnecting = move || identity_recovery() > 0 && canisters_res.loading()();
 
     view! {
         {children}
+        <DelegationRefresher/>
+        <Show when=reconnecting>
+            <div class="fixed top-4 inset-x-0 z-[60] flex justify-center pointer-events-none">
+                <span class="px-4 py-2 rounded-full bg-black/80 text-white text-sm animate-pulse">
+                    Reconnecting...
+                </span>
+            </div>
+        </Show>
         <Suspense>
             {move || {
-                canisters_res()
+                auth_cans
+                    .resolved()
                     .map(|res| {
-                        let cans_wire = try_or_redirect!(res);
-                        let cans = try_or_redirect!(cans_wire.canisters());
+                        let cans = match res {
+                            Ok(Some(cans)) => cans,
+                            Ok(None) => return,
+                            Err(AuthCansError::InvalidIdentity(e)) if identity_recovery.get_untracked() < MAX_IDENTITY_RECOVERY_ATTEMPTS => {
+                                log::warn!("received an unusable identity, requesting a fresh one: {e}");
+                                identity_recovery.update(|r| *r += 1);
+                                return;
+                            }
+                            Err(e) => {
+                                failure_redirect(e);
+                                return;
+                            }
+                        };
 
                         let (_, set_user_canister_id, _) =  use_local_storage::<Option<Principal>, JsonSerdeCodec>(USER_CANISTER_ID_STORE);
                         set_user_canister_id(Some(cans.user_canister()));
@@ -2,8 +2,11 @@ use futures::Future;
 pub use leptos::*;
 
 use crate::{
-    state::canisters::{authenticated_canisters, Canisters},
-    try_or_redirect_opt,
+    state::{
+        auth::anonymous_browse,
+        canisters::{authenticated_canisters, AuthCansError, Canisters},
+    },
+    utils::route::failure_redirect,
 };
 
 #[component]
@@ -15,11 +18,22 @@ where
     N: IntoView + 'static,
     EF: Fn(Canisters<true>) -> N + 'static + Clone,
 {
-    let cans_res = authenticated_canisters();
+    let auth_cans = authenticated_canisters();
+    let anon_browse = anonymous_browse();
+    // Authenticated canisters are required, persist the temporary identity
+    create_effect(move |_| anon_browse.request_identity());
+
     let children = store_value(children);
     let loader = move || {
-        let cans_wire = try_or_redirect_opt!(cans_res()?);
-        let cans = try_or_redirect_opt!(cans_wire.canisters());
+        let cans = match auth_cans.resolved()? {
+            Ok(cans) => cans?,
+            // Unusable identities are recovered (or redirected) by the base route
+            Err(AuthCansError::InvalidIdentity(_)) => return None,
+            Err(e) => {
+                failure_redirect(e);
+                return None;
+            }
+        };
         Some((children.get_value())(cans).into_view())
     };
 
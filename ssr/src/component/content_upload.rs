@@ -11,7 +11,7 @@ fn YoutubeUploadInner(canisters: Canisters<true>, #[prop(optional)] url: String)
     let url_value = RwSignal::new(url);
     let create_short_lived_delegated_identity = |canisters: &Canisters<true>| {
         let id = canisters.identity();
-        DelegatedIdentityWire::delegate_short_lived_identity(id)
+        DelegatedIdentityWire::delegate_short_lived_identity(id, canisters.user_canister())
     };
 
     let on_submit = create_action(move |_| {
@@ -0,0 +1,82 @@
+use gloo::timers::callback::Timeout;
+use leptos::*;
+
+use crate::{component::toast::Toasts, utils::web::copy_to_clipboard_checked};
+
+/// Duration (in milliseconds) a touch must be held to count as a long press
+const LONG_PRESS_MS: u32 = 500;
+
+/// Copies `text` on long press (touch) or right click (mouse)
+/// a long press doesn't trigger the click (e.g navigation) of `children`
+#[component]
+pub fn CopyOnHold(
+    #[prop(into)] text: String,
+    /// Shown in the toast, e.g "Principal copied"
+    #[prop(into)]
+    copied_message: String,
+    children: Children,
+    #[prop(into, optional)] class: String,
+) -> impl IntoView {
+    let toasts = Toasts::get();
+    let copy = store_value(move || {
+        let copying = copy_to_clipboard_checked(&text);
+        let copied_message = copied_message.clone();
+        spawn_local(async move {
+            if copying.await.is_some() {
+                toasts.success(copied_message);
+            } else {
+                toasts.error("Couldn't copy");
+            }
+        });
+    });
+
+    let press = store_value(None::<Timeout>);
+    // Whether the current touch turned into a long press
+    let held = store_value(false);
+    let cancel_press = move || press.update_value(|p| _ = p.take().map(Timeout::cancel));
+
+    let on_touchstart = move |_: ev::TouchEvent| {
+        held.set_value(false);
+        let timeout = Timeout::new(LONG_PRESS_MS, move || {
+            held.set_value(true);
+            copy.with_value(|copy| copy());
+        });
+        press.set_value(Some(timeout));
+    };
+    let on_touchend = move |ev: ev::TouchEvent| {
+        cancel_press();
+        if held.get_value() {
+            // Suppresses the click that follows the touch
+            ev.prevent_default();
+        }
+    };
+    let on_contextmenu = move |ev: ev::MouseEvent| {
+        ev.prevent_default();
+        // Some mobile browsers open the context menu on long press as well
+        if !held.get_value() {
+            copy.with_value(|copy| copy());
+        }
+    };
+    let on_click = move |ev: ev::MouseEvent| {
+        if held.get_value() {
+            ev.prevent_default();
+            ev.stop_propagation();
+            held.set_value(false);
+        }
+    };
+
+    view! {
+        <span
+            class=format!("select-none {class}")
+            style="-webkit-touch-callout: none"
+            on:touchstart=on_touchstart
+            on:touchmove=move |_| cancel_press()
+            on:touchcancel=move |_| cancel_press()
+            on:touchend=on_touchend
+            on:contextmenu=on_contextmenu
+            on:click=on_click
+        >
+            {children()}
+        </span>
+    }
+}
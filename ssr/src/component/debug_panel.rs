@@ -0,0 +1,111 @@
+use ic_agent::Identity;
+use leptos::*;
+use leptos_icons::*;
+use serde::Serialize;
+use web_time::Duration;
+
+use crate::{
+    auth::refresh_token_expiry,
+    component::canisters_prov::AuthCansProvider,
+    state::canisters::Canisters,
+    utils::{current_epoch, timestamp::to_hh_mm_ss, web::copy_to_clipboard},
+};
+
+/// Non-secret metadata of the current delegation
+/// NEVER add the delegated secret key or any JWK here
+#[derive(Serialize, Clone)]
+struct DelegationDebugInfo {
+    principal: String,
+    user_canister: String,
+    delegation_expiry_ns: u64,
+    delegation_chain_len: usize,
+    refresh_token_expiry_ms: Option<u128>,
+}
+
+impl DelegationDebugInfo {
+    fn new(canisters: &Canisters<true>, refresh_token_expiry_ms: Option<u128>) -> Self {
+        let identity = canisters.identity();
+        Self {
+            principal: canisters.user_principal().to_text(),
+            user_canister: canisters.user_canister().to_text(),
+            delegation_expiry_ns: canisters.expiry_ns(),
+            delegation_chain_len: identity.delegation_chain().len(),
+            refresh_token_expiry_ms,
+        }
+    }
+}
+
+fn expires_in(expiry: Duration) -> String {
+    let now = current_epoch();
+    if expiry <= now {
+        return "expired".into();
+    }
+    to_hh_mm_ss(expiry - now)
+}
+
+#[component]
+fn DebugRow(label: &'static str, #[prop(into)] value: String) -> impl IntoView {
+    view! {
+        <div class="flex flex-row w-full justify-between gap-4">
+            <span class="text-white/50">{label}</span>
+            <span class="truncate">{value}</span>
+        </div>
+    }
+}
+
+#[component]
+fn DelegationDebugInfoView(info: DelegationDebugInfo) -> impl IntoView {
+    let copied = create_rw_signal(false);
+    let info_json = serde_json::to_string_pretty(&info).unwrap_or_default();
+    let refresh_expiry = info
+        .refresh_token_expiry_ms
+        .map(|ms| expires_in(Duration::from_millis(ms as u64)))
+        .unwrap_or_else(|| "no refresh token".into());
+
+    view! {
+        <div class="flex flex-col w-full gap-2 text-sm font-mono">
+            <DebugRow label="Principal" value=info.principal/>
+            <DebugRow label="User canister" value=info.user_canister/>
+            <DebugRow
+                label="Delegation expires in"
+                value=expires_in(Duration::from_nanos(info.delegation_expiry_ns))
+            />
+            <DebugRow label="Delegation chain length" value=info.delegation_chain_len.to_string()/>
+            <DebugRow label="Refresh token expires in" value=refresh_expiry/>
+            <button
+                class="flex flex-row gap-2 items-center self-end text-primary-600"
+                on:click=move |_| copied.set(copy_to_clipboard(&info_json).is_some())
+            >
+                <Icon icon=icondata::FaCopyRegular/>
+                {move || if copied() { "Copied!" } else { "Copy debug info" }}
+            </button>
+        </div>
+    }
+}
+
+/// Developer only panel for debugging auth issues
+#[component]
+pub fn DelegationDebugPanel() -> impl IntoView {
+    let refresh_expiry = create_local_resource(|| (), |_| refresh_token_expiry());
+
+    view! {
+        <div class="flex flex-col w-full gap-2 text-white">
+            <span class="text-md font-bold">Delegation Debug Info</span>
+            <AuthCansProvider let:canisters>
+                <Suspense>
+                    {move || {
+                        let refresh_expiry = refresh_expiry()?.ok().flatten();
+                        Some(
+                            view! {
+                                <DelegationDebugInfoView info=DelegationDebugInfo::new(
+                                    &canisters,
+                                    refresh_expiry,
+                                )/>
+                            },
+                        )
+                    }}
+                </Suspense>
+            </AuthCansProvider>
+        </div>
+    }
+}
@@ -0,0 +1,75 @@
+use leptos::*;
+use leptos_icons::*;
+use web_time::Duration;
+
+use crate::{
+    auth::refresh_identity,
+    consts::auth::{DELEGATION_CHECK_INTERVAL, DELEGATION_REFRESH_THRESHOLD},
+    state::{auth::auth_state, canisters::auth_canisters_store},
+    utils::current_epoch,
+};
+
+/// Refreshes the delegated identity shortly before it expires
+/// shows a banner prompting the user to reconnect if that fails
+#[component]
+pub fn DelegationRefresher() -> impl IntoView {
+    let auth = auth_state();
+    let canisters = auth_canisters_store();
+    let refreshing = store_value(false);
+    let refresh_failed = create_rw_signal(false);
+
+    let check = move || {
+        let Some(expiry_ns) = canisters.with_untracked(|c| c.as_ref().map(|c| c.expiry_ns()))
+        else {
+            return;
+        };
+        let remaining = Duration::from_nanos(expiry_ns).saturating_sub(current_epoch());
+        if remaining > DELEGATION_REFRESH_THRESHOLD || refreshing.get_value() {
+            return;
+        }
+        refreshing.set_value(true);
+        spawn_local(async move {
+            match refresh_identity().await {
+                // Replacing the identity re-authenticates the canisters
+                Ok(Some(id)) => _ = auth.try_set(Some(id)),
+                Ok(None) => {
+                    log::warn!("delegation is expiring but there is no session to refresh");
+                    _ = refresh_failed.try_set(true);
+                }
+                Err(e) => {
+                    log::warn!("failed to refresh delegation: {e}");
+                    _ = refresh_failed.try_set(true);
+                }
+            }
+            _ = refreshing.try_set_value(false);
+        });
+    };
+
+    // Check as soon as (new) canisters are available
+    create_effect(move |_| {
+        canisters.track();
+        check();
+    });
+    if let Ok(handle) = set_interval_with_handle(check, DELEGATION_CHECK_INTERVAL) {
+        on_cleanup(move || handle.clear());
+    }
+
+    view! {
+        <Show when=refresh_failed>
+            <div class="fixed top-4 inset-x-4 z-[60] flex flex-row gap-2 items-center justify-between px-4 py-2 rounded-md bg-neutral-900 text-white text-sm shadow-lg">
+                <span>Your session is about to expire</span>
+                <div class="flex flex-row gap-3 items-center">
+                    <button
+                        class="text-primary-600 font-semibold"
+                        on:click=move |_| _ = window().location().reload()
+                    >
+                        Reconnect
+                    </button>
+                    <button aria-label="Dismiss" on:click=move |_| refresh_failed.set(false)>
+                        <Icon icon=icondata::ChCross/>
+                    </button>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -32,6 +32,10 @@ pub(crate) type InferData<T> = <T as CursoredDataProvider>::Data;
 /// and renders children
 /// It will fetch new data whenever the end of the list is reached
 /// also shows a loader while fetching data
+/// and `end_content` once the provider runs out of data
+///
+/// Providers may return empty pages before the end (e.g while filtering),
+/// fetching continues for as long as the end of the list stays in view
 /// node_ref MUST be passed to the root element if passed to the `children` fn
 #[component]
 pub(crate) fn InfiniteScroller<Prov, EF, N, RootNode>(
@@ -39,7 +43,15 @@ pub(crate) fn InfiniteScroller<Prov, EF, N, RootNode>(
     fetch_count: usize,
     children: EF,
     #[prop(optional, into)] empty_content: ViewFn,
+    #[prop(optional, into)] end_content: ViewFn,
     #[prop(optional, into)] custom_loader: Option<ViewFn>,
+    /// Number of items fetched by the first page, at least `fetch_count`
+    /// e.g to fill up to a restored scroll position
+    #[prop(optional)]
+    initial_count: usize,
+    /// Called with the total number of loaded items after every fetch
+    #[prop(optional, into)]
+    on_load: Option<Callback<usize>>,
     #[prop(optional)] _iv: PhantomData<N>,
     #[prop(optional)] _rn: PhantomData<RootNode>,
 ) -> impl IntoView
@@ -52,14 +64,20 @@ where
     let data = create_rw_signal(Vec::<InferData<Prov>>::new());
     let end = create_rw_signal(false);
     let cursor = create_rw_signal(0);
+    let next_cursor = store_value(0);
 
     let fetch_res = create_resource(cursor, move |cursor| {
         let provider = provider.clone();
+        let count = if cursor == 0 {
+            fetch_count.max(initial_count)
+        } else {
+            fetch_count
+        };
         async move {
             let PageEntry {
                 data: mut fetched,
                 end: list_end,
-            } = match provider.get_by_cursor(cursor, cursor + fetch_count).await {
+            } = match provider.get_by_cursor(cursor, cursor + count).await {
                 Ok(t) => t,
                 Err(e) => {
                     log::warn!("failed to fetch data err {e}");
@@ -71,6 +89,10 @@ where
             };
             data.try_update(|t| t.append(&mut fetched));
             end.try_set(list_end);
+            _ = next_cursor.try_set_value(cursor + count);
+            if let (Some(on_load), Some(loaded)) = (on_load, data.try_with_untracked(Vec::len)) {
+                on_load(loaded);
+            }
         }
     });
     let upper_data = move || {
@@ -82,20 +104,34 @@ where
     };
     let last_data = move || with!(|data| data.last().cloned());
     let last_elem = create_node_ref::<RootNode>();
+    // Re-mounted after every fetch, so that it's observed again if it stays in view
+    let sentinel = create_node_ref::<html::Div>();
 
+    let fetch_next = move || {
+        // The current page is still loading
+        if end.get_untracked() || cursor.get_untracked() == next_cursor.get_value() {
+            return;
+        }
+        cursor.set(next_cursor.get_value());
+    };
     use_intersection_observer_with_options(
         last_elem,
         move |entry, _| {
-            let Some(_visible) = entry.first().filter(|entry| entry.is_intersecting()) else {
-                return;
-            };
-            if end.get_untracked() {
-                return;
+            if entry.first().is_some_and(|entry| entry.is_intersecting()) {
+                fetch_next();
             }
-            cursor.update(|c| *c += fetch_count);
         },
         UseIntersectionObserverOptions::default().thresholds(vec![0.1]),
     );
+    use_intersection_observer_with_options(
+        sentinel,
+        move |entry, _| {
+            if entry.first().is_some_and(|entry| entry.is_intersecting()) {
+                fetch_next();
+            }
+        },
+        UseIntersectionObserverOptions::default(),
+    );
     let data_loading = fetch_res.loading();
     let children = store_value(children);
     let loader = custom_loader.unwrap_or_else(|| BulletLoader.into());
@@ -108,9 +144,15 @@ where
         />
         {move || { last_data().map(|info| (children.get_value())(info, Some(last_elem))) }}
 
+        <Show when=move || !end() && !data_loading()>
+            <div node_ref=sentinel class="w-full h-px"></div>
+        </Show>
         <Show when=data_loading>{loader.run()}</Show>
         <Show when=move || {
-            !data_loading() && data.with(|d| d.is_empty())
+            end() && !data_loading() && data.with(|d| d.is_empty())
         }>{empty_content.run()}</Show>
+        <Show when=move || {
+            end() && !data_loading() && data.with(|d| !d.is_empty())
+        }>{end_content.run()}</Show>
     }
 }
@@ -1,5 +1,7 @@
 pub mod airdrop_logo;
+pub mod animated_balance;
 pub mod auth_providers;
+pub mod avatar;
 pub mod back_btn;
 pub mod base_route;
 pub mod bullet_loader;
@@ -7,7 +9,11 @@ pub mod canisters_prov;
 pub mod coming_soon;
 pub mod connect;
 pub mod content_upload;
+pub mod copy_on_hold;
 pub mod dashbox;
+#[cfg(feature = "dev-tools")]
+pub mod debug_panel;
+pub mod delegation_refresh;
 pub mod feed_popup;
 pub mod hn_icons;
 pub mod ic_symbol;
@@ -17,12 +23,18 @@ pub mod login_modal;
 pub mod modal;
 pub mod nav;
 pub mod nav_icons;
+pub mod onboarding_tour;
 pub mod option;
 pub mod overlay;
+pub mod platform_stats;
 pub mod profile_placeholders;
+pub mod qr;
 pub mod scrolling_post_view;
+pub mod seek_bar;
 pub mod social;
 pub mod spinner;
+pub mod thumbnail;
 pub mod title;
+pub mod toast;
 pub mod toggle;
 pub mod video_player;
@@ -2,14 +2,112 @@ use super::overlay::ShadowOverlay;
 use leptos::*;
 use leptos_icons::*;
 
+#[cfg(feature = "hydrate")]
+mod focus {
+    use wasm_bindgen::JsCast;
+    use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+    const FOCUSABLE: &str = "a[href], button:not([disabled]), input:not([disabled]), \
+        select:not([disabled]), textarea:not([disabled]), [tabindex]:not([tabindex='-1'])";
+
+    fn focusable(root: &Element) -> Vec<HtmlElement> {
+        let Ok(nodes) = root.query_selector_all(FOCUSABLE) else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|idx| nodes.item(idx)?.dyn_into().ok())
+            .collect()
+    }
+
+    pub fn active_element() -> Option<HtmlElement> {
+        leptos::document().active_element()?.dyn_into().ok()
+    }
+
+    pub fn focus_first(root: &Element) {
+        if let Some(first) = focusable(root).first() {
+            _ = first.focus();
+        }
+    }
+
+    /// Keep Tab/Shift-Tab cycling within `root`
+    pub fn trap_tab(root: &Element, ev: &KeyboardEvent) {
+        let elems = focusable(root);
+        let (Some(first), Some(last)) = (elems.first(), elems.last()) else {
+            ev.prevent_default();
+            return;
+        };
+        let active = active_element();
+        let inside = active
+            .as_ref()
+            .is_some_and(|a| root.contains(Some(a.as_ref())));
+        if ev.shift_key() && (!inside || active.as_ref() == Some(first)) {
+            ev.prevent_default();
+            _ = last.focus();
+        } else if !ev.shift_key() && (!inside || active.as_ref() == Some(last)) {
+            ev.prevent_default();
+            _ = first.focus();
+        }
+    }
+}
+
+/// Dialog over a dimmed background
+///
+/// Focus moves into the dialog when opened and is restored to the
+/// previously focused element (the trigger) when closed
+/// Tab cycles within the dialog, Escape closes it
 #[component]
 pub fn Modal(#[prop(into)] show: RwSignal<bool>, children: ChildrenFn) -> impl IntoView {
+    let dialog_ref = create_node_ref::<html::Div>();
+
+    #[cfg(feature = "hydrate")]
+    {
+        let trigger = store_value(None::<web_sys::HtmlElement>);
+        create_effect(move |_| {
+            let Some(dialog) = dialog_ref.get() else {
+                return;
+            };
+            trigger.set_value(focus::active_element());
+            focus::focus_first(&dialog);
+        });
+        create_effect(move |_| {
+            if show() {
+                return;
+            }
+            if let Some(trigger) = trigger.get_value() {
+                _ = trigger.focus();
+                trigger.set_value(None);
+            }
+        });
+    }
+
+    let on_keydown = move |ev: ev::KeyboardEvent| match ev.key().as_str() {
+        "Escape" => {
+            ev.stop_propagation();
+            show.set(false);
+        }
+        "Tab" =>
+        {
+            #[cfg(feature = "hydrate")]
+            if let Some(dialog) = dialog_ref.get_untracked() {
+                focus::trap_tab(&dialog, &ev);
+            }
+        }
+        _ => (),
+    };
+
     view! {
         <ShadowOverlay show>
-            <div class="mx-4 py-4 px-8 max-w-full max-h-full items-center cursor-auto flex-col flex justify-around bg-neutral-900 rounded-md divide-y-2 divide-neutral-800">
+            <div
+                _ref=dialog_ref
+                role="dialog"
+                aria-modal="true"
+                on:keydown=on_keydown
+                class="mx-4 py-4 px-8 max-w-full max-h-full items-center cursor-auto flex-col flex justify-around bg-neutral-900 rounded-md divide-y-2 divide-neutral-800"
+            >
                 <div class="flex w-full justify-end items-center p-2">
                     <button
                         on:click=move |_| show.set(false)
+                        aria-label="Close"
                         class="text-white text-center p-1 text-lg md:text-xl bg-primary-600 rounded-full"
                     >
                         <Icon icon=icondata::ChCross/>
@@ -99,7 +99,7 @@ pub fn NavBar() -> impl IntoView {
             "/" => 0,
             "/leaderboard" => 1,
             "/upload" => 2,
-            "/wallet" | "/transactions" => 3,
+            "/wallet" | "/wallet/transactions" | "/transactions" => 3,
             "/menu" => 4,
             s if s.starts_with("/your-profile") => 4,
             s if s.starts_with("/hot-or-not") => {
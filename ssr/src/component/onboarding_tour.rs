@@ -0,0 +1,100 @@
+use leptos::*;
+use leptos_icons::*;
+
+use crate::state::local_storage::use_onboarding_tour_store;
+
+/// A single step of the onboarding tour
+#[derive(Clone, Copy)]
+pub struct TourStep {
+    pub icon: icondata::Icon,
+    pub title: &'static str,
+    pub body: &'static str,
+}
+
+pub const DEFAULT_TOUR_STEPS: &[TourStep] = &[
+    TourStep {
+        icon: icondata::AiSwapOutlined,
+        title: "Swipe to browse",
+        body: "Swipe up for the next video, down to go back.",
+    },
+    TourStep {
+        icon: icondata::AiHeartOutlined,
+        title: "Tap the heart",
+        body: "Like videos you enjoy, or vote hot or not to win COYNs.",
+    },
+    TourStep {
+        icon: icondata::AiWalletOutlined,
+        title: "Your COYNs",
+        body: "Your balance and rewards live in the wallet, find it in the bottom bar.",
+    },
+];
+
+/// Step by step tour for first time visitors
+/// shown until completed or skipped, can be replayed from settings
+///
+/// The tour is a card at the bottom of the screen,
+/// the feed behind it remains interactive
+#[component]
+pub fn OnboardingTour(
+    #[prop(default = DEFAULT_TOUR_STEPS)] steps: &'static [TourStep],
+) -> impl IntoView {
+    let (completed, set_completed, _) = use_onboarding_tour_store();
+    let step_idx = create_rw_signal(0usize);
+
+    // Local storage is only known on the client
+    let mounted = create_rw_signal(false);
+    create_effect(move |_| mounted.set(true));
+
+    let finish = move || {
+        set_completed(true);
+        step_idx.set(0);
+    };
+    let next = move || {
+        if step_idx.get_untracked() + 1 >= steps.len() {
+            finish();
+        } else {
+            step_idx.update(|i| *i += 1);
+        }
+    };
+
+    view! {
+        <Show when=move || mounted() && !completed() && !steps.is_empty()>
+            <div class="fixed inset-x-0 bottom-20 z-[60] flex justify-center px-4 pointer-events-none">
+                {move || {
+                    let idx = step_idx();
+                    let step = steps[idx];
+                    let last = idx + 1 == steps.len();
+                    view! {
+                        <div class="flex flex-col gap-3 w-full md:w-5/12 p-4 rounded-xl bg-black/90 text-white border border-white/20 pointer-events-auto">
+                            <div class="flex flex-row gap-3 items-center">
+                                <Icon class="text-3xl text-primary-600 shrink-0" icon=step.icon/>
+                                <div class="flex flex-col">
+                                    <span class="text-lg font-semibold">{step.title}</span>
+                                    <span class="text-sm text-white/70">{step.body}</span>
+                                </div>
+                            </div>
+                            <div class="flex flex-row justify-between items-center">
+                                <span class="text-xs text-white/50">
+                                    {format!("{}/{}", idx + 1, steps.len())}
+                                </span>
+                                <div class="flex flex-row gap-4 items-center text-sm">
+                                    <Show when=move || !last>
+                                        <button class="text-white/50" on:click=move |_| finish()>
+                                            Skip
+                                        </button>
+                                    </Show>
+                                    <button
+                                        class="px-4 py-1 rounded-full bg-primary-600"
+                                        on:click=move |_| next()
+                                    >
+                                        {if last { "Got it" } else { "Next" }}
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    }
+                }}
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,36 @@
+use leptos::*;
+
+use crate::utils::platform_stats::{get_platform_stats, PlatformStats};
+
+#[component]
+fn StatItem(value: u64, label: &'static str) -> impl IntoView {
+    view! {
+        <div class="flex flex-col flex-1 items-center">
+            <span class="text-xl font-bold text-white">{value}</span>
+            <span class="text-sm text-white/50">{label}</span>
+        </div>
+    }
+}
+
+/// Aggregate platform stats, available without authentication
+#[component]
+pub fn PlatformStatsStrip() -> impl IntoView {
+    let stats = create_resource(|| (), |_| get_platform_stats());
+
+    view! {
+        <Suspense>
+            {move || {
+                stats()
+                    .map(|stats| {
+                        let PlatformStats { total_users, total_videos } = stats.unwrap_or_default();
+                        view! {
+                            <div class="flex flex-row w-full py-4 rounded-xl bg-white/10 divide-x divide-white/20">
+                                <StatItem value=total_users label="Creators"/>
+                                <StatItem value=total_videos label="Videos"/>
+                            </div>
+                        }
+                    })
+            }}
+        </Suspense>
+    }
+}
@@ -0,0 +1,32 @@
+use leptos::*;
+use qrcode::{render::svg, QrCode};
+
+/// Minimum rendered size of the QR code, in pixels
+const QR_MIN_SIZE: u32 = 200;
+
+fn qr_svg(data: &str) -> Option<String> {
+    let code = QrCode::new(data.as_bytes())
+        .inspect_err(|e| log::warn!("failed to generate QR code: {e}"))
+        .ok()?;
+    Some(
+        code.render::<svg::Color>()
+            .min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE)
+            .dark_color(svg::Color("#000000"))
+            .light_color(svg::Color("#ffffff"))
+            .build(),
+    )
+}
+
+/// QR code of `data`, rendered as an inline SVG
+/// only regenerated when `data` changes
+#[component]
+pub fn QrCode(#[prop(into)] data: Signal<String>) -> impl IntoView {
+    let svg = create_memo(move |_| data.with(|d| qr_svg(d)));
+
+    view! {
+        <div
+            class="w-48 h-48 md:w-56 md:h-56 p-2 bg-white rounded-md [&>svg]:w-full [&>svg]:h-full"
+            inner_html=move || svg().unwrap_or_default()
+        ></div>
+    }
+}
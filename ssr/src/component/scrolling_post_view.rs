@@ -2,6 +2,8 @@ use leptos::*;
 use leptos_icons::*;
 use leptos_use::{use_intersection_observer_with_options, UseIntersectionObserverOptions};
 
+use crate::consts::MOUNTED_POSTS_WINDOW;
+use crate::page::post_view::like_status::LikeStatusCache;
 use crate::page::post_view::video_loader::{BgView, VideoViewForQueue};
 
 use crate::state::audio_state::AudioState;
@@ -32,6 +34,7 @@ pub fn ScrollingPostView<F: Fn() -> V + Clone + 'static, V>(
     recovering_state: RwSignal<bool>,
     queue_end: RwSignal<bool>,
     #[prop(optional, into)] overlay: Option<ViewFn>,
+    /// Fetch more posts once at most this many are queued after the current one
     threshold_trigger_fetch: usize,
 ) -> impl IntoView {
     let AudioState {
@@ -39,6 +42,7 @@ pub fn ScrollingPostView<F: Fn() -> V + Clone + 'static, V>(
         show_mute_icon,
         ..
     } = AudioState::get();
+    LikeStatusCache::provide(video_queue, current_idx);
 
     let scroll_root: NodeRef<html::Div> = create_node_ref();
 
@@ -71,7 +75,7 @@ pub fn ScrollingPostView<F: Fn() -> V + Clone + 'static, V>(
                                 {
                                     return;
                                 }
-                                if video_queue.with_untracked(|q| q.len()).saturating_sub(queue_idx)
+                                if video_queue.with_untracked(|q| q.len()).saturating_sub(queue_idx + 1)
                                 <= threshold_trigger_fetch
                                 {
                                     next_videos.as_ref().map(|nv| { nv() });
@@ -92,7 +96,7 @@ pub fn ScrollingPostView<F: Fn() -> V + Clone + 'static, V>(
                             }
                         });
                         let show_video = create_memo(move |_| {
-                            queue_idx.abs_diff(current_idx()) <= 20
+                            queue_idx.abs_diff(current_idx()) <= MOUNTED_POSTS_WINDOW
                         });
                         view! {
                             <div _ref=container_ref class="snap-always snap-end w-full h-full">
@@ -0,0 +1,123 @@
+use leptos::{ev, html::Div, *};
+use leptos_dom::html::Video;
+use leptos_use::use_event_listener;
+
+use crate::canister::utils::thumbnail_at_url;
+
+/// Height (in pixels) of the preview thumbnails
+const PREVIEW_HEIGHT: u32 = 120;
+
+/// Progress bar for a video, drag to seek
+///
+/// While dragging, a thumbnail of the video at the scrub position is shown
+/// the actual seek only happens on release to avoid audio glitches
+#[component]
+pub fn SeekBar(
+    video_ref: NodeRef<Video>,
+    #[prop(into)] uid: Signal<Option<String>>,
+) -> impl IntoView {
+    let bar_ref = create_node_ref::<Div>();
+    let progress = create_rw_signal(0.0f64);
+    let duration = create_rw_signal(0.0f64);
+    // Scrub position (0.0 - 1.0) while dragging
+    let scrub = create_rw_signal(None::<f64>);
+    // Cleared once a preview fails to load, only the bar is shown afterwards
+    let previews_available = create_rw_signal(true);
+
+    _ = use_event_listener(video_ref, ev::timeupdate, move |_| {
+        let Some(video) = video_ref.get_untracked() else {
+            return;
+        };
+        let total = video.duration();
+        if !total.is_finite() || total <= 0.0 {
+            return;
+        }
+        duration.set(total);
+        progress.set(video.current_time() / total);
+    });
+
+    let fraction_at = move |client_x: i32| {
+        let bar = bar_ref.get_untracked()?;
+        let rect = bar.get_bounding_client_rect();
+        if rect.width() <= 0.0 {
+            return None;
+        }
+        Some(((client_x as f64 - rect.left()) / rect.width()).clamp(0.0, 1.0))
+    };
+
+    let on_down = move |ev: ev::PointerEvent| {
+        ev.stop_propagation();
+        if let Some(bar) = bar_ref.get_untracked() {
+            _ = bar.set_pointer_capture(ev.pointer_id());
+        }
+        scrub.set(fraction_at(ev.client_x()));
+    };
+    let on_move = move |ev: ev::PointerEvent| {
+        if scrub.get_untracked().is_none() {
+            return;
+        }
+        scrub.set(fraction_at(ev.client_x()));
+    };
+    let on_up = move |ev: ev::PointerEvent| {
+        ev.stop_propagation();
+        let Some(fraction) = scrub.get_untracked() else {
+            return;
+        };
+        scrub.set(None);
+        let Some(video) = video_ref.get_untracked() else {
+            return;
+        };
+        let total = duration.get_untracked();
+        if total > 0.0 {
+            video.set_current_time(fraction * total);
+            progress.set(fraction);
+        }
+    };
+    let on_cancel = move |_: ev::PointerEvent| scrub.set(None);
+
+    let shown_progress = move || scrub().unwrap_or_else(progress);
+    let preview_url = move || {
+        if !previews_available() {
+            return None;
+        }
+        let fraction = scrub()?;
+        let uid = uid()?;
+        thumbnail_at_url(uid, fraction * duration.get_untracked(), PREVIEW_HEIGHT).ok()
+    };
+
+    view! {
+        <div
+            _ref=bar_ref
+            class="absolute inset-x-0 bottom-[4.5rem] z-[5] px-2 py-3 cursor-pointer"
+            style="touch-action: none;"
+            on:pointerdown=on_down
+            on:pointermove=on_move
+            on:pointerup=on_up
+            on:pointercancel=on_cancel
+        >
+            {move || {
+                preview_url()
+                    .map(|src| {
+                        view! {
+                            <img
+                                class="absolute bottom-6 -translate-x-1/2 h-[120px] rounded-md border border-white/50 bg-black pointer-events-none"
+                                style:left=move || format!("{}%", shown_progress() * 100.0)
+                                src=src
+                                on:error=move |_| previews_available.set(false)
+                            />
+                        }
+                    })
+            }}
+            <div
+                class="relative w-full bg-white/30 rounded-full transition-[height]"
+                class=("h-1", move || scrub.with(|s| s.is_none()))
+                class=("h-2", move || scrub.with(|s| s.is_some()))
+            >
+                <div
+                    class="absolute left-0 top-0 h-full bg-white rounded-full"
+                    style:width=move || format!("{}%", shown_progress() * 100.0)
+                ></div>
+            </div>
+        </div>
+    }
+}
@@ -1,6 +1,9 @@
 use super::ic_symbol::IcSymbol;
 use leptos::*;
 use leptos_icons::*;
+use reqwest::Url;
+
+use crate::{consts::social, utils::web::copy_to_clipboard};
 
 #[component]
 fn FollowItem(#[prop(into)] href: String, #[prop(into)] icon: icondata::Icon) -> impl IntoView {
@@ -30,3 +33,68 @@ social_button!(Telegram, icondata::TbBrandTelegram, TELEGRAM);
 social_button!(Discord, icondata::BiDiscordAlt, DISCORD);
 social_button!(Twitter, icondata::BiTwitter, TWITTER);
 social_button!(IcWebsite, IcSymbol, IC_WEBSITE);
+
+fn share_intent(base: &str, param: &str, url: &str) -> String {
+    Url::parse_with_params(base, &[(param, url)])
+        .map(|u| u.to_string())
+        .unwrap_or_default()
+}
+
+#[component]
+fn ShareItem(
+    #[prop(into)] href: String,
+    #[prop(into)] icon: icondata::Icon,
+    label: &'static str,
+    #[prop(optional)] copy_url: Option<String>,
+) -> impl IntoView {
+    view! {
+        <a
+            href=href
+            target="_blank"
+            rel="noopener noreferrer"
+            class="flex flex-col items-center gap-1 text-xs"
+            on:click=move |_| {
+                if let Some(url) = copy_url.as_ref() {
+                    _ = copy_to_clipboard(url);
+                }
+            }
+        >
+            <div class="h-12 w-12 text-2xl rounded-full grid place-items-center border border-primary-600">
+                <Icon icon/>
+            </div>
+            {label}
+        </a>
+    }
+}
+
+/// Direct share destinations for `url`
+/// for browsers without the Web Share API
+#[component]
+pub fn ShareTargets(#[prop(into)] url: String) -> impl IntoView {
+    view! {
+        <div class="grid grid-cols-4 gap-2 sm:gap-4 w-full justify-items-center">
+            <ShareItem
+                href=share_intent(social::WHATSAPP_SHARE, "text", &url)
+                icon=icondata::BsWhatsapp
+                label="WhatsApp"
+            />
+            <ShareItem
+                href=share_intent(social::TELEGRAM_SHARE, "url", &url)
+                icon=icondata::TbBrandTelegram
+                label="Telegram"
+            />
+            <ShareItem
+                href=share_intent(social::TWITTER_SHARE, "url", &url)
+                icon=icondata::BiTwitter
+                label="X"
+            />
+            // Discord has no share intent, the link is copied for pasting instead
+            <ShareItem
+                href=social::DISCORD
+                icon=icondata::BiDiscordAlt
+                label="Discord"
+                copy_url=url.clone()
+            />
+        </div>
+    }
+}
@@ -0,0 +1,43 @@
+use leptos::*;
+use leptos_icons::*;
+
+/// Portrait (9:16) thumbnail for a post
+/// posters of other aspect ratios are cropped around the center instead of stretched,
+/// missing or broken posters show a centered placeholder
+#[component]
+pub fn Thumbnail(
+    #[prop(into)] src: MaybeSignal<Option<String>>,
+    #[prop(into, optional)] class: String,
+) -> impl IntoView {
+    let image_error = create_rw_signal(false);
+    let src = Signal::derive(move || src.get().filter(|_| !image_error()));
+
+    view! {
+        <div class=format!(
+            "relative aspect-[9/16] w-full overflow-hidden flex items-center justify-center bg-black {class}",
+        )>
+            {move || {
+                src()
+                    .map(|src| {
+                        view! {
+                            <img
+                                class="object-cover object-center w-full h-full"
+                                on:error=move |_| _ = image_error.try_set(true)
+                                src=src
+                            />
+                        }
+                            .into_view()
+                    })
+                    .unwrap_or_else(|| {
+                        view! {
+                            <div class="flex text-center flex-col items-center text-white">
+                                <Icon class="h-8 w-8" icon=icondata::TbCloudX/>
+                                <span class="text-md">Not Available</span>
+                            </div>
+                        }
+                            .into_view()
+                    })
+            }}
+        </div>
+    }
+}
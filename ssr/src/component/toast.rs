@@ -0,0 +1,98 @@
+use gloo::timers::callback::Timeout;
+use leptos::*;
+
+/// Duration (in milliseconds) for which a toast is shown
+const TOAST_DISPLAY_MS: u32 = 2000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Success,
+    Error,
+}
+
+#[derive(Clone, PartialEq)]
+struct Toast {
+    message: String,
+    kind: ToastKind,
+    /// Distinguishes consecutive toasts with the same message
+    seq: u64,
+}
+
+/// Short lived confirmations, rendered by [ToastHost]
+/// only one toast is shown at a time, newer toasts replace older ones
+#[derive(Clone, Copy)]
+pub struct Toasts {
+    current: RwSignal<Option<Toast>>,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self {
+            current: create_rw_signal(None),
+        }
+    }
+}
+
+impl Toasts {
+    pub fn get() -> Self {
+        expect_context()
+    }
+
+    pub fn show(&self, message: impl Into<String>, kind: ToastKind) {
+        let seq = self
+            .current
+            .with_untracked(|t| t.as_ref().map_or(0, |t| t.seq + 1));
+        _ = self.current.try_set(Some(Toast {
+            message: message.into(),
+            kind,
+            seq,
+        }));
+    }
+
+    pub fn success(&self, message: impl Into<String>) {
+        self.show(message, ToastKind::Success);
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.show(message, ToastKind::Error);
+    }
+}
+
+#[component]
+pub fn ToastHost() -> impl IntoView {
+    let toasts = Toasts::get();
+    create_effect(move |_| {
+        let Some(toast) = toasts.current.get() else {
+            return;
+        };
+        Timeout::new(TOAST_DISPLAY_MS, move || {
+            // Only clear if no newer toast replaced this one
+            if toasts.current.try_get_untracked().flatten().as_ref() == Some(&toast) {
+                _ = toasts.current.try_set(None);
+            }
+        })
+        .forget();
+    });
+
+    view! {
+        {move || {
+            toasts
+                .current
+                .get()
+                .map(|toast| {
+                    view! {
+                        <div
+                            role="status"
+                            class="fixed bottom-24 left-1/2 -translate-x-1/2 z-[60] px-4 py-2 rounded-md text-sm shadow-lg"
+                            class=("bg-white/90", toast.kind == ToastKind::Success)
+                            class=("text-black", toast.kind == ToastKind::Success)
+                            class=("bg-red-600", toast.kind == ToastKind::Error)
+                            class=("text-white", toast.kind == ToastKind::Error)
+                        >
+                            {toast.message}
+                        </div>
+                    }
+                })
+        }}
+    }
+}
@@ -11,8 +11,16 @@ pub use remote::*;
 use once_cell::sync::Lazy;
 use reqwest::Url;
 
+/// Host used for absolute links when the request host is unknown
+pub const CANONICAL_HOST: &str = "yral.com";
+// Cloudflare defaults, see [crate::state::cloudflare::CloudflareConfig]
 pub const CF_STREAM_BASE: &str = "https://customer-2p3jflss4r4hmpnz.cloudflarestream.com";
-pub const FALLBACK_PROPIC_BASE: &str = "https://api.dicebear.com/7.x/big-smile/svg";
+/// Delivery base of Cloudflare Images, `{CF_IMAGES_BASE}/{image_id}/{variant}`
+pub const CF_IMAGES_BASE: &str = "https://imagedelivery.net/abXI9nS4DYYtyR1yFFtziA";
+/// Largest accepted profile picture
+pub const MAX_AVATAR_SIZE_BYTES: u64 = 5 * 1024 * 1024;
+/// Base of Dicebear generated avatars, `{DICEBEAR_BASE}/{style}/svg?seed={seed}`
+pub const DICEBEAR_BASE: &str = "https://api.dicebear.com/7.x";
 // an example URL is "https://imagedelivery.net/abXI9nS4DYYtyR1yFFtziA/gob.5/public";
 pub const GOBGOB_PROPIC_URL: &str = "https://imagedelivery.net/abXI9nS4DYYtyR1yFFtziA/gob.";
 pub const GOBGOB_TOTAL_COUNT: u32 = 18557;
@@ -24,6 +32,14 @@ pub const NOTIFICATIONS_ENABLED_STORE: &str = "yral-notifications-enabled";
 pub const NSFW_TOGGLE_STORE: &str = "nsfw-enabled";
 pub const REFERRER_STORE: &str = "referrer";
 pub const USER_CANISTER_ID_STORE: &str = "user-canister-id";
+pub const FEED_POSITION_STORE: &str = "feed-position";
+pub const ONBOARDING_TOUR_STORE: &str = "onboarding-tour-completed";
+pub const DATA_SAVER_STORE: &str = "data-saver-enabled";
+pub const WALLET_CACHE_STORE: &str = "wallet-cache";
+pub const REPORTED_POSTS_STORE: &str = "reported-posts";
+pub const PENDING_LIKES_STORE: &str = "pending-likes";
+pub const AUDIO_UNMUTED_STORE: &str = "audio-unmuted";
+pub const LIST_SCROLL_STORE: &str = "list-scroll";
 
 pub static OFF_CHAIN_AGENT_GRPC_URL: Lazy<Url> =
     Lazy::new(|| Url::parse("https://icp-off-chain-agent.fly.dev:443").unwrap());
@@ -32,22 +48,76 @@ pub static GTAG_MEASUREMENT_ID: Lazy<&str> = Lazy::new(|| "G-PLNNETMSLM");
 pub static DOWNLOAD_UPLOAD_SERVICE: Lazy<Url> =
     Lazy::new(|| Url::parse("https://download-upload-service.fly.dev").unwrap());
 pub const ML_FEED_GRPC_URL: &str = "https://yral-ml-feed-server.fly.dev:443";
+/// Maximum number of in-flight canister calls from the client
+pub const MAX_CONCURRENT_CANISTER_CALLS: usize = 6;
+/// Un-likes are held locally for this long (in milliseconds) before being sent
+/// so that an accidental double tap doesn't make any canister calls
+pub const UNLIKE_GRACE_PERIOD_MS: u32 = 800;
+/// Attempts for a like toggle failing with a transient error
+pub const LIKE_SYNC_MAX_ATTEMPTS: u32 = 3;
+/// Delay (in milliseconds) before the first retry of a like toggle, doubled on every retry
+pub const LIKE_SYNC_RETRY_BACKOFF_MS: u32 = 200;
+/// Timeout for a single request to an external service
+pub const HTTP_TIMEOUT: web_time::Duration = web_time::Duration::from_secs(10);
+/// Retries for idempotent requests to external services
+pub const HTTP_MAX_RETRIES: u32 = 2;
+pub const HTTP_RETRY_BACKOFF: web_time::Duration = web_time::Duration::from_millis(200);
+/// Profile details older than this are refetched when the page regains focus
+pub const PROFILE_DETAILS_STALE_AFTER: web_time::Duration = web_time::Duration::from_secs(5 * 60);
+/// Default minimum watch time (in seconds) for a like to count toward trending
+pub const TRENDING_LIKE_MIN_WATCH_SECS: f64 = 3.0;
+/// Interval at which the cached platform stats are refreshed
+pub const PLATFORM_STATS_REFRESH_INTERVAL: web_time::Duration =
+    web_time::Duration::from_secs(30 * 60);
+/// Number of posts requested per feed page (after the first one)
+pub const FEED_PAGE_SIZE: u64 = 25;
+/// Number of posts kept queued ahead of the current one
+/// once fewer remain, the next page of [FEED_PAGE_SIZE] posts is requested
+/// so the page size controls the bandwidth per request, while this controls
+/// how early requests are made (smoothness); a page size smaller than this
+/// results in back to back requests. Clamped to 0 by the data saver setting
+pub const FEED_PREFETCH_AHEAD: usize = 10;
+/// Posts within this distance of the current one are mounted in the feed
+pub const MOUNTED_POSTS_WINDOW: usize = 20;
+/// Number of upcoming posts whose media is preloaded
+pub const VIDEO_PRELOAD_WINDOW: usize = 2;
+/// Number of recently preloaded posts that won't be preloaded again
+pub const VIDEO_PRELOAD_CACHE_SIZE: usize = 20;
+/// Maximum length of a post description, in characters (not bytes)
+/// enforced by both the upload composer and the server
+pub const MAX_DESCRIPTION_CHARS: usize = 500;
+/// Decimals of the utility token (COYNs), balances are whole numbers
+pub const COYNS_DECIMALS: u8 = 0;
 
 pub mod social {
     pub const TELEGRAM: &str = "https://t.me/+c-LTX0Cp-ENmMzI1";
     pub const DISCORD: &str = "https://discord.gg/GZ9QemnZuj";
     pub const TWITTER: &str = "https://twitter.com/Yral_app";
     pub const IC_WEBSITE: &str = "https://vyatz-hqaaa-aaaam-qauea-cai.ic0.app";
+
+    // Share intents, the shared url is passed as a query parameter
+    pub const WHATSAPP_SHARE: &str = "https://wa.me/";
+    pub const TELEGRAM_SHARE: &str = "https://t.me/share/url";
+    pub const TWITTER_SHARE: &str = "https://twitter.com/intent/tweet";
 }
 
 pub mod auth {
     use web_time::Duration;
 
+    // Defaults, see [crate::state::auth::config::AuthConfig]
     /// Delegation Expiry, 7 days
     pub const DELEGATION_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
     /// Refresh expiry, 30 days
     pub const REFRESH_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 30);
     pub const REFRESH_TOKEN_COOKIE: &str = "user-identity";
+    /// Delegations expiring within this window are refreshed
+    pub const DELEGATION_REFRESH_THRESHOLD: Duration = Duration::from_secs(60 * 60);
+    /// Interval between delegation expiry checks
+    pub const DELEGATION_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+    /// New identities a single IP can persist in a burst
+    pub const IDENTITY_GEN_BURST: u32 = 10;
+    /// A new identity can be persisted by an IP once every interval, after the burst
+    pub const IDENTITY_GEN_REFILL_INTERVAL: Duration = Duration::from_secs(60);
 }
 
 #[cfg(feature = "oauth-ssr")]
@@ -7,17 +7,22 @@ use axum_extra::extract::cookie::Key;
 use leptos::LeptosOptions;
 use leptos_router::RouteListing;
 
+#[cfg(feature = "cloudflare")]
+use crate::state::cloudflare::CloudflareCredentials;
 use crate::{
-    auth::server_impl::store::KVStoreImpl,
-    state::{canisters::Canisters, server::AppState},
+    auth::server_impl::{client_ip::TrustedProxies, store::KVStoreImpl},
+    state::{
+        auth::config::AuthConfig, canisters::Canisters, cloudflare::CloudflareConfig,
+        dev_mode::DevMode, server::AppState,
+    },
 };
 
 #[cfg(feature = "cloudflare")]
-fn init_cf() -> gob_cloudflare::CloudflareAuth {
+fn init_cf(creds: &CloudflareCredentials) -> gob_cloudflare::CloudflareAuth {
     use gob_cloudflare::{CloudflareAuth, Credentials};
     let creds = Credentials {
-        token: env::var("CF_TOKEN").expect("`CF_TOKEN` is required!"),
-        account_id: env::var("CF_ACCOUNT_ID").expect("`CF_ACCOUNT_ID` is required!"),
+        token: creds.token.clone(),
+        account_id: creds.account_id.clone(),
     };
     CloudflareAuth::new(creds)
 }
@@ -168,7 +173,16 @@ impl AppStateBuilder {
     }
 
     pub async fn build(mut self) -> AppStateRes {
+        // Fail fast on an incomplete developer mode configuration
+        if let Some(dev) = DevMode::get() {
+            log::warn!(
+                "developer mode enabled, using the replica at {}",
+                dev.agent_url
+            );
+        }
         let kv = self.init_kv().await;
+        #[cfg(feature = "cloudflare")]
+        let cloudflare_credentials = CloudflareCredentials::from_env();
         #[cfg(feature = "local-bin")]
         {
             self.containers.start_backend().await;
@@ -182,8 +196,13 @@ impl AppStateBuilder {
             #[cfg(feature = "backend-admin")]
             admin_canisters: init_admin_canisters(),
             #[cfg(feature = "cloudflare")]
-            cloudflare: init_cf(),
+            cloudflare: init_cf(&cloudflare_credentials),
+            #[cfg(feature = "cloudflare")]
+            cloudflare_credentials,
             kv,
+            cloudflare_config: CloudflareConfig::from_env(),
+            auth_config: AuthConfig::from_env(),
+            trusted_proxies: TrustedProxies::from_env(),
             cookie_key: init_cookie_key(),
             #[cfg(feature = "oauth-ssr")]
             google_oauth_clients: init_google_oauth(),
@@ -192,6 +211,8 @@ impl AppStateBuilder {
             grpc_offchain_channel: init_grpc_offchain_channel().await,
         };
 
+        crate::utils::platform_stats::cache::spawn_refresher(app_state.canisters.clone());
+
         AppStateRes {
             app_state,
             #[cfg(feature = "local-bin")]
@@ -10,6 +10,7 @@ use hot_or_not_web_leptos_ssr::{app::App, init::AppStateBuilder, state::server::
 use leptos::{get_configuration, logging::log, provide_context};
 use leptos_axum::handle_server_fns_with_context;
 use leptos_axum::{generate_route_list, LeptosRoutes};
+use std::net::SocketAddr;
 
 pub async fn server_fn_handler(
     State(app_state): State<AppState>,
@@ -25,7 +26,12 @@ pub async fn server_fn_handler(
             provide_context(app_state.admin_canisters.clone());
             #[cfg(feature = "cloudflare")]
             provide_context(app_state.cloudflare.clone());
+            #[cfg(feature = "cloudflare")]
+            provide_context(app_state.cloudflare_credentials.clone());
             provide_context(app_state.kv.clone());
+            provide_context(app_state.cloudflare_config.clone());
+            provide_context(app_state.auth_config);
+            provide_context(app_state.trusted_proxies.clone());
             provide_context(app_state.cookie_key.clone());
             #[cfg(feature = "oauth-ssr")]
             provide_context(app_state.google_oauth_clients.clone());
@@ -52,7 +58,12 @@ pub async fn leptos_routes_handler(
             provide_context(app_state.admin_canisters.clone());
             #[cfg(feature = "cloudflare")]
             provide_context(app_state.cloudflare.clone());
+            #[cfg(feature = "cloudflare")]
+            provide_context(app_state.cloudflare_credentials.clone());
             provide_context(app_state.kv.clone());
+            provide_context(app_state.cloudflare_config.clone());
+            provide_context(app_state.auth_config);
+            provide_context(app_state.trusted_proxies.clone());
             provide_context(app_state.cookie_key.clone());
             #[cfg(feature = "oauth-ssr")]
             provide_context(app_state.google_oauth_clients.clone());
@@ -131,8 +142,12 @@ async fn main() {
     // `axum::Server` is a re-export of `hyper::Server`
     log::info!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
-        .with_graceful_shutdown(terminate)
-        .await
-        .unwrap();
+    // Peer addresses are used for per IP rate limits
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(terminate)
+    .await
+    .unwrap();
 }
@@ -1,6 +1,6 @@
 use leptos::*;
 
-use crate::component::title::Title;
+use crate::component::{platform_stats::PlatformStatsStrip, title::Title};
 
 #[component]
 pub fn AboutUs() -> impl IntoView {
@@ -20,6 +20,7 @@ pub fn AboutUs() -> impl IntoView {
                         it or sharing it with their friends and family!
                     </span>
                 </div>
+                <PlatformStatsStrip/>
             </div>
         </div>
     }
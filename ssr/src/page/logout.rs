@@ -4,31 +4,79 @@ use leptos_router::Redirect;
 use leptos_use::storage::use_local_storage;
 
 use crate::{
-    auth::logout_identity,
-    component::loading::Loading,
+    auth::{is_rate_limited, is_storage_unavailable, logout, logout_identity},
+    component::{loading::Loading, toast::Toasts},
     consts::ACCOUNT_CONNECTED_STORE,
+    page::wallet::cache::use_wallet_cache_store,
     state::{auth::auth_state, canisters::auth_canisters_store},
-    try_or_redirect_opt,
-    utils::event_streaming::events::{LogoutClicked, LogoutConfirmation},
+    utils::{
+        event_streaming::events::{LogoutClicked, LogoutConfirmation},
+        route::failure_redirect,
+    },
 };
 
+/// Sign out without switching to a fresh identity
+/// the page is reloaded so that the next account starts from a clean state
+#[component]
+pub fn SwitchAccount() -> impl IntoView {
+    let (_, write_account_connected, _) =
+        use_local_storage::<bool, FromToStringCodec>(ACCOUNT_CONNECTED_STORE);
+    let (_, _, clear_wallet_cache) = use_wallet_cache_store();
+    let switch_action = create_action(move |&()| {
+        let clear_wallet_cache = clear_wallet_cache.clone();
+        async move {
+            if let Err(e) = logout().await {
+                log::warn!("failed to logout: {e}");
+                return;
+            }
+            write_account_connected(false);
+            clear_wallet_cache();
+            _ = window().location().set_href("/");
+        }
+    });
+    let pending = switch_action.pending();
+
+    view! {
+        <button
+            class="text-white/50 text-sm md:text-md underline disabled:opacity-50"
+            disabled=pending
+            on:click=move |_| switch_action.dispatch(())
+        >
+            {move || if pending() { "Switching..." } else { "Switch account" }}
+        </button>
+    }
+}
+
 #[component]
 pub fn Logout() -> impl IntoView {
     let canister_store = auth_canisters_store();
 
     LogoutClicked.send_event(canister_store);
     let auth = auth_state();
+    let toasts = Toasts::get();
 
     let auth_res = create_blocking_resource(
         || (),
         move |_| async move {
-            let id = try_or_redirect_opt!(logout_identity().await);
+            let id = match logout_identity().await {
+                Ok(id) => id,
+                Err(e) if is_storage_unavailable(&e) || is_rate_limited(&e) => {
+                    failure_redirect("We couldn't log you out right now, please try again later");
+                    return None;
+                }
+                Err(e) => {
+                    failure_redirect(e);
+                    return None;
+                }
+            };
 
             LogoutConfirmation.send_event(canister_store);
 
             let (_, write_account_connected, _) =
                 use_local_storage::<bool, FromToStringCodec>(ACCOUNT_CONNECTED_STORE);
             write_account_connected(false);
+            let (_, _, clear_wallet_cache) = use_wallet_cache_store();
+            clear_wallet_cache();
             Some(id)
         },
     );
@@ -42,6 +90,7 @@ pub fn Logout() -> impl IntoView {
                         .flatten()
                         .map(|id| {
                             auth.set(Some(id));
+                            toasts.success("You have been logged out");
                             view! { <Redirect path="/menu"/> }
                         })
                 }}
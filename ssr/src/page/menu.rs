@@ -9,6 +9,7 @@ use crate::consts::{social, NSFW_TOGGLE_STORE};
 use crate::state::auth::account_connected_reader;
 use crate::state::canisters::Canisters;
 use crate::state::content_seed_client::ContentSeedClient;
+use crate::state::local_storage::use_feed_position_store;
 use crate::utils::notifications::get_token_for_principal;
 use crate::utils::profile::ProfileDetails;
 use candid::Principal;
@@ -123,6 +124,7 @@ fn ProfileInfo() -> impl IntoView {
 fn NsfwToggle() -> impl IntoView {
     let (nsfw_enabled, set_nsfw_enabled, _) =
         use_local_storage::<bool, FromToStringCodec>(NSFW_TOGGLE_STORE);
+    let (_, set_feed_position, _) = use_feed_position_store();
     let toggle_ref = create_node_ref::<Input>();
 
     _ = use_event_listener(toggle_ref, ev::change, move |_| {
@@ -131,7 +133,9 @@ fn NsfwToggle() -> impl IntoView {
                 .get_untracked()
                 .map(|t| t.checked())
                 .unwrap_or_default(),
-        )
+        );
+        // Feed changes with the NSFW preference
+        set_feed_position(None);
     });
 
     view! {
@@ -252,6 +256,10 @@ pub fn Menu() -> impl IntoView {
                 </div>
             </div>
             <div class="flex flex-col py-12 px-8 gap-8 w-full text-lg">
+                {
+                    #[cfg(feature = "dev-tools")]
+                    view! { <crate::component::debug_panel::DelegationDebugPanel/> }
+                }
                 <NsfwToggle/>
                 <MenuItem
                     href="/account-transfer"
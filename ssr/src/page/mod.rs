@@ -8,6 +8,7 @@ pub mod google_redirect;
 pub mod leaderboard;
 pub mod logout;
 pub mod menu;
+pub mod not_found;
 pub mod notifs;
 pub mod post_view;
 pub mod privacy;
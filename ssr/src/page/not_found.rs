@@ -0,0 +1,28 @@
+use leptos::*;
+
+/// Not found page for dead links
+/// responds with a 404 status when server rendered
+#[component]
+pub fn NotFound(
+    #[prop(optional, default = "Page not found")] message: &'static str,
+) -> impl IntoView {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::error_template::AppError;
+        use leptos_axum::ResponseOptions;
+
+        if let Some(response) = use_context::<ResponseOptions>() {
+            response.set_status(AppError::NotFound.status_code());
+        }
+    }
+
+    view! {
+        <div class="flex flex-col items-center gap-4 justify-center h-dvh w-dvw bg-black">
+            <span class="text-white text-5xl md:text-6xl font-bold">404</span>
+            <span class="text-white/50 text-lg md:text-xl lg:text-2xl">{message}</span>
+            <a href="/" class="px-4 py-2 bg-primary-600 text-center text-white rounded-full">
+                Back to feed
+            </a>
+        </div>
+    }
+}
@@ -10,9 +10,14 @@ use crate::{
         bullet_loader::BulletLoader, canisters_prov::AuthCansProvider, hn_icons::*,
         spinner::SpinnerFit,
     },
-    state::canisters::{unauth_canisters, Canisters},
+    state::{
+        app_events::{AppEvent, AppEvents},
+        auth::anonymous_browse,
+        canisters::{unauth_canisters, Canisters},
+    },
     try_or_redirect_opt,
     utils::{
+        call_limit::{limit_call, CallPriority},
         posts::PostDetails,
         profile::{BetDetails, BetKind, BetOutcome},
         timestamp::to_hh_mm_ss,
@@ -71,7 +76,11 @@ async fn bet_on_post(
         post_canister_id,
     };
 
-    let res = user.bet_on_currently_viewing_post(place_bet_arg).await?;
+    let res = limit_call(
+        CallPriority::User,
+        user.bet_on_currently_viewing_post(place_bet_arg),
+    )
+    .await?;
 
     let betting_status = match res {
         Result1::Ok(p) => p,
@@ -159,25 +168,31 @@ fn HNButtonOverlay(
         },
     );
     let place_bet_res = place_bet_action.value();
+    let events = AppEvents::get();
     create_effect(move |_| {
         if place_bet_res().flatten().is_some() {
             refetch_bet.notify();
+            events.publish(AppEvent::BalanceChanged);
         }
     });
     let running = place_bet_action.pending();
 
     view! {
-        <AuthCansProvider let:canisters>
-        {
-            create_effect(move |_| {
-                let Some(bet_direction) = bet_direction() else {
-                    return;
-                };
-                let bet_amount = coin.get_untracked().into();
-                place_bet_action.dispatch((canisters.clone(), bet_direction, bet_amount));
-            });
-        }
-        </AuthCansProvider>
+        // Only request canisters once a bet is placed
+        // to avoid persisting identities for anonymous users
+        <Show when=move || bet_direction.with(|b| b.is_some())>
+            <AuthCansProvider let:canisters>
+            {
+                create_effect(move |_| {
+                    let Some(bet_direction) = bet_direction() else {
+                        return;
+                    };
+                    let bet_amount = coin.get_untracked().into();
+                    place_bet_action.dispatch((canisters.clone(), bet_direction, bet_amount));
+                });
+            }
+            </AuthCansProvider>
+        </Show>
         <div class="flex w-full justify-center">
             <button
                 disabled=running
@@ -456,19 +471,36 @@ pub fn HNGameOverlay(post: PostDetails) -> impl IntoView {
                 async move {
                     let post = post.get_value();
                     let user = cans.authenticated_user().await?;
-                    let bet_participation = user
-                        .get_individual_hot_or_not_bet_placed_by_this_profile(
+                    let bet_participation = limit_call(
+                        CallPriority::Background,
+                        user.get_individual_hot_or_not_bet_placed_by_this_profile(
                             post.canister_id,
                             post.post_id,
-                        )
-                        .await?;
+                        ),
+                    )
+                    .await?;
                     Ok::<_, ServerFnError>(bet_participation.map(BetDetails::from))
                 }
             },
         )
     };
 
+    let anon_browse = anonymous_browse();
+
     view! {
+        <Show
+            when=move || !anon_browse.is_active()
+            fallback=move || {
+                // Anonymous users can't have participated yet
+                view! {
+                    <MaybeHNButtons
+                        post=post.get_value()
+                        bet_direction coin
+                        refetch_bet
+                    />
+                }
+            }
+        >
         <AuthCansProvider fallback=LoaderWithShadowBg let:canisters>
         {
             let bet_participation_outcome = create_bet_participation_outcome(canisters);
@@ -492,5 +524,6 @@ pub fn HNGameOverlay(post: PostDetails) -> impl IntoView {
             }
         }
         </AuthCansProvider>
+        </Show>
     }
 }
@@ -0,0 +1,146 @@
+use candid::Principal;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    canister::individual_user_template::{FolloweeArg, Result2},
+    state::canisters::{auth_canisters_store, Canisters},
+    utils::{
+        call_limit::{limit_call, CallPriority},
+        format_count,
+        ic::{canister_call, CallError},
+    },
+};
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct FollowStatus {
+    following: bool,
+    followers: u64,
+}
+
+/// Creator's (canister id, principal)
+pub type Followee = (Principal, Principal);
+
+fn followee_arg((followee_canister_id, followee_principal_id): Followee) -> FolloweeArg {
+    FolloweeArg {
+        followee_canister_id,
+        followee_principal_id,
+    }
+}
+
+async fn follow_status(
+    cans: &Canisters<true>,
+    followee: Followee,
+) -> Result<FollowStatus, CallError> {
+    let user = cans.authenticated_user().await?;
+    let following = match limit_call(
+        CallPriority::Background,
+        user.do_i_follow_this_user(followee_arg(followee)),
+    )
+    .await?
+    {
+        Result2::Ok(following) => following,
+        Result2::Err(e) => return Err(CallError::Canister(format!("{e:?}"))),
+    };
+    let creator = cans.individual_user(followee.0).await?;
+    let details = limit_call(CallPriority::Background, creator.get_profile_details()).await?;
+
+    Ok(FollowStatus {
+        following,
+        followers: details.followers_count,
+    })
+}
+
+/// Follow status of the creator of a post, fetched along with the like status
+/// `None` for the user's own posts or if the status couldn't be fetched
+pub async fn creator_follow_status(
+    cans: &Canisters<true>,
+    followee: Followee,
+) -> Option<FollowStatus> {
+    if cans.user_canister() == followee.0 {
+        return None;
+    }
+    follow_status(cans, followee)
+        .await
+        .inspect_err(|e| log::warn!("failed to fetch follow status: {e}"))
+        .ok()
+}
+
+async fn toggle_follow(cans: Canisters<true>, followee: Followee) -> Result<bool, CallError> {
+    let user = cans.authenticated_user().await?;
+    match canister_call(
+        CallPriority::User,
+        user.update_profiles_i_follow_toggle_list_with_specified_profile(followee_arg(followee)),
+    )
+    .await?
+    {
+        Result2::Ok(following) => Ok(following),
+        Result2::Err(e) => Err(CallError::Canister(format!("{e:?}"))),
+    }
+}
+
+#[component]
+fn FollowToggle(status: FollowStatus, followee: Followee) -> impl IntoView {
+    let following = create_rw_signal(status.following);
+    let followers = create_rw_signal(status.followers);
+    let canisters = auth_canisters_store();
+
+    let toggle = create_action(move |&follow: &bool| {
+        let cans = canisters.get_untracked();
+        async move {
+            let Some(cans) = cans else {
+                return;
+            };
+            let set = |follow: bool| {
+                if following.try_get_untracked() == Some(follow) {
+                    return;
+                }
+                _ = following.try_set(follow);
+                _ = followers.try_update(|f| {
+                    *f = if follow {
+                        f.saturating_add(1)
+                    } else {
+                        f.saturating_sub(1)
+                    }
+                });
+            };
+            // Optimistic, reconciled with the canister state
+            set(follow);
+            match toggle_follow(cans, followee).await {
+                Ok(confirmed) => set(confirmed),
+                Err(e) => {
+                    log::warn!("failed to toggle follow: {e}");
+                    set(!follow);
+                }
+            }
+        }
+    });
+    let pending = toggle.pending();
+
+    view! {
+        <button
+            class="flex flex-row gap-1 items-center px-2 py-0.5 rounded-full text-xs md:text-sm pointer-events-auto disabled:opacity-50"
+            class=("bg-primary-600", move || !following())
+            class=("bg-white/20", following)
+            disabled=pending
+            on:click=move |_| toggle.dispatch(!following.get_untracked())
+        >
+            <span>{move || if following() { "Following" } else { "Follow" }}</span>
+            <span class="text-white/70">{move || format_count(followers())}</span>
+        </button>
+    }
+}
+
+/// Follow button for the creator of a post
+/// `status` is fetched along with the like status of the post, see [creator_follow_status]
+/// hidden until it's available, so on the user's own posts and while browsing anonymously
+#[component]
+pub fn FollowButton(
+    creator_canister: Principal,
+    creator_principal: Principal,
+    #[prop(into)] status: Signal<Option<FollowStatus>>,
+) -> impl IntoView {
+    let followee = (creator_canister, creator_principal);
+
+    move || status().map(|status| view! { <FollowToggle status followee/> })
+}
@@ -0,0 +1,86 @@
+//! Liked status of the posts mounted around the current one, prefetched by the feed
+//! overlays read their status from here instead of querying their post individually
+use std::collections::HashMap;
+
+use candid::Principal;
+use leptos::*;
+
+use crate::{
+    consts::MOUNTED_POSTS_WINDOW, state::canisters::auth_canisters_store, utils::posts::PostDetails,
+};
+
+use super::video_iter::posts_liked_by_me;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LikeStatus {
+    Loading,
+    /// (liked, like count)
+    Loaded(bool, u64),
+    /// The overlay should fall back to its own query
+    Failed,
+}
+
+/// Provided in context by the feed
+#[derive(Clone, Copy)]
+pub struct LikeStatusCache {
+    statuses: RwSignal<HashMap<(Principal, u64), LikeStatus>>,
+}
+
+impl LikeStatusCache {
+    /// Prefetch the status of the posts in `video_queue` that are mounted
+    /// around `current_idx`, as the user's canisters become available
+    pub fn provide(video_queue: RwSignal<Vec<PostDetails>>, current_idx: RwSignal<usize>) -> Self {
+        let this = Self {
+            statuses: create_rw_signal(HashMap::new()),
+        };
+        provide_context(this);
+
+        let canisters = auth_canisters_store();
+        create_effect(move |_| {
+            let Some(cans) = canisters() else {
+                return;
+            };
+            let start = current_idx().saturating_sub(MOUNTED_POSTS_WINDOW);
+            let missing: Vec<_> = video_queue.with(|q| {
+                this.statuses.with_untracked(|s| {
+                    q.iter()
+                        .skip(start)
+                        .take(2 * MOUNTED_POSTS_WINDOW + 1)
+                        // Authenticated feeds already carry the status
+                        .filter(|p| p.liked_by_user.is_none())
+                        .map(|p| (p.canister_id, p.post_id))
+                        .filter(|key| !s.contains_key(key))
+                        .collect()
+                })
+            });
+            if missing.is_empty() {
+                return;
+            }
+
+            this.statuses.update(|s| {
+                s.extend(missing.iter().map(|&key| (key, LikeStatus::Loading)));
+            });
+            spawn_local(async move {
+                let res = posts_liked_by_me(&cans, &missing).await;
+                _ = this.statuses.try_update(|s| {
+                    for (key, res) in missing.into_iter().zip(res) {
+                        let status = match res {
+                            Ok((liked, likes)) => LikeStatus::Loaded(liked, likes),
+                            Err(e) => {
+                                log::warn!("failed to prefetch like status of {key:?}: {e}");
+                                LikeStatus::Failed
+                            }
+                        };
+                        s.insert(key, status);
+                    }
+                });
+            });
+        });
+
+        this
+    }
+
+    pub fn get(&self, key: (Principal, u64)) -> Option<LikeStatus> {
+        self.statuses.with(|s| s.get(&key).copied())
+    }
+}
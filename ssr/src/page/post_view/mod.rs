@@ -1,14 +1,25 @@
 mod bet;
 pub mod error;
+mod follow;
+pub mod like_status;
 pub mod overlay;
+mod preload;
+mod reaction;
 pub mod single_post;
 pub mod video_iter;
 pub mod video_loader;
 use crate::{
     abselector,
-    component::{scrolling_post_view::ScrollingPostView, spinner::FullScreenSpinner},
-    consts::NSFW_TOGGLE_STORE,
-    state::canisters::{unauth_canisters, Canisters},
+    component::{
+        onboarding_tour::OnboardingTour, scrolling_post_view::ScrollingPostView,
+        spinner::FullScreenSpinner,
+    },
+    consts::{FEED_PREFETCH_AHEAD, NSFW_TOGGLE_STORE},
+    page::not_found::NotFound,
+    state::{
+        canisters::{unauth_canisters, Canisters},
+        local_storage::{use_data_saver_store, use_feed_position_store, FeedPosition},
+    },
     try_or_redirect,
     utils::{
         ab_testing::ABComponent,
@@ -21,8 +32,9 @@ use codee::string::FromToStringCodec;
 use futures::StreamExt;
 use leptos::*;
 use leptos_router::*;
-use leptos_use::{storage::use_local_storage, use_debounce_fn};
+use leptos_use::{storage::use_local_storage, use_debounce_fn, use_throttle_fn_with_arg};
 
+use preload::use_video_preload;
 use video_iter::{FeedResultType, VideoFetchStream};
 
 #[derive(Params, PartialEq, Clone, Copy)]
@@ -43,6 +55,14 @@ pub struct PostViewCtx {
     queue_end: RwSignal<bool>,
 }
 
+impl PostViewCtx {
+    /// The post following `idx` in the feed queue
+    pub fn post_after(&self, idx: usize) -> Option<PostDetails> {
+        self.video_queue
+            .with_untracked(|q| q.get(idx.saturating_add(1)).cloned())
+    }
+}
+
 #[component]
 pub fn CommonPostViewWithUpdates(
     initial_post: Option<PostDetails>,
@@ -56,6 +76,14 @@ pub fn CommonPostViewWithUpdates(
         queue_end,
     } = expect_context();
 
+    // Data saver only fetches once the last queued post is reached
+    let (data_saver, _, _) = use_data_saver_store();
+    let threshold_trigger_fetch = if data_saver.get_untracked() {
+        0
+    } else {
+        threshold_trigger_fetch
+    };
+
     let recovering_state = create_rw_signal(false);
     if let Some(initial_post) = initial_post.clone() {
         fetch_cursor.update_untracked(|f| {
@@ -93,6 +121,8 @@ pub fn CommonPostViewWithUpdates(
         500.0,
     );
 
+    use_video_preload(video_queue, current_idx);
+
     let current_post_base = create_memo(move |_| {
         with!(|video_queue| {
             let cur_idx = current_idx();
@@ -101,6 +131,13 @@ pub fn CommonPostViewWithUpdates(
         })
     });
 
+    let (_, set_feed_position, _) = use_feed_position_store();
+    let feed = get_feed_component_identifier()().unwrap_or_default();
+    let save_feed_position = use_throttle_fn_with_arg(
+        move |pos: FeedPosition| set_feed_position(Some(pos)),
+        1000.0,
+    );
+
     create_effect(move |_| {
         let Some((canister_id, post_id)) = current_post_base() else {
             return;
@@ -109,6 +146,12 @@ pub fn CommonPostViewWithUpdates(
             &format!("/hot-or-not/{canister_id}/{post_id}",),
             Default::default(),
         );
+        save_feed_position(FeedPosition {
+            canister_id,
+            post_id,
+            idx: current_idx.get_untracked(),
+            feed: feed.to_string(),
+        });
     });
 
     view! {
@@ -182,7 +225,7 @@ pub fn PostViewWithUpdates(initial_post: Option<PostDetails>) -> impl IntoView {
         <CommonPostViewWithUpdates
             initial_post
             fetch_video_action
-            threshold_trigger_fetch=10
+            threshold_trigger_fetch=FEED_PREFETCH_AHEAD
         />
     }
 }
@@ -277,30 +320,32 @@ pub fn PostView() -> impl IntoView {
     } = expect_context();
     let canisters = unauth_canisters();
 
-    let fetch_first_video_uid = create_resource(initial_canister_and_post, move |params| {
-        let canisters = canisters.clone();
-        async move {
-            let Some(params) = params else {
-                return Err(());
-            };
-            let cached_post = video_queue
-                .with_untracked(|q| q.get(current_idx.get_untracked()).cloned())
-                .filter(|post| {
-                    post.canister_id == params.canister_id && post.post_id == params.post_id
-                });
-            if let Some(post) = cached_post {
-                return Ok(Some(post));
-            }
+    // Blocking so that the not found status is set before streaming the response
+    let fetch_first_video_uid =
+        create_blocking_resource(initial_canister_and_post, move |params| {
+            let canisters = canisters.clone();
+            async move {
+                let Some(params) = params else {
+                    return Ok(None);
+                };
+                let cached_post = video_queue
+                    .with_untracked(|q| q.get(current_idx.get_untracked()).cloned())
+                    .filter(|post| {
+                        post.canister_id == params.canister_id && post.post_id == params.post_id
+                    });
+                if let Some(post) = cached_post {
+                    return Ok(Some(post));
+                }
 
-            match get_post_uid(&canisters, params.canister_id, params.post_id).await {
-                Ok(post) => Ok(post),
-                Err(e) => {
-                    failure_redirect(e);
-                    Err(())
+                match get_post_uid(&canisters, params.canister_id, params.post_id).await {
+                    Ok(post) => Ok(post),
+                    Err(e) => {
+                        failure_redirect(e);
+                        Err(())
+                    }
                 }
             }
-        }
-    });
+        });
 
     view! {
         <Suspense fallback=FullScreenSpinner>
@@ -308,20 +353,25 @@ pub fn PostView() -> impl IntoView {
             let component_PostViewWithUpdatesMLFeed: ABComponent = Box::new(move || {
                 fetch_first_video_uid()
                     .and_then(|initial_post| {
-                        let initial_post = initial_post.ok()?;
-                        Some(view! { <PostViewWithUpdatesMLFeed initial_post /> })
+                        let Some(initial_post) = initial_post.ok()? else {
+                            return Some(view! { <NotFound message="Post not found"/> });
+                        };
+                        Some(view! { <PostViewWithUpdatesMLFeed initial_post=Some(initial_post) /> })
                     })
             });
             let component_PostViewWithUpdates: ABComponent = Box::new(move || {
                 fetch_first_video_uid()
                     .and_then(|initial_post| {
-                        let initial_post = initial_post.ok()?;
-                        Some(view! { <PostViewWithUpdates initial_post /> })
+                        let Some(initial_post) = initial_post.ok()? else {
+                            return Some(view! { <NotFound message="Post not found"/> });
+                        };
+                        Some(view! { <PostViewWithUpdates initial_post=Some(initial_post) /> })
                     })
             });
             abselector!(get_feed_component_identifier(), component_PostViewWithUpdatesMLFeed, component_PostViewWithUpdates)()
         }
 
         </Suspense>
+        <OnboardingTour/>
     }
 }
@@ -1,30 +1,54 @@
 use crate::{
     component::{
-        canisters_prov::WithAuthCans, hn_icons::HomeFeedShareIcon, modal::Modal,
-        option::SelectOption,
+        avatar::Avatar, canisters_prov::WithAuthCans, copy_on_hold::CopyOnHold,
+        hn_icons::HomeFeedShareIcon, login_modal::LoginModal, modal::Modal, option::SelectOption,
+        qr::QrCode, social::ShareTargets, toast::Toasts,
+    },
+    state::{
+        audio_state::AudioState,
+        auth::{account_connected_reader, anonymous_browse, with_valid_delegation},
+        canisters::{auth_canisters_store, Canisters},
+        local_storage::use_reported_posts_store,
     },
-    state::canisters::{auth_canisters_store, Canisters},
     utils::{
         event_streaming::events::{LikeVideo, ShareVideo},
-        posts::PostDetails,
+        format_count,
+        i18n::t,
+        posts::{get_base_url, PostDetails},
+        profile::{avatar_seed, fallback_propic_url, AvatarStyle},
         report::ReportOption,
         route::failure_redirect,
         user::UserDetails,
-        web::{copy_to_clipboard, share_url},
+        web::{copy_to_clipboard_checked, share_url},
     },
 };
-use gloo::timers::callback::Timeout;
 use leptos::*;
 use leptos_icons::*;
-use leptos_use::use_window;
 
-use super::{bet::HNGameOverlay, video_iter::post_liked_by_me};
+use super::{
+    bet::HNGameOverlay,
+    follow::{creator_follow_status, FollowButton, FollowStatus},
+    like_status::{LikeStatus, LikeStatusCache},
+    reaction::{DoubleTapLike, LikeSync},
+    video_iter::post_liked_by_me,
+};
 
 #[component]
-fn LikeAndAuthCanLoader(post: PostDetails) -> impl IntoView {
-    let likes = create_rw_signal(post.likes);
+fn LikeAndAuthCanLoader(
+    post: PostDetails,
+    follow_status: RwSignal<Option<FollowStatus>>,
+) -> impl IntoView {
+    let post_canister = post.canister_id;
+    let post_id = post.post_id;
+    let initial_liked = (post.liked_by_user, post.likes);
 
-    let liked = create_rw_signal(None::<bool>);
+    let like_sync = LikeSync::new(post_canister, post_id, post.likes);
+    let LikeSync {
+        liked,
+        likes,
+        error,
+        ..
+    } = like_sync;
     let icon_name = Signal::derive(move || {
         if liked().unwrap_or_default() {
             "/img/heart-icon-liked.svg"
@@ -33,46 +57,21 @@ fn LikeAndAuthCanLoader(post: PostDetails) -> impl IntoView {
         }
     });
 
-    let post_canister = post.canister_id;
-    let post_id = post.post_id;
-    let initial_liked = (post.liked_by_user, post.likes);
     let canisters = auth_canisters_store();
 
-    let like_toggle = create_action(move |&()| {
-        let post_details = post.clone();
-        let canister_store = canisters;
-
-        async move {
-            let Some(canisters) = canisters.get_untracked() else {
-                log::warn!("Trying to toggle like without auth");
-                return;
-            };
-            batch(move || {
-                if liked.get_untracked().unwrap_or_default() {
-                    likes.update(|l| *l -= 1);
-                    liked.set(Some(false));
-                } else {
-                    likes.update(|l| *l += 1);
-                    liked.set(Some(true));
-
-                    LikeVideo.send_event(post_details, likes, canister_store);
-                }
-            });
-            let Ok(individual) = canisters.individual_user(post_canister).await else {
-                return;
-            };
-            match individual
-                .update_post_toggle_like_status_by_caller(post_id)
-                .await
-            {
-                Ok(_) => (),
-                Err(e) => {
-                    log::warn!("Error toggling like status: {:?}", e);
-                    liked.update(|l| _ = l.as_mut().map(|l| *l = !*l));
-                }
+    let like_toggle = move || {
+        let Some(cans) = canisters.get_untracked() else {
+            log::warn!("Trying to toggle like without auth");
+            return;
+        };
+        // Likes made with an expired delegation are replayed once it's refreshed
+        let post = post.clone();
+        with_valid_delegation(cans, move |cans| {
+            if like_sync.toggle(cans) == Some(true) {
+                LikeVideo.send_event(post, likes, canisters);
             }
-        }
-    });
+        });
+    };
 
     let liked_fetch = move |cans: Canisters<true>| async move {
         if let Some(liked) = initial_liked.0 {
@@ -87,46 +86,169 @@ fn LikeAndAuthCanLoader(post: PostDetails) -> impl IntoView {
             }
         }
     };
+    // The creator's follow status is fetched in the same batch
+    let followee = (post_canister, post.poster_principal);
+    let status_fetch = move |cans: Canisters<true>, with_liked: bool| async move {
+        let liked = async {
+            if with_liked {
+                Some(liked_fetch(cans.clone()).await)
+            } else {
+                None
+            }
+        };
+        futures::join!(liked, creator_follow_status(&cans, followee))
+    };
+
+    // Prefer the status prefetched by the feed, posts are only queried
+    // individually outside of the feed or if the prefetch failed
+    let like_status = use_context::<LikeStatusCache>();
+    let status_key = (post_canister, post_id);
+    if let Some(cache) = like_status {
+        create_effect(move |_| {
+            if let Some(LikeStatus::Loaded(liked, likes)) = cache.get(status_key) {
+                like_sync.set_initial(liked, likes);
+            }
+        });
+    }
+    let fetch_individually = move || match like_status {
+        Some(_) if initial_liked.0.is_some() => true,
+        Some(cache) => cache.get(status_key) == Some(LikeStatus::Failed),
+        None => true,
+    };
+
+    // Anonymous users haven't liked anything yet
+    // the identity is only persisted once they try to like a post
+    let anon_browse = anonymous_browse();
+    let browsing_anonymously = anon_browse.is_active_untracked();
+    if browsing_anonymously {
+        like_sync.set_initial(false, initial_liked.1);
+    }
+    // Refetched if the feed's prefetch fails later on
+    let status_fetch_mode = create_memo(move |_| {
+        (
+            !anon_browse.is_active(),
+            !browsing_anonymously && fetch_individually(),
+        )
+    });
+    let pending_like = create_rw_signal(false);
+    let like_toggle_c = like_toggle.clone();
+    create_effect(move |_| {
+        if !pending_like() || canisters.with(|c| c.is_none()) {
+            return;
+        }
+        pending_like.set(false);
+        like_toggle_c();
+    });
+    let on_like_click = move || {
+        if anon_browse.is_active_untracked() {
+            anon_browse.request_identity();
+            pending_like.set(true);
+            return;
+        }
+        like_toggle()
+    };
 
-    let liking = like_toggle.pending();
+    // Double taps only ever like the post
+    if let Some(double_tap) = use_context::<DoubleTapLike>() {
+        let on_like_click = on_like_click.clone();
+        create_effect(move |prev: Option<()>| {
+            double_tap.burst.track();
+            if prev.is_none() {
+                return;
+            }
+            if double_tap.burst.with_untracked(|b| b.is_none())
+                || pending_like.get_untracked()
+                || liked.get_untracked() != Some(false)
+            {
+                return;
+            }
+            on_like_click();
+        });
+    }
 
     view! {
-        <div class="flex flex-col gap-1 items-center">
+        <div class="relative flex flex-col gap-1 items-center">
             <button
-                on:click=move |_| like_toggle.dispatch(())
-                disabled=move || liking() || liked.with(|l| l.is_none())
+                on:click=move |_| on_like_click()
+                disabled=move || pending_like() || liked.with(|l| l.is_none())
             >
                 <img src=icon_name style="width: 1em; height: 1em;"/>
             </button>
-            <span class="text-sm md:text-md">{likes}</span>
-            <WithAuthCans with=liked_fetch let:d>
-                {move || {
-                    likes.set(d.1.1);
-                    liked.set(Some(d.1.0))
-                }}
-
-            </WithAuthCans>
+            <span class="text-sm md:text-md">{move || format_count(likes())}</span>
+            <Show when=move || like_sync.pending_sync()>
+                <span class="absolute -bottom-4 text-white/50 text-xs" title="Pending sync">
+                    <Icon icon=icondata::AiClockCircleOutlined/>
+                </span>
+            </Show>
+            {move || {
+                error()
+                    .map(|msg| {
+                        view! {
+                            <span class="absolute right-12 w-40 p-2 rounded-md bg-black/60 text-xs text-right">
+                                {msg}
+                            </span>
+                        }
+                    })
+            }}
+            // Statuses are fetched once the user has an identity, also after logging in
+            {move || {
+                let (has_identity, with_liked) = status_fetch_mode();
+                if !has_identity {
+                    return None;
+                }
+                Some(
+                    view! {
+                        <WithAuthCans with=move |cans| status_fetch(cans, with_liked) let:d>
+                            {move || {
+                                let (liked, follow) = d.1;
+                                if let Some((liked, likes)) = liked {
+                                    like_sync.set_initial(liked, likes);
+                                }
+                                follow_status.set(follow);
+                            }}
+                        </WithAuthCans>
+                    },
+                )
+            }}
         </div>
 
     }
 }
 
+/// Mute toggle for the videos, the preference is shared by all posts
+#[component]
+fn MuteToggle() -> impl IntoView {
+    let muted = expect_context::<AudioState>().muted;
+
+    view! {
+        <button
+            on:click=move |_| AudioState::toggle_mute()
+            aria-label=move || if muted() { t("overlay.unmute") } else { t("overlay.mute") }
+        >
+            <Show
+                when=muted
+                fallback=|| view! { <Icon class="drop-shadow-lg" icon=icondata::BiVolumeFullSolid/> }
+            >
+                <Icon class="drop-shadow-lg" icon=icondata::BiVolumeMuteSolid/>
+            </Show>
+        </button>
+    }
+}
+
 #[component]
 pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
     let show_share = create_rw_signal(false);
     let show_report = create_rw_signal(false);
     let (report_option, set_report_option) =
         create_signal(ReportOption::Nudity.as_str().to_string());
-    let show_copied_popup = create_rw_signal(false);
-    let base_url = || {
-        use_window()
-            .as_ref()
-            .and_then(|w| w.location().origin().ok())
-    };
+    let show_qr = create_rw_signal(false);
     let video_url = move || {
-        base_url()
-            .map(|b| format!("{b}/hot-or-not/{}/{}", post.canister_id, post.post_id))
-            .unwrap_or_default()
+        format!(
+            "{}/hot-or-not/{}/{}",
+            get_base_url(),
+            post.canister_id,
+            post.post_id
+        )
     };
 
     let post_details_share = post.clone();
@@ -136,71 +258,132 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
     let share = move || {
         let post_details = post_details_share.clone();
         let url = video_url();
-        if share_url(&url).is_some() {
+        let fallback = move || {
+            _ = show_share.try_set(true);
+            ShareVideo.send_event(post_details, canisters);
+        };
+        // Sharing must start synchronously with the click, see [share_url]
+        let Some(sharing) = share_url(&url) else {
+            fallback();
             return;
-        }
-        show_share.set(true);
-        ShareVideo.send_event(post_details, canisters);
+        };
+        spawn_local(async move {
+            if sharing.await.needs_fallback() {
+                fallback();
+            }
+        });
     };
 
     let profile_url = format!("/profile/{}", post.poster_principal.to_text());
+    let poster_fallback_propic =
+        fallback_propic_url(&avatar_seed(post.poster_principal), AvatarStyle::default());
     let post_c = post.clone();
+    let follow_status = create_rw_signal(None);
 
+    let toasts = Toasts::get();
     let click_copy = move |text: String| {
-        _ = copy_to_clipboard(&text);
-        show_copied_popup.set(true);
-        Timeout::new(1200, move || show_copied_popup.set(false)).forget();
+        // Context isn't available once the task is suspended
+        let (copied_msg, failed_msg) = (t("overlay.link_copied"), t("overlay.link_copy_failed"));
+        let copying = copy_to_clipboard_checked(&text);
+        spawn_local(async move {
+            if copying.await.is_some() {
+                toasts.success(copied_msg);
+            } else {
+                toasts.error(failed_msg);
+            }
+        });
     };
 
+    let (is_connected, _) = account_connected_reader();
+    let show_login = create_rw_signal(false);
+    let open_report = move || {
+        // Reports are tied to the reporter's account
+        if is_connected.get_untracked() {
+            show_report.set(true);
+        } else {
+            show_login.set(true);
+        }
+    };
+
+    let report_key = (post.canister_id, post.post_id);
+    let (reported_posts, set_reported_posts, _) = use_reported_posts_store();
+    let already_reported = move || reported_posts.with(|r| r.contains(&report_key));
+
     let post_details_report = post.clone();
     let click_report = create_action(move |()| {
-        #[cfg(feature = "ga4")]
-        {
-            use crate::utils::report::send_report_offchain;
+        let post_details = post_details_report.clone();
+        let user_details = UserDetails::try_get_from_canister_store(canisters_copy);
+        let reason = report_option.get_untracked();
+        let url = video_url();
+        let thanks_msg = t("overlay.report_thanks");
 
-            let post_details = post_details_report.clone();
-            let user_details = UserDetails::try_get_from_canister_store(canisters_copy).unwrap();
+        async move {
+            #[cfg(feature = "ga4")]
+            {
+                use crate::utils::report::send_report_offchain;
 
-            spawn_local(async move {
-                send_report_offchain(
+                let Some(user_details) = user_details else {
+                    log::warn!("Trying to report a post without auth");
+                    return;
+                };
+                let res = send_report_offchain(
                     user_details.details.principal.to_string(),
                     post_details.poster_principal.to_string(),
                     post_details.canister_id.to_string(),
                     post_details.post_id.to_string(),
                     post_details.uid,
-                    report_option.get_untracked(),
-                    video_url(),
+                    reason,
+                    url,
                 )
-                .await
-                .unwrap();
-            });
-        }
+                .await;
+                if let Err(e) = res {
+                    log::warn!("failed to report post: {e}");
+                    return;
+                }
+            }
+            #[cfg(not(feature = "ga4"))]
+            {
+                _ = (post_details, user_details, reason, url);
+            }
 
-        async move {
-            show_report.set(false);
+            set_reported_posts.update(|r| r.push(report_key));
+            _ = show_report.try_set(false);
+            toasts.success(thanks_msg);
         }
     });
+    let report_pending = click_report.pending();
 
     view! {
         <div class="flex flex-col pointer-events-none flex-nowrap h-full justify-between pt-5 pb-20 px-2 md:px-6 w-full text-white absolute bottom-0 left-0 bg-transparent z-[4]">
             <div class="flex pointer-events-auto flex-row gap-2 w-9/12 rounded-s-full bg-gradient-to-r from-black/25 via-80% via-black/10 items-center p-2">
                 <div class="w-fit flex">
                     <a
-                        href=profile_url
+                        href=profile_url.clone()
                         class="w-10 md:w-12 h-10 md:h-12 overflow-clip rounded-full border-primary-600 border-2"
                     >
-                        <img class="h-full w-full object-cover" src=post.propic_url/>
+                        <Avatar class="h-full" src=post.propic_url fallback=poster_fallback_propic/>
                     </a>
                 </div>
                 <div class="flex flex-col justify-center min-w-0">
                     <div class="flex flex-row text-xs md:text-sm lg:text-base gap-1">
-                        <span class="font-semibold truncate">
-                            {post.display_name}
-                        </span>
+                        <CopyOnHold
+                            class="min-w-0 truncate"
+                            text=post.poster_principal.to_text()
+                            copied_message=t("overlay.principal_copied")
+                        >
+                            <a href=profile_url class="font-semibold">
+                                {post.display_name}
+                            </a>
+                        </CopyOnHold>
+                        <FollowButton
+                            creator_canister=post.canister_id
+                            creator_principal=post.poster_principal
+                            status=follow_status
+                        />
                         <span class="font-semibold">"|"</span>
                         <span class="flex flex-row gap-1 items-center">
                             <Icon class="text-sm md:text-base lg:text-lg" icon=icondata::AiEyeOutlined/>
-                            {post.views}
+                            {format_count(post.views)}
                         </span>
                     </div>
                     <ExpandableText description=post.description/>
@@ -208,13 +391,18 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
             </div>
             <div class="flex flex-col gap-2 w-full">
                 <div class="flex flex-col pointer-events-auto gap-6 self-end items-end text-2xl md:text-3xl lg:text-4xl">
-                    <button on:click=move |_| show_report.set(true)>
+                    <MuteToggle/>
+                    <button
+                        on:click=move |_| open_report()
+                        disabled=already_reported
+                        class="disabled:opacity-50"
+                    >
                         <Icon class="drop-shadow-lg" icon=icondata::TbMessageReport/>
                     </button>
                     <a href="/refer-earn">
                         <Icon class="drop-shadow-lg" icon=icondata::AiGiftFilled/>
                     </a>
-                    <LikeAndAuthCanLoader post=post_c.clone() />
+                    <LikeAndAuthCanLoader post=post_c.clone() follow_status />
                     <button on:click=move |_| share()>
                         <Icon class="drop-shadow-lg" icon=HomeFeedShareIcon />
                     </button>
@@ -225,30 +413,33 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
             </div>
         </div>
         <Modal show=show_share>
-            <div class="flex flex-col justify-center items-center gap-4 text-white">
-                <span class="text-lg">Share</span>
-                <div class="flex flex-row w-full gap-2">
-                    <p class="text-md max-w-full bg-white/10 rounded-full p-2 overflow-x-scroll whitespace-nowrap">
+            <div class="flex flex-col justify-center items-center gap-4 text-white w-full">
+                <span class="text-lg">{t("overlay.share")}</span>
+                {move || view! { <ShareTargets url=video_url()/> }}
+                <div class="flex flex-row w-full gap-2 items-center">
+                    <p class="text-sm md:text-md min-w-0 flex-1 bg-white/10 rounded-full p-2 overflow-x-scroll whitespace-nowrap">
                         {video_url}
                     </p>
-                    <button on:click=move |_| click_copy(video_url())>
+                    <button class="shrink-0" on:click=move |_| click_copy(video_url())>
                         <Icon class="text-xl" icon=icondata::FaCopyRegular/>
                     </button>
                 </div>
+                <button
+                    class="flex flex-row gap-2 items-center text-sm md:text-md text-white/70"
+                    on:click=move |_| show_qr.update(|s| *s = !*s)
+                >
+                    <Icon icon=icondata::AiQrcodeOutlined/>
+                    {move || if show_qr() { t("overlay.hide_qr") } else { t("overlay.show_qr") }}
+                </button>
+                <Show when=show_qr>
+                    <QrCode data=Signal::derive(video_url)/>
+                </Show>
             </div>
-
-            <Show when=show_copied_popup>
-                <div class="flex flex-col justify-center items-center">
-                    <span class="absolute mt-80 flex flex-row justify-center items-center bg-white/90 rounded-md h-10 w-28 text-center shadow-lg">
-                        <p>Link Copied!</p>
-                    </span>
-                </div>
-            </Show>
         </Modal>
         <Modal show=show_report>
             <div class="flex flex-col justify-center items-center gap-4 text-white">
-                <span class="text-lg">Report Post</span>
-                <span class="text-lg">Please select a reason:</span>
+                <span class="text-lg">{t("overlay.report_post")}</span>
+                <span class="text-lg">{t("overlay.report_reason")}</span>
                 <div class="max-w-full text-md text-black">
                     <select
                         class="p-2 w-full block rounded-lg text-sm"
@@ -280,11 +471,24 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
                         />
                     </select>
                 </div>
-                <button on:click=move |_| click_report.dispatch(())>
-                    <div class="rounded-lg bg-pink-500 p-1">Submit</div>
+                <button
+                    on:click=move |_| click_report.dispatch(())
+                    disabled=move || report_pending() || already_reported()
+                    class="disabled:opacity-50"
+                >
+                    <div class="rounded-lg bg-pink-500 p-1">
+                        {move || {
+                            if report_pending() {
+                                t("overlay.report_submitting")
+                            } else {
+                                t("overlay.report_submit")
+                            }
+                        }}
+                    </div>
                 </button>
             </div>
         </Modal>
+        <LoginModal show=show_login/>
     }
 }
 
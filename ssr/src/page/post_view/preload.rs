@@ -0,0 +1,84 @@
+//! Warms the browser cache with the media of upcoming posts
+//! so that swiping to them doesn't stall
+use std::collections::VecDeque;
+
+use candid::Principal;
+use leptos::*;
+
+use crate::{
+    canister::utils::{bg_url, stream_url},
+    consts::{VIDEO_PRELOAD_CACHE_SIZE, VIDEO_PRELOAD_WINDOW},
+    state::{cloudflare::StreamVariant, local_storage::use_data_saver_store},
+    utils::{posts::PostDetails, web::connection_save_data},
+};
+
+type PostKey = (Principal, u64);
+
+/// Recently preloaded posts, least recently used first
+#[derive(Default)]
+struct PreloadLru(VecDeque<PostKey>);
+
+impl PreloadLru {
+    /// Mark `key` as used
+    /// returns true if it wasn't already preloaded
+    fn touch(&mut self, key: PostKey) -> bool {
+        let existing = self.0.iter().position(|k| *k == key);
+        if let Some(idx) = existing {
+            self.0.remove(idx);
+        } else if self.0.len() >= VIDEO_PRELOAD_CACHE_SIZE {
+            self.0.pop_front();
+        }
+        self.0.push_back(key);
+        existing.is_none()
+    }
+}
+
+async fn preload_url(url: String) {
+    #[cfg(feature = "hydrate")]
+    {
+        use gloo::net::http::Request;
+        use web_sys::RequestMode;
+
+        // Opaque responses are enough to populate the HTTP cache
+        if let Err(e) = Request::get(&url).mode(RequestMode::NoCors).send().await {
+            log::debug!("failed to preload {url}: {e}");
+        }
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        _ = url;
+    }
+}
+
+fn preload_post(post: &PostDetails, variant: StreamVariant) {
+    let urls = [stream_url(&post.uid, variant), bg_url(&post.uid)];
+    for url in urls.into_iter().flatten() {
+        spawn_local(preload_url(url));
+    }
+}
+
+/// Preload the stream manifest and thumbnail of the
+/// [VIDEO_PRELOAD_WINDOW] posts after `current_idx`
+/// paused when the user or the browser asks to save data
+pub fn use_video_preload(video_queue: RwSignal<Vec<PostDetails>>, current_idx: RwSignal<usize>) {
+    let (data_saver, _, _) = use_data_saver_store();
+    let preloaded = store_value(PreloadLru::default());
+    let variant = StreamVariant::get();
+
+    create_effect(move |_| {
+        let idx = current_idx();
+        if data_saver.get_untracked() || connection_save_data() {
+            return;
+        }
+        video_queue.with(|q| {
+            for post in q.iter().skip(idx + 1).take(VIDEO_PRELOAD_WINDOW) {
+                let fresh = preloaded
+                    .try_update_value(|p| p.touch((post.canister_id, post.post_id)))
+                    .unwrap_or_default();
+                if fresh {
+                    preload_post(post, variant);
+                }
+            }
+        });
+    });
+}
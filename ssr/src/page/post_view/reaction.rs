@@ -0,0 +1,492 @@
+use candid::Principal;
+use gloo::timers::{callback::Timeout, future::TimeoutFuture};
+use leptos::{ev::MouseEvent, *};
+use wasm_bindgen::JsCast;
+use web_time::{Duration, Instant};
+
+use crate::{
+    consts::{LIKE_SYNC_MAX_ATTEMPTS, LIKE_SYNC_RETRY_BACKOFF_MS, UNLIKE_GRACE_PERIOD_MS},
+    state::{
+        app_events::{AppEvent, AppEvents},
+        canisters::Canisters,
+        like_queue::{LikeQueue, PendingLike},
+    },
+    utils::{
+        call_limit::CallPriority,
+        ic::{canister_call, CallError},
+    },
+};
+
+/// Duration (in milliseconds) for which like errors are shown
+const LIKE_ERROR_DISPLAY_MS: u32 = 2500;
+
+/// Maximum interval (in milliseconds) between the taps of a double tap
+const DOUBLE_TAP_INTERVAL_MS: f64 = 300.0;
+
+/// Duration (in milliseconds) of the double tap heart animation
+const DOUBLE_TAP_BURST_MS: u32 = 800;
+
+/// Like status of a post, synced with the canister
+///
+/// The UI state always reflects the latest intent of the user,
+/// canister calls are serialized and only made until the canister state
+/// matches the latest intent, so superseded toggles are never sent
+/// Un-likes are additionally held for [UNLIKE_GRACE_PERIOD_MS] after the latest toggle
+/// Transient failures are retried with exponential backoff, once
+/// [LIKE_SYNC_MAX_ATTEMPTS] are exhausted the intent is queued in [LikeQueue]
+/// (the UI keeps the optimistic state) and synced once back online
+/// Confirmed states are shared with other views of the same post via [AppEvents]
+#[derive(Clone, Copy)]
+pub struct LikeSync {
+    /// Latest intended state, `None` while loading
+    pub liked: RwSignal<Option<bool>>,
+    pub likes: RwSignal<u64>,
+    /// Message for the last failed sync, cleared after a while
+    pub error: RwSignal<Option<&'static str>>,
+    /// Last state confirmed by the canister
+    committed: StoredValue<Option<bool>>,
+    syncing: StoredValue<bool>,
+    /// Un-likes aren't sent before this, re-armed by every un-like
+    unlike_grace_until: StoredValue<Option<Instant>>,
+    events: AppEvents,
+    queue: Option<LikeQueue>,
+    post_canister: Principal,
+    post_id: u64,
+}
+
+impl LikeSync {
+    pub fn new(post_canister: Principal, post_id: u64, likes: u64) -> Self {
+        let this = Self {
+            liked: create_rw_signal(None),
+            likes: create_rw_signal(likes),
+            error: create_rw_signal(None),
+            committed: store_value(None),
+            syncing: store_value(false),
+            unlike_grace_until: store_value(None),
+            events: AppEvents::get(),
+            queue: LikeQueue::get(),
+            post_canister,
+            post_id,
+        };
+        this.events.subscribe(move |ev| {
+            let &AppEvent::Liked {
+                canister_id,
+                post_id,
+                liked,
+                likes,
+            } = ev
+            else {
+                return;
+            };
+            // Our own syncs take precedence
+            if canister_id != this.post_canister
+                || post_id != this.post_id
+                || this.syncing.get_value()
+            {
+                return;
+            }
+            this.set_initial(liked, likes);
+        });
+
+        this
+    }
+
+    /// Set the state as known by the canister
+    /// a queued intent for the post is shown on top of it
+    pub fn set_initial(&self, liked: bool, likes: u64) {
+        self.committed.set_value(Some(liked));
+        batch(|| {
+            self.likes.set(likes);
+            self.liked.set(Some(liked));
+        });
+        if let Some(pending) = self.queued() {
+            self.set_intent(pending);
+        }
+    }
+
+    fn queued(&self) -> Option<bool> {
+        self.queue?.pending(self.post_canister, self.post_id)
+    }
+
+    /// Whether the latest intent is waiting to be synced
+    pub fn pending_sync(&self) -> bool {
+        self.queued().is_some()
+    }
+
+    fn set_intent(&self, liked: bool) {
+        let Some(current) = self.liked.get_untracked() else {
+            return;
+        };
+        if current == liked {
+            return;
+        }
+        batch(|| {
+            self.likes.update(|l| *l = likes_after(*l, liked));
+            self.liked.set(Some(liked));
+        });
+    }
+
+    /// Toggle the like status
+    /// returns the new intended state
+    pub fn toggle(&self, canisters: Canisters<true>) -> Option<bool> {
+        let liked = !self.liked.get_untracked()?;
+        self.set_intent(liked);
+        if !liked {
+            let grace = Duration::from_millis(UNLIKE_GRACE_PERIOD_MS.into());
+            self.unlike_grace_until
+                .set_value(Some(Instant::now() + grace));
+        }
+        // The new intent supersedes the queued one
+        if let Some(queue) = self.queue {
+            queue.remove(self.post_canister, self.post_id);
+        }
+
+        // An in-flight sync will pick up the latest intent
+        if !self.syncing.get_value() {
+            self.syncing.set_value(true);
+            let this = *self;
+            spawn_local(async move {
+                this.sync(canisters).await;
+                _ = this.syncing.try_set_value(false);
+            });
+        }
+
+        Some(liked)
+    }
+
+    async fn sync(self, canisters: Canisters<true>) {
+        let individual = match canisters.individual_user(self.post_canister).await {
+            Ok(individual) => individual,
+            Err(e) => {
+                self.fail(e.into());
+                return;
+            }
+        };
+
+        let mut attempt = 1;
+        loop {
+            // Component was unmounted
+            let Some(Some(intended)) = self.liked.try_get_untracked() else {
+                return;
+            };
+            if !needs_toggle(intended, self.committed.try_get_value().flatten()) {
+                self.events.publish(AppEvent::Liked {
+                    canister_id: self.post_canister,
+                    post_id: self.post_id,
+                    liked: intended,
+                    likes: self.likes.get_untracked(),
+                });
+                return;
+            }
+            // Re-liking within the grace period makes the sync a no-op
+            if !intended && self.wait_unlike_grace().await {
+                continue;
+            }
+
+            match canister_call(
+                CallPriority::User,
+                individual.update_post_toggle_like_status_by_caller(self.post_id),
+            )
+            .await
+            {
+                Ok(liked) => _ = self.committed.try_set_value(Some(liked)),
+                Err(CallError::Replica(e)) if attempt < LIKE_SYNC_MAX_ATTEMPTS => {
+                    log::warn!("Error toggling like status (attempt {attempt}): {e}");
+                    TimeoutFuture::new(LIKE_SYNC_RETRY_BACKOFF_MS << (attempt - 1)).await;
+                    attempt += 1;
+                    // The failed toggle may have gone through,
+                    // so the canister state must be known before toggling again
+                    match canister_call(
+                        CallPriority::User,
+                        individual.get_individual_post_details_by_id(self.post_id),
+                    )
+                    .await
+                    {
+                        Ok(post) => _ = self.committed.try_set_value(Some(post.liked_by_me)),
+                        Err(e) => {
+                            self.fail(e);
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    self.fail(e);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Wait for the grace period of the latest un-like to pass
+    /// returns whether it had to wait, the intent may have changed in between
+    async fn wait_unlike_grace(&self) -> bool {
+        let mut waited = false;
+        // Un-likes made while waiting push the deadline further
+        while let Some(Some(until)) = self.unlike_grace_until.try_get_value() {
+            let now = Instant::now();
+            if now >= until {
+                break;
+            }
+            TimeoutFuture::new((until - now).as_millis() as u32 + 1).await;
+            waited = true;
+        }
+        waited
+    }
+
+    fn fail(&self, e: CallError) {
+        log::warn!("Error toggling like status: {e}");
+        if let (CallError::Replica(_), Some(queue), Some(Some(liked))) =
+            (&e, self.queue, self.liked.try_get_untracked())
+        {
+            queue.enqueue(PendingLike {
+                canister_id: self.post_canister,
+                post_id: self.post_id,
+                liked,
+            });
+            return;
+        }
+        self.revert();
+        if self
+            .error
+            .try_set(Some(e.user_message("This post is no longer available")))
+            .is_some()
+        {
+            return;
+        }
+        let error = self.error;
+        spawn_local(async move {
+            TimeoutFuture::new(LIKE_ERROR_DISPLAY_MS).await;
+            _ = error.try_set(None);
+        });
+    }
+
+    /// Reconcile the UI state with the canister state
+    fn revert(&self) {
+        if let Some(Some(committed)) = self.committed.try_get_value() {
+            self.set_intent(committed);
+        }
+    }
+}
+
+/// Like count after switching to `liked`
+fn likes_after(likes: u64, liked: bool) -> u64 {
+    if liked {
+        likes.saturating_add(1)
+    } else {
+        likes.saturating_sub(1)
+    }
+}
+
+/// Whether the canister must be toggled to reach the intended state
+/// an unknown canister state is toggled, the response confirms the result
+fn needs_toggle(intended: bool, committed: Option<bool>) -> bool {
+    committed != Some(intended)
+}
+
+/// Position of a double tap, relative to the post
+#[derive(Clone, Copy, PartialEq)]
+pub struct TapBurst {
+    pub x: f64,
+    pub y: f64,
+    /// Distinguishes consecutive bursts at the same position
+    seq: u64,
+}
+
+/// Double tap anywhere on a post to like it
+/// provided by the post container, [LikeSync] consumers like the post
+/// (never unlike) on every [TapBurst]
+#[derive(Clone, Copy)]
+pub struct DoubleTapLike {
+    last_tap_ms: StoredValue<Option<f64>>,
+    pub burst: RwSignal<Option<TapBurst>>,
+}
+
+impl Default for DoubleTapLike {
+    fn default() -> Self {
+        Self {
+            last_tap_ms: store_value(None),
+            burst: create_rw_signal(None),
+        }
+    }
+}
+
+impl DoubleTapLike {
+    /// Must be attached to the click events of the post container
+    pub fn on_tap(&self, ev: &MouseEvent) {
+        // Taps on controls (including the synthetic click on the mute checkbox)
+        // are not taps on the post
+        let on_control = ev
+            .target()
+            .and_then(|t| t.dyn_into::<leptos::web_sys::Element>().ok())
+            .and_then(|el| el.closest("button, a, input, select").ok().flatten())
+            .is_some();
+        if on_control {
+            return;
+        }
+
+        let now = ev.time_stamp();
+        let is_double = self
+            .last_tap_ms
+            .get_value()
+            .is_some_and(|prev| now - prev <= DOUBLE_TAP_INTERVAL_MS);
+        if !is_double {
+            self.last_tap_ms.set_value(Some(now));
+            return;
+        }
+        // A triple tap is a double tap followed by a single tap
+        self.last_tap_ms.set_value(None);
+
+        let Some(container) = ev
+            .current_target()
+            .and_then(|t| t.dyn_into::<leptos::web_sys::Element>().ok())
+        else {
+            return;
+        };
+        let rect = container.get_bounding_client_rect();
+        let seq = self.burst.with_untracked(|b| b.map_or(0, |b| b.seq + 1));
+        self.burst.set(Some(TapBurst {
+            x: ev.client_x() as f64 - rect.left(),
+            y: ev.client_y() as f64 - rect.top(),
+            seq,
+        }));
+    }
+}
+
+/// Heart animation at the position of the last double tap
+#[component]
+pub fn DoubleTapHeart(tap: DoubleTapLike) -> impl IntoView {
+    create_effect(move |_| {
+        let Some(burst) = tap.burst.get() else {
+            return;
+        };
+        Timeout::new(DOUBLE_TAP_BURST_MS, move || {
+            // Only clear if no newer burst replaced this one
+            if tap.burst.try_get_untracked().flatten() == Some(burst) {
+                _ = tap.burst.try_set(None);
+            }
+        })
+        .forget();
+    });
+
+    view! {
+        {move || {
+            tap.burst
+                .get()
+                .map(|burst| {
+                    view! {
+                        <img
+                            src="/img/heart-icon-liked.svg"
+                            class="absolute w-24 h-24 -translate-x-1/2 -translate-y-1/2 pointer-events-none z-[5] animate-ping"
+                            style:left=format!("{}px", burst.x)
+                            style:top=format!("{}px", burst.y)
+                        />
+                    }
+                })
+        }}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{likes_after, needs_toggle};
+
+    /// Sync loop of [super::LikeSync] against a fake canister,
+    /// taps made while a call is in flight are picked up by the next iteration
+    struct Sim {
+        liked: bool,
+        likes: u64,
+        canister: bool,
+        calls: usize,
+    }
+
+    impl Sim {
+        fn new(liked: bool, likes: u64) -> Self {
+            Self {
+                liked,
+                likes,
+                canister: liked,
+                calls: 0,
+            }
+        }
+
+        fn tap(&mut self) {
+            self.liked = !self.liked;
+            self.likes = likes_after(self.likes, self.liked);
+        }
+
+        /// Run one iteration of the sync loop, returns whether it's done
+        fn sync_step(&mut self) -> bool {
+            if !needs_toggle(self.liked, Some(self.canister)) {
+                return true;
+            }
+            self.canister = !self.canister;
+            self.calls += 1;
+            false
+        }
+
+        fn sync(&mut self) {
+            while !self.sync_step() {}
+        }
+    }
+
+    #[test]
+    fn superseded_toggles_are_not_sent() {
+        let mut sim = Sim::new(false, 10);
+        for _ in 0..6 {
+            sim.tap();
+        }
+        sim.sync();
+        assert_eq!(sim.calls, 0);
+        assert!(!sim.canister);
+        assert_eq!(sim.likes, 10);
+    }
+
+    #[test]
+    fn alternating_rapid_toggles_settle_on_latest_intent() {
+        let mut sim = Sim::new(false, 10);
+        // Taps between the calls of a single sync
+        for taps in [1, 2, 3, 1, 4, 1, 2] {
+            for _ in 0..taps {
+                sim.tap();
+            }
+            if sim.sync_step() {
+                break;
+            }
+        }
+        sim.sync();
+
+        assert_eq!(sim.canister, sim.liked);
+        assert_eq!(sim.likes, 10 + sim.liked as u64);
+        // Every call was made for an intent that was current when sent
+        assert!(sim.calls <= 7, "{} calls", sim.calls);
+    }
+
+    #[test]
+    fn alternating_toggles_keep_count_consistent() {
+        let mut sim = Sim::new(true, 1);
+        for i in 0..101 {
+            sim.tap();
+            if i % 3 == 0 {
+                sim.sync_step();
+            }
+        }
+        sim.sync();
+        assert!(!sim.liked);
+        assert!(!sim.canister);
+        assert_eq!(sim.likes, 0);
+    }
+
+    #[test]
+    fn unknown_state_is_toggled() {
+        assert!(needs_toggle(true, None));
+        assert!(needs_toggle(false, None));
+        assert!(needs_toggle(true, Some(false)));
+        assert!(!needs_toggle(true, Some(true)));
+    }
+
+    #[test]
+    fn likes_never_underflow() {
+        assert_eq!(likes_after(0, false), 0);
+        assert_eq!(likes_after(u64::MAX, true), u64::MAX);
+    }
+}
@@ -3,18 +3,19 @@ use leptos::*;
 use leptos_router::*;
 use serde::{Deserialize, Serialize};
 
-use super::{overlay::VideoDetailsOverlay, video_loader::VideoView};
+use super::{
+    overlay::VideoDetailsOverlay,
+    reaction::{DoubleTapHeart, DoubleTapLike},
+    video_loader::VideoView,
+};
 use crate::{
     canister::utils::bg_url,
     component::{
         back_btn::go_back_or_fallback, scrolling_post_view::MuteIconOverlay,
         spinner::FullScreenSpinner,
     },
-    state::{
-        audio_state::AudioState,
-        canisters::{auth_canisters_store, unauth_canisters},
-    },
-    utils::posts::{get_post_uid, PostDetails},
+    state::audio_state::AudioState,
+    utils::posts::{get_post_details, PostDetails},
 };
 
 #[derive(Params, PartialEq, Clone, Copy)]
@@ -27,7 +28,7 @@ struct PostParams {
 enum PostFetchError {
     Invalid,
     Unavailable,
-    GetUid(String),
+    Fetch(String),
 }
 
 #[component]
@@ -37,16 +38,22 @@ fn SinglePostViewInner(post: PostDetails) -> impl IntoView {
         show_mute_icon,
         ..
     } = expect_context();
-    let bg_url = bg_url(&post.uid);
+    let bg_url = bg_url(&post.uid).map(|url| format!("url({url})")).ok();
+    let double_tap = DoubleTapLike::default();
+    provide_context(double_tap);
 
     view! {
         <div class="w-dvw h-dvh">
-            <div class="bg-transparent w-full h-full relative overflow-hidden">
+            <div
+                class="bg-transparent w-full h-full relative overflow-hidden"
+                on:click=move |ev| double_tap.on_tap(&ev)
+            >
                 <div
                     class="absolute top-0 left-0 bg-cover bg-center w-full h-full z-[1] blur-lg"
                     style:background-color="rgb(0, 0, 0)"
-                    style:background-image=format!("url({bg_url})")
+                    style:background-image=bg_url
                 />
+                <DoubleTapHeart tap=double_tap/>
                 <VideoDetailsOverlay post=post.clone()/>
                 <VideoView
                     post=Some(post)
@@ -76,17 +83,11 @@ fn UnavailablePost() -> impl IntoView {
 #[component]
 pub fn SinglePost() -> impl IntoView {
     let params = use_params::<PostParams>();
-    let auth_cans = auth_canisters_store();
     let fetch_post = create_resource(params, move |params| async move {
         let params = params.map_err(|_| PostFetchError::Invalid)?;
-        let post_uid = if let Some(canisters) = auth_cans.get_untracked() {
-            get_post_uid(&canisters, params.canister_id, params.post_id).await
-        } else {
-            let canisters = unauth_canisters();
-            get_post_uid(&canisters, params.canister_id, params.post_id).await
-        };
-        post_uid
-            .map_err(|e| PostFetchError::GetUid(e.to_string()))
+        get_post_details(params.canister_id, params.post_id)
+            .await
+            .map_err(|e| PostFetchError::Fetch(e.to_string()))
             .and_then(|post| post.ok_or(PostFetchError::Unavailable))
     });
 
@@ -102,7 +103,7 @@ pub fn SinglePost() -> impl IntoView {
             Err(PostFetchError::Unavailable) => view! {
                 <UnavailablePost/>
             },
-            Err(PostFetchError::GetUid(e)) => view! {
+            Err(PostFetchError::Fetch(e)) => view! {
                 <Redirect path=format!("/error?err={e}")/>
             }
         })}
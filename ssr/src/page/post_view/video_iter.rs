@@ -10,7 +10,10 @@ use crate::{
     canister::post_cache::{self, NsfwFilter},
     consts::USER_CANISTER_ID_STORE,
     state::canisters::{auth_canisters_store, Canisters},
-    utils::posts::{get_post_uid, FetchCursor, PostDetails, PostViewError},
+    utils::{
+        call_limit::{limit_call, CallPriority},
+        posts::{get_post_uid, FetchCursor, PostDetails, PostViewError},
+    },
 };
 
 pub async fn post_liked_by_me(
@@ -19,12 +22,29 @@ pub async fn post_liked_by_me(
     post_id: u64,
 ) -> Result<(bool, u64), PostViewError> {
     let individual = canisters.individual_user(post_canister).await?;
-    let post = individual
-        .get_individual_post_details_by_id(post_id)
-        .await?;
+    let post = limit_call(
+        CallPriority::Background,
+        individual.get_individual_post_details_by_id(post_id),
+    )
+    .await?;
     Ok((post.liked_by_me, post.like_count))
 }
 
+/// Liked status (liked, like count) of many posts, in the order of `posts`
+/// the individual user canister has no batch query for post details,
+/// so posts are queried concurrently (bounded by the global call limit)
+pub async fn posts_liked_by_me(
+    canisters: &Canisters<true>,
+    posts: &[(Principal, u64)],
+) -> Vec<Result<(bool, u64), PostViewError>> {
+    posts
+        .iter()
+        .map(|&(post_canister, post_id)| post_liked_by_me(canisters, post_canister, post_id))
+        .collect::<FuturesOrdered<_>>()
+        .collect()
+        .await
+}
+
 type PostsStream<'a> = Pin<Box<dyn Stream<Item = Vec<Result<PostDetails, PostViewError>>> + 'a>>;
 
 #[derive(Debug, Eq, PartialEq)]
@@ -106,6 +126,7 @@ impl<'a, const AUTH: bool> VideoFetchStream<'a, AUTH> {
     ) -> Result<FetchVideosRes<'a>, PostViewError> {
         #[cfg(feature = "hydrate")]
         {
+            use crate::state::auth::anonymous_browse;
             use crate::utils::ml_feed::ml_feed_grpcweb::MLFeed;
             use leptos::expect_context;
 
@@ -116,6 +137,9 @@ impl<'a, const AUTH: bool> VideoFetchStream<'a, AUTH> {
             let user_canister_id;
             if let Some(canister_id) = user_canister_id_local_storage.get_untracked() {
                 user_canister_id = canister_id;
+            } else if anonymous_browse().is_active_untracked() {
+                // Anonymous users don't have a canister yet
+                user_canister_id = Principal::anonymous();
             } else {
                 let cans_store = auth_canisters_store();
                 let mut cans_stream = cans_store.to_stream();
@@ -9,14 +9,18 @@ use crate::{
         individual_user_template::PostViewDetailsFromFrontend,
         utils::{bg_url, mp4_url},
     },
-    component::{feed_popup::FeedPopUp, video_player::VideoPlayer},
+    component::{feed_popup::FeedPopUp, seek_bar::SeekBar, video_player::VideoPlayer},
     state::{
-        auth::account_connected_reader, canisters::unauth_canisters,
+        auth::account_connected_reader, canisters::unauth_canisters, cloudflare::StreamVariant,
         local_storage::use_referrer_store,
     },
 };
 
-use super::{overlay::VideoDetailsOverlay, PostDetails};
+use super::{
+    overlay::VideoDetailsOverlay,
+    reaction::{DoubleTapHeart, DoubleTapLike},
+    PostDetails,
+};
 
 #[component]
 pub fn BgView(
@@ -33,6 +37,8 @@ pub fn BgView(
 
     let (show_refer_login_popup, set_show_refer_login_popup) = create_signal(true);
     let (referrer_store, _, _) = use_referrer_store();
+    let double_tap = DoubleTapLike::default();
+    provide_context(double_tap);
 
     create_effect(move |_| {
         if current_idx.get() % 5 != 0 {
@@ -44,12 +50,16 @@ pub fn BgView(
     });
 
     view! {
-        <div class="bg-transparent w-full h-full relative overflow-hidden">
+        <div
+            class="bg-transparent w-full h-full relative overflow-hidden"
+            on:click=move |ev| double_tap.on_tap(&ev)
+        >
             <div
                 class="absolute top-0 left-0 bg-cover bg-center w-full h-full z-[1] blur-lg"
                 style:background-color="rgb(0, 0, 0)"
-                style:background-image=move || format!("url({})", bg_url(uid()))
+                style:background-image=move || bg_url(uid()).map(|url| format!("url({url})")).ok()
             ></div>
+            <DoubleTapHeart tap=double_tap/>
             <Show when=move || {
                 current_idx.get() != 0 && current_idx.get() % 5 == 0 && !is_connected.get()
                     && show_login_popup.get()
@@ -89,8 +99,11 @@ pub fn VideoView(
 ) -> impl IntoView {
     let post_for_uid = post.clone();
     let uid = create_memo(move |_| post_for_uid.with(|p| p.as_ref().map(|p| p.uid.clone())));
-    let view_bg_url = move || uid().map(bg_url);
-    let view_video_url = move || uid().map(mp4_url);
+    let view_bg_url = move || uid().and_then(|uid| bg_url(uid).ok());
+    let variant = StreamVariant::get();
+    let view_video_url = move || uid().and_then(|uid| mp4_url(uid, variant).ok());
+    // Shown instead of a blank player if the post has no playable video
+    let unavailable = move || uid.with(Option::is_some) && view_video_url().is_none();
 
     // Handles mute/unmute
     create_effect(move |_| {
@@ -196,6 +209,12 @@ pub fn VideoView(
             view_bg_url=Signal::derive(view_bg_url)
             view_video_url=Signal::derive(view_video_url)
         />
+        <Show when=unavailable>
+            <div class="absolute inset-0 z-[4] flex items-center justify-center bg-black text-white/70 text-sm">
+                This video is unavailable
+            </div>
+        </Show>
+        <SeekBar video_ref=_ref uid/>
     }
 }
 
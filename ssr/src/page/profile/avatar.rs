@@ -0,0 +1,260 @@
+use candid::Principal;
+use leptos::{html::Input, *};
+use leptos_icons::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "hydrate")]
+use leptos_use::use_event_listener;
+
+use crate::{
+    auth::signature::RequestSignature,
+    canister::individual_user_template::{Result10, UserProfileUpdateDetailsFromFrontend},
+    state::{
+        canisters::Canisters, cloudflare::CloudflareConfig,
+        profile_details::invalidate_profile_details,
+    },
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AvatarUploadInfo {
+    pub id: String,
+    pub upload_url: String,
+}
+
+impl AvatarUploadInfo {
+    pub fn delivery_url(&self) -> String {
+        let config = CloudflareConfig::get();
+        format!("{}/{}/public", config.images_base, self.id)
+    }
+}
+
+/// One time upload url for the profile picture of `principal`
+#[server]
+pub async fn get_avatar_upload_info(
+    principal: Principal,
+    signature: RequestSignature,
+) -> Result<AvatarUploadInfo, ServerFnError> {
+    signature
+        .verify_for(principal, "get_avatar_upload_info", &principal)
+        .await?;
+    server_impl::get_avatar_upload_info_impl(principal).await
+}
+
+#[cfg(all(feature = "ssr", feature = "cloudflare"))]
+mod server_impl {
+    use candid::Principal;
+    use leptos::{expect_context, ServerFnError};
+    use serde::Deserialize;
+
+    use crate::{
+        state::cloudflare::{CloudflareConfig, CloudflareCredentials},
+        utils::http::http_client,
+    };
+
+    use super::AvatarUploadInfo;
+
+    #[derive(Deserialize)]
+    struct DirectUploadResult {
+        id: String,
+        #[serde(rename = "uploadURL")]
+        upload_url: String,
+    }
+
+    #[derive(Deserialize)]
+    struct DirectUploadRes {
+        result: DirectUploadResult,
+    }
+
+    pub async fn get_avatar_upload_info_impl(
+        _principal: Principal,
+    ) -> Result<AvatarUploadInfo, ServerFnError> {
+        let creds: CloudflareCredentials = expect_context();
+        let cf_config: CloudflareConfig = expect_context();
+        let url = cf_config.api_base.join(&format!(
+            "accounts/{}/images/v2/direct_upload",
+            creds.account_id
+        ))?;
+
+        let client = http_client();
+        let req = client.client().post(url).bearer_auth(&creds.token);
+        let res: DirectUploadRes = client.send(req).await?.json().await?;
+
+        Ok(AvatarUploadInfo {
+            id: res.result.id,
+            upload_url: res.result.upload_url,
+        })
+    }
+}
+
+#[cfg(all(feature = "ssr", not(feature = "cloudflare")))]
+mod server_impl {
+    use candid::Principal;
+    use leptos::ServerFnError;
+
+    use super::AvatarUploadInfo;
+
+    /// Nothing can be uploaded without cloudflare
+    /// failing keeps a placeholder URL from being saved as the profile picture
+    pub async fn get_avatar_upload_info_impl(
+        _principal: Principal,
+    ) -> Result<AvatarUploadInfo, ServerFnError> {
+        Err(ServerFnError::new(
+            "Profile pictures can't be uploaded in this build",
+        ))
+    }
+}
+
+async fn upload_avatar(info: &AvatarUploadInfo, file: &gloo::file::File) -> Result<(), String> {
+    #[cfg(all(feature = "hydrate", feature = "cloudflare"))]
+    {
+        use gloo::net::http::Request;
+        use leptos::web_sys::FormData;
+
+        let form = FormData::new().map_err(|_| "Failed to read the image".to_string())?;
+        form.append_with_blob("file", file.as_ref())
+            .map_err(|_| "Failed to read the image".to_string())?;
+        let res = Request::post(&info.upload_url)
+            .body(form)
+            .map_err(|e| e.to_string())?
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.ok() {
+            return Err(format!("Upload failed with status {}", res.status()));
+        }
+        Ok(())
+    }
+    // Nothing was uploaded, the delivery URL must not be saved
+    #[cfg(not(all(feature = "hydrate", feature = "cloudflare")))]
+    {
+        _ = (info, file);
+        Err("Profile pictures can't be uploaded in this build".into())
+    }
+}
+
+async fn set_avatar(
+    canisters: Canisters<true>,
+    display_name: Option<String>,
+    file: gloo::file::File,
+) -> Result<String, String> {
+    let principal = canisters.user_principal();
+    let signature =
+        RequestSignature::sign(canisters.identity(), "get_avatar_upload_info", &principal)
+            .map_err(|e| e.to_string())?;
+    let info = get_avatar_upload_info(principal, signature)
+        .await
+        .map_err(|e| e.to_string())?;
+    upload_avatar(&info, &file).await?;
+
+    let url = info.delivery_url();
+    let user = canisters
+        .authenticated_user()
+        .await
+        .map_err(|e| e.to_string())?;
+    let res = user
+        .update_profile_display_details(UserProfileUpdateDetailsFromFrontend {
+            profile_picture_url: Some(url.clone()),
+            display_name,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Result10::Err(e) = res {
+        return Err(format!("{e:?}"));
+    }
+
+    Ok(url)
+}
+
+/// Profile picture, editable by the owner of the profile
+/// the current picture stays visible while a new one uploads
+#[component]
+pub fn ProfilePicture(
+    #[prop(into)] src: String,
+    #[prop(into)] alt: String,
+    /// Canisters of the profile owner, if the viewer owns the profile
+    #[prop(into)]
+    own_canisters: Signal<Option<Canisters<true>>>,
+    display_name: Option<String>,
+) -> impl IntoView {
+    let src = create_rw_signal(src);
+    let error = create_rw_signal(None::<String>);
+    let file_ref = create_node_ref::<Input>();
+
+    let upload_action = create_action(move |file: &gloo::file::File| {
+        let file = file.clone();
+        let display_name = display_name.clone();
+        let canisters = own_canisters.get_untracked();
+        async move {
+            let Some(canisters) = canisters else {
+                return;
+            };
+            match set_avatar(canisters, display_name, file).await {
+                Ok(url) => {
+                    _ = src.try_set(url);
+                    invalidate_profile_details();
+                }
+                Err(e) => {
+                    log::warn!("failed to update profile picture: {e}");
+                    _ = error.try_set(Some(
+                        "Failed to update the picture, please try again".into(),
+                    ));
+                }
+            }
+        }
+    });
+    let uploading = upload_action.pending();
+
+    #[cfg(feature = "hydrate")]
+    {
+        _ = use_event_listener(file_ref, ev::change, move |ev| {
+            use wasm_bindgen::JsCast;
+            use web_sys::HtmlInputElement;
+
+            use crate::consts::MAX_AVATAR_SIZE_BYTES;
+            let Some(file) = ev
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0))
+            else {
+                return;
+            };
+            let file = gloo::file::File::from(file);
+            if file.size() > MAX_AVATAR_SIZE_BYTES {
+                error.set(Some(format!(
+                    "Picture must be smaller than {} MB",
+                    MAX_AVATAR_SIZE_BYTES / (1024 * 1024)
+                )));
+                return;
+            }
+            error.set(None);
+            upload_action.dispatch(file);
+        });
+    }
+
+    view! {
+        <div class="flex flex-col items-center gap-1">
+            <div class="relative h-24 w-24">
+                <img class="h-24 w-24 rounded-full object-cover" alt=alt src=src/>
+                <Show when=uploading>
+                    <div class="absolute inset-0 rounded-full bg-black/50 grid place-items-center">
+                        <div class="h-8 w-8 rounded-full border-2 border-white border-t-transparent animate-spin"></div>
+                    </div>
+                </Show>
+                <Show when=move || own_canisters.with(|c| c.is_some())>
+                    <label class="absolute bottom-0 right-0 p-1 rounded-full bg-primary-600 text-white cursor-pointer">
+                        <Icon icon=icondata::AiEditOutlined/>
+                        <input
+                            _ref=file_ref
+                            type="file"
+                            accept="image/*"
+                            class="sr-only"
+                            disabled=uploading
+                        />
+                    </label>
+                </Show>
+            </div>
+            {move || error().map(|e| view! { <span class="text-xs text-red-500">{e}</span> })}
+        </div>
+    }
+}
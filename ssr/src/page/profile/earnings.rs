@@ -0,0 +1,179 @@
+use leptos::*;
+use leptos_icons::*;
+use web_time::Duration;
+
+use crate::{
+    component::infinite_scroller::CursoredDataProvider,
+    page::wallet::txn::{provider::get_history_provider, TxnInfo},
+    state::canisters::Canisters,
+    utils::current_epoch,
+};
+
+/// Number of transactions fetched per page while aggregating
+const EARNINGS_PAGE_SIZE: usize = 50;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EarningsPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl EarningsPeriod {
+    const ALL: [Self; 3] = [Self::Day, Self::Week, Self::Month];
+
+    fn duration(self) -> Duration {
+        let days = match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+        };
+        Duration::from_secs(days * 24 * 60 * 60)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Day => "Day",
+            Self::Week => "Week",
+            Self::Month => "Month",
+        }
+    }
+}
+
+/// Creator earnings in the current and the previous period
+#[derive(Clone, Copy, Default)]
+struct PeriodEarnings {
+    current: u64,
+    previous: u64,
+}
+
+impl PeriodEarnings {
+    fn compute(earnings: &[TxnInfo], period: EarningsPeriod) -> Self {
+        let now = current_epoch();
+        let current_start = now.saturating_sub(period.duration());
+        let previous_start = current_start.saturating_sub(period.duration());
+
+        earnings.iter().fold(Self::default(), |mut acc, txn| {
+            if txn.timestamp >= current_start {
+                acc.current = acc.current.saturating_add(txn.amount);
+            } else if txn.timestamp >= previous_start {
+                acc.previous = acc.previous.saturating_add(txn.amount);
+            }
+            acc
+        })
+    }
+}
+
+#[component]
+fn EarningsTrend(earnings: PeriodEarnings) -> impl IntoView {
+    let PeriodEarnings { current, previous } = earnings;
+    let (icon, class) = match current.cmp(&previous) {
+        std::cmp::Ordering::Greater => (icondata::AiArrowUpOutlined, "text-green-600"),
+        std::cmp::Ordering::Less => (icondata::AiArrowDownOutlined, "text-red-600"),
+        std::cmp::Ordering::Equal => (icondata::AiMinusOutlined, "text-white/50"),
+    };
+
+    view! {
+        <div class=format!("flex flex-row gap-1 items-center text-sm {class}")>
+            <Icon icon/>
+            <span>{current.abs_diff(previous)} vs previous</span>
+        </div>
+    }
+}
+
+/// Commission earned by the creator over a selectable period
+/// only available for the authenticated user's own profile
+#[component]
+pub fn CreatorEarnings(canisters: Canisters<true>) -> impl IntoView {
+    let period = create_rw_signal(EarningsPeriod::Week);
+    let earnings = create_rw_signal(Vec::<TxnInfo>::new());
+    let loaded = create_rw_signal(false);
+
+    // Fetch enough history for the longest period and the one before it
+    // history is newest first, so stop at the first older transaction
+    let provider = get_history_provider(canisters);
+    spawn_local(async move {
+        let cutoff = current_epoch().saturating_sub(EarningsPeriod::Month.duration() * 2);
+        let mut cursor = 0;
+        loop {
+            let page = match provider
+                .get_by_cursor(cursor, cursor + EARNINGS_PAGE_SIZE)
+                .await
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    log::warn!("failed to fetch history for creator earnings: {e}");
+                    break;
+                }
+            };
+            let reached_cutoff = page.data.iter().any(|txn| txn.timestamp < cutoff);
+            // Component was unmounted
+            if earnings
+                .try_update(|earnings| {
+                    earnings.extend(
+                        page.data
+                            .into_iter()
+                            .filter(|txn| txn.tag.is_creator_earning() && txn.timestamp >= cutoff),
+                    )
+                })
+                .is_none()
+            {
+                return;
+            }
+            cursor += EARNINGS_PAGE_SIZE;
+            if page.end || reached_cutoff {
+                break;
+            }
+        }
+        _ = loaded.try_set(true);
+    });
+
+    let period_earnings =
+        create_memo(move |_| earnings.with(|e| PeriodEarnings::compute(e, period())));
+    let period_class = move |p: EarningsPeriod| {
+        if period() == p {
+            "px-3 py-1 rounded-full bg-primary-600 text-white"
+        } else {
+            "px-3 py-1 rounded-full bg-white/10 text-white/50"
+        }
+    };
+
+    view! {
+        <div class="flex flex-col w-11/12 sm:w-7/12 gap-3 p-4 rounded-xl bg-white/10 text-white">
+            <div class="flex flex-row w-full justify-between items-center">
+                <span class="text-md font-semibold">Creator Earnings</span>
+                <div class="flex flex-row gap-1 text-xs md:text-sm">
+                    {EarningsPeriod::ALL
+                        .into_iter()
+                        .map(|p| {
+                            view! {
+                                <button class=move || period_class(p) on:click=move |_| period.set(p)>
+                                    {p.label()}
+                                </button>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+            <Show
+                when=loaded
+                fallback=|| view! { <div class="w-1/3 rounded-full py-3 bg-white/30 animate-pulse"></div> }
+            >
+                <div class="flex flex-row w-full justify-between items-end">
+                    <Show
+                        when=move || period_earnings().current != 0
+                        fallback=|| view! { <span class="text-white/50">No earnings in this period</span> }
+                    >
+                        <span class="text-xl">{move || period_earnings().current} COYNs</span>
+                    </Show>
+                    <Show when=move || {
+                        let earnings = period_earnings();
+                        earnings.current != 0 || earnings.previous != 0
+                    }>
+                        <EarningsTrend earnings=period_earnings()/>
+                    </Show>
+                </div>
+            </Show>
+        </div>
+    }
+}
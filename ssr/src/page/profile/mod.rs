@@ -1,3 +1,5 @@
+mod avatar;
+mod earnings;
 mod ic;
 pub mod overlay;
 mod posts;
@@ -12,10 +14,16 @@ use leptos_router::*;
 
 use crate::{
     component::{back_btn::BackButton, connect::ConnectLogin, spinner::FullScreenSpinner},
-    state::{auth::account_connected_reader, canisters::unauth_canisters},
+    page::not_found::NotFound,
+    state::{
+        auth::account_connected_reader,
+        canisters::{auth_canisters_store, unauth_canisters},
+    },
     utils::{posts::PostDetails, profile::ProfileDetails},
 };
 
+use avatar::ProfilePicture;
+use earnings::CreatorEarnings;
 use posts::ProfilePosts;
 use speculation::ProfileSpeculations;
 
@@ -43,7 +51,7 @@ fn Stat(stat: u64, #[prop(into)] info: String) -> impl IntoView {
 }
 
 #[component]
-fn ListSwitcher(user_canister: Principal) -> impl IntoView {
+fn ListSwitcher(user_canister: Principal, user_principal: Principal) -> impl IntoView {
     let (cur_tab, set_cur_tab) = create_query_signal::<String>("tab");
     let current_tab = create_memo(move |_| {
         with!(|cur_tab| match cur_tab.as_deref() {
@@ -77,7 +85,7 @@ fn ListSwitcher(user_canister: Principal) -> impl IntoView {
                 when=move || current_tab() == 0
                 fallback=move || view! { <ProfileSpeculations user_canister /> }
             >
-                <ProfilePosts user_canister/>
+                <ProfilePosts user_canister user_principal/>
             </Show>
         </div>
     }
@@ -88,8 +96,15 @@ fn ProfileViewInner(user: ProfileDetails, user_canister: Principal) -> impl Into
     let username_or_principal = user.username_or_principal();
     let profile_pic = user.profile_pic_or_random();
     let display_name = user.display_name_or_fallback();
+    let current_display_name = user.display_name.clone();
     let earnings = user.lifetime_earnings;
     let (is_connected, _) = account_connected_reader();
+    let auth_cans = auth_canisters_store();
+    let own_canisters = Signal::derive(move || {
+        auth_cans
+            .get()
+            .filter(|cans| cans.user_canister() == user_canister)
+    });
 
     view! {
         <div class="min-h-screen bg-black text-white overflow-y-scroll pt-10 pb-12">
@@ -99,10 +114,11 @@ fn ProfileViewInner(user: ProfileDetails, user_canister: Principal) -> impl Into
             <div class="grid grid-cols-1 gap-5 justify-normal justify-items-center w-full">
                 <div class="flex flex-row w-11/12 sm:w-7/12 justify-center">
                     <div class="flex flex-col justify-center items-center">
-                        <img
-                            class="h-24 w-24 rounded-full"
-                            alt=username_or_principal.clone()
+                        <ProfilePicture
                             src=profile_pic
+                            alt=username_or_principal.clone()
+                            own_canisters
+                            display_name=current_display_name
                         />
                         <div class="flex flex-col text-center items-center">
                             <span
@@ -132,7 +148,10 @@ fn ProfileViewInner(user: ProfileDetails, user_canister: Principal) -> impl Into
                     <Stat stat=user.hots info="Hots"/>
                     <Stat stat=user.nots info="Nots"/>
                 </div>
-                <ListSwitcher user_canister/>
+                {move || {
+                    own_canisters().map(|canisters| view! { <CreatorEarnings canisters/> })
+                }}
+                <ListSwitcher user_canister user_principal=user.principal/>
             </div>
         </div>
     }
@@ -149,15 +168,21 @@ pub fn ProfileView() -> impl IntoView {
         })
     };
 
-    let user_details = create_resource(principal, |principal| async move {
+    // Blocking so that the not found status is set before streaming the response
+    let user_details = create_blocking_resource(principal, |principal| async move {
+        let Some(principal) = principal else {
+            return Ok(None);
+        };
         let canisters = unauth_canisters();
-        let user_canister = canisters
-            .get_individual_canister_by_user_principal(principal?)
-            .await
-            .ok()??;
-        let user = canisters.individual_user(user_canister).await.ok()?;
-        let user_details = user.get_profile_details().await.ok()?;
-        Some((user_details.into(), user_canister))
+        let Some(user_canister) = canisters
+            .get_individual_canister_by_user_principal(principal)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let user = canisters.individual_user(user_canister).await?;
+        let user_details = user.get_profile_details().await?;
+        Ok::<_, ServerFnError>(Some((user_details.into(), user_canister)))
     });
 
     let ProfilePostsContext {
@@ -180,12 +205,12 @@ pub fn ProfileView() -> impl IntoView {
                     .get()
                     .map(|user| {
                         view! {
-                            {move || {
-                                if let Some((user, user_canister)) = user.clone() {
+                            {move || match user.clone() {
+                                Ok(Some((user, user_canister))) => {
                                     view! { <ProfileViewInner user user_canister/> }
-                                } else {
-                                    view! { <Redirect path="/"/> }
                                 }
+                                Ok(None) => view! { <NotFound message="Profile not found"/> },
+                                Err(e) => view! { <Redirect path=format!("/error?err={e}")/> },
                             }}
                         }
                     })
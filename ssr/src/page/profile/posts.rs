@@ -5,8 +5,8 @@ use candid::Principal;
 
 use crate::{
     canister::utils::bg_url,
-    component::profile_placeholders::NoMorePostsGraphic,
-    state::canisters::{auth_canisters_store, unauth_canisters},
+    component::{profile_placeholders::NoMorePostsGraphic, thumbnail::Thumbnail},
+    state::canisters::auth_canisters_store,
     utils::{
         event_streaming::events::ProfileViewVideo, posts::PostDetails, profile::PostsProvider,
     },
@@ -17,7 +17,7 @@ use super::ProfilePostsContext;
 
 #[component]
 fn Post(details: PostDetails, user_canister: Principal, _ref: NodeRef<html::Div>) -> impl IntoView {
-    let image_error = create_rw_signal(false);
+    let thumbnail = bg_url(&details.uid).ok();
 
     let auth_canister = auth_canisters_store();
 
@@ -34,9 +34,6 @@ fn Post(details: PostDetails, user_canister: Principal, _ref: NodeRef<html::Div>
         }
     };
 
-    let handle_image_error =
-        move |_| _ = image_error.try_update(|image_error| *image_error = !*image_error);
-
     let canisters = auth_canisters_store();
     let post_details = details.clone();
     let video_click = move || {
@@ -45,26 +42,9 @@ fn Post(details: PostDetails, user_canister: Principal, _ref: NodeRef<html::Div>
 
     view! {
         <div _ref=_ref class="relative w-full basis-1/3 md:basis-1/4 xl:basis-1/5">
-            <div class="relative aspect-[9/16] h-full rounded-md border-white/20 m-2 border-[1px]">
+            <div class="relative aspect-[9/16] h-full rounded-md border-white/20 m-2 border-[1px] overflow-hidden">
                 <a class="h-full w-full" href=profile_post_url on:click=move |_| video_click()>
-                    <Show
-                        when=image_error
-                        fallback=move || {
-                            view! {
-                                <img
-                                    class="object-cover w-full h-full"
-                                    on:error=handle_image_error
-                                    src=bg_url(details.uid.clone())
-                                />
-                            }
-                        }
-                    >
-
-                        <div class="h-full flex text-center flex-col place-content-center items-center text-white">
-                            <Icon class="h-8 w-8" icon=icondata::TbCloudX/>
-                            <span class="text-md">Not Available</span>
-                        </div>
-                    </Show>
+                    <Thumbnail src=thumbnail class="h-full"/>
 
                     <div class="absolute bottom-1 left-1 grid grid-cols-2 items-center gap-1">
                         <Icon
@@ -87,14 +67,14 @@ fn Post(details: PostDetails, user_canister: Principal, _ref: NodeRef<html::Div>
 }
 
 #[component]
-pub fn ProfilePosts(user_canister: Principal) -> impl IntoView {
+pub fn ProfilePosts(user_canister: Principal, user_principal: Principal) -> impl IntoView {
     let ProfilePostsContext {
         video_queue,
         start_index,
         ..
     } = expect_context();
 
-    let provider = PostsProvider::new(unauth_canisters(), video_queue, start_index, user_canister);
+    let provider = PostsProvider::new(video_queue, start_index, user_principal);
 
     view! {
         <ProfileStream
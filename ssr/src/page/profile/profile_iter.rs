@@ -74,7 +74,9 @@ impl<const LIMIT: u64> ProfVideoStream<LIMIT> for ProfileVideoStream<LIMIT> {
                 let end = v.len() < LIMIT as usize;
                 let posts = v
                     .into_iter()
-                    .map(|details| PostDetails::from_canister_post(AUTH, user_canister, details))
+                    .filter_map(|details| {
+                        PostDetails::from_canister_post(AUTH, user_canister, details)
+                    })
                     .collect::<Vec<_>>();
                 Ok(PostsRes { posts, end })
             }
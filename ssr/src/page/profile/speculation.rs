@@ -8,7 +8,7 @@ use web_time::Duration;
 use super::ic::ProfileStream;
 use crate::{
     canister::utils::bg_url,
-    component::profile_placeholders::NoMoreBetsGraphic,
+    component::{profile_placeholders::NoMoreBetsGraphic, thumbnail::Thumbnail},
     state::canisters::unauth_canisters,
     utils::{
         posts::PostDetails,
@@ -45,17 +45,10 @@ pub fn ExternalUser(user: Option<ProfileDetails>) -> impl IntoView {
 
 #[component]
 pub fn ExternalPost(post: Option<PostDetails>) -> impl IntoView {
-    let bg_url = post.map(|p| bg_url(p.uid));
+    let bg_url = post.and_then(|p| bg_url(p.uid).ok());
     view! {
         <div class="absolute top-0 left-0 h-full w-full z-10 rounded-md overflow-clip">
-            {move || {
-                bg_url
-                    .clone()
-                    .map(|bgurl| {
-                        view! { <img class="object-cover h-full w-full" src=bgurl.clone()/> }
-                    })
-            }}
-
+            <Thumbnail src=bg_url class="h-full"/>
         </div>
     }
 }
@@ -159,11 +152,7 @@ pub fn Speculation(details: BetDetails, _ref: NodeRef<html::Div>) -> impl IntoVi
             let canister = unauth_canisters();
             let user = canister.individual_user(canister_id).await.ok()?;
             let post_details = user.get_individual_post_details_by_id(post_id).await.ok()?;
-            Some(PostDetails::from_canister_post(
-                false,
-                canister_id,
-                post_details,
-            ))
+            PostDetails::from_canister_post(false, canister_id, post_details)
         },
     );
 
@@ -1,21 +1,22 @@
 mod history;
 
 use candid::Principal;
-use gloo::timers::callback::Timeout;
 use ic_agent::Identity;
 use leptos::*;
 use leptos_icons::*;
 use leptos_router::create_query_signal;
-use leptos_use::use_window;
 
 use crate::component::canisters_prov::AuthCansProvider;
 use crate::component::connect::ConnectLogin;
 use crate::state::canisters::auth_canisters_store;
 use crate::utils::event_streaming::events::{Refer, ReferShareLink};
 use crate::{
-    component::{back_btn::BackButton, dashbox::DashboxLoading, title::Title},
+    component::{back_btn::BackButton, dashbox::DashboxLoading, title::Title, toast::Toasts},
     state::auth::account_connected_reader,
-    utils::web::{copy_to_clipboard, share_url},
+    utils::{
+        posts::get_base_url,
+        web::{copy_to_clipboard_checked, share_url},
+    },
 };
 use history::HistoryView;
 
@@ -32,42 +33,40 @@ fn WorkButton(#[prop(into)] text: String, #[prop(into)] head: String) -> impl In
 #[component]
 fn ReferLoaded(user_principal: Principal) -> impl IntoView {
     let refer_code = user_principal.to_text();
-    let window = use_window();
-    let refer_link = window
-        .as_ref()
-        .and_then(|w| {
-            let origin = w.location().origin().ok()?;
-            Some(format!(
-                "{}/?user_refer={}",
-                origin,
-                user_principal.to_text()
-            ))
-        })
-        .unwrap_or_default();
+    let refer_link = format!(
+        "{}/?user_refer={}",
+        get_base_url(),
+        user_principal.to_text()
+    );
 
     let (logged_in, _) = account_connected_reader();
-    let show_copied_popup = create_rw_signal(false);
     let canister_store = auth_canisters_store();
+    let toasts = Toasts::get();
 
-    let click_copy = create_action(move |refer_link: &String| {
-        let refer_link = refer_link.clone();
-
-        async move {
-            let _ = copy_to_clipboard(&refer_link);
-
+    let click_copy = move |refer_link: String| {
+        let copying = copy_to_clipboard_checked(&refer_link);
+        spawn_local(async move {
             ReferShareLink.send_event(logged_in, canister_store);
-
-            show_copied_popup.set(true);
-            Timeout::new(1200, move || show_copied_popup.set(false)).forget();
-        }
-    });
+            if copying.await.is_some() {
+                toasts.success("Link copied");
+            } else {
+                toasts.error("Couldn't copy the link");
+            }
+        });
+    };
 
     let handle_share = move || {
         let url = refer_link.clone();
-        if share_url(&url).is_some() {
+        // Sharing must start synchronously with the click, see [share_url]
+        let Some(sharing) = share_url(&url) else {
+            click_copy(url);
             return;
-        }
-        click_copy.dispatch(url)
+        };
+        spawn_local(async move {
+            if sharing.await.needs_fallback() {
+                click_copy(url)
+            }
+        });
     };
 
     view! {
@@ -77,13 +76,6 @@ fn ReferLoaded(user_principal: Principal) -> impl IntoView {
                 <Icon class="text-xl text-primary-500" icon=icondata::IoShareSocialSharp/>
             </button>
         </div>
-        <Show when=show_copied_popup>
-            <div class="absolute flex flex-col justify-center items-center z-[4]">
-                <span class="absolute top-28 flex flex-row justify-center items-center bg-white/90 rounded-md h-10 w-28 text-center shadow-lg">
-                    <p class="text-black">Link Copied!</p>
-                </span>
-            </div>
-        </Show>
     }
 }
 
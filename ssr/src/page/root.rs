@@ -2,33 +2,122 @@ use candid::Principal;
 use leptos::*;
 use leptos_router::*;
 
-use crate::component::spinner::FullScreenSpinner;
 #[cfg(feature = "ssr")]
-use crate::{canister::post_cache, state::canisters::unauth_canisters};
+use crate::canister::post_cache;
+use crate::{
+    component::spinner::FullScreenSpinner,
+    page::post_view::PostViewCtx,
+    state::{canisters::unauth_canisters, local_storage::use_feed_position_store},
+    utils::posts::{get_feed_component_identifier, get_post_uid},
+};
 
-#[server]
-async fn get_top_post_id() -> Result<Option<(Principal, u64)>, ServerFnError> {
+/// Strategy for selecting the first post shown in the feed
+/// read from the `FIRST_POST_STRATEGY` env var on every request
+/// so that it can be swapped without a redeploy
+///
+/// Deep linked posts (`/hot-or-not/:canister_id/:post_id`) never go through
+/// the root page and always win regardless of the strategy
+#[cfg(feature = "ssr")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FirstPostStrategy {
+    /// Top post of the home feed
+    #[default]
+    Trending,
+    /// Top post of the hot or not feed
+    HotOrNot,
+    /// Most recently created post among the top posts
+    Newest,
+    /// Pinned (sponsored) post, set via the `PINNED_POST` env var
+    /// formatted as `<canister_id>/<post_id>`
+    Pinned,
+}
+
+#[cfg(feature = "ssr")]
+impl FirstPostStrategy {
+    fn from_env() -> Self {
+        let Ok(strategy) = std::env::var("FIRST_POST_STRATEGY") else {
+            return Self::default();
+        };
+        match strategy.to_lowercase().as_str() {
+            "trending" => Self::Trending,
+            "hot-or-not" => Self::HotOrNot,
+            "newest" => Self::Newest,
+            "pinned" => Self::Pinned,
+            _ => {
+                log::warn!("Unknown first post strategy {strategy}, using default");
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Number of top posts considered by [FirstPostStrategy::Newest]
+#[cfg(feature = "ssr")]
+const NEWEST_CANDIDATE_CNT: u64 = 20;
+
+#[cfg(feature = "ssr")]
+async fn fetch_top_posts(
+    hot_or_not: bool,
+    limit: u64,
+) -> Result<Vec<post_cache::PostScoreIndexItemV1>, ServerFnError> {
     let canisters = unauth_canisters();
     let post_cache = canisters.post_cache().await?;
 
-    let top_items = match post_cache
-        .get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_cursor(
-            0,
-            1,
-            None,
-            None,
-            Some(post_cache::NsfwFilter::ExcludeNsfw),
-        )
-        .await?
-    {
-        post_cache::Result_::Ok(items) => items,
-        post_cache::Result_::Err(_) => {
-            return Err(ServerFnError::ServerError(
-                "failed to fetch top post".to_string(),
-            ));
+    let filter = Some(post_cache::NsfwFilter::ExcludeNsfw);
+    let res = if hot_or_not {
+        post_cache
+            .get_top_posts_aggregated_from_canisters_on_this_network_for_hot_or_not_feed_cursor(
+                0, limit, None, None, filter,
+            )
+            .await?
+    } else {
+        post_cache
+            .get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_cursor(
+                0, limit, None, None, filter,
+            )
+            .await?
+    };
+
+    match res {
+        post_cache::Result_::Ok(items) => Ok(items),
+        post_cache::Result_::Err(_) => Err(ServerFnError::ServerError(
+            "failed to fetch top post".to_string(),
+        )),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn pinned_post() -> Option<(Principal, u64)> {
+    let pinned = std::env::var("PINNED_POST").ok()?;
+    let (canister_id, post_id) = pinned.split_once('/')?;
+    Some((
+        Principal::from_text(canister_id).ok()?,
+        post_id.parse().ok()?,
+    ))
+}
+
+#[server]
+async fn get_top_post_id() -> Result<Option<(Principal, u64)>, ServerFnError> {
+    let top_item = match FirstPostStrategy::from_env() {
+        FirstPostStrategy::Trending => fetch_top_posts(false, 1).await?.into_iter().next(),
+        FirstPostStrategy::HotOrNot => fetch_top_posts(true, 1).await?.into_iter().next(),
+        FirstPostStrategy::Newest => fetch_top_posts(false, NEWEST_CANDIDATE_CNT)
+            .await?
+            .into_iter()
+            .max_by_key(|item| {
+                item.created_at
+                    .as_ref()
+                    .map(|t| (t.secs_since_epoch, t.nanos_since_epoch))
+            }),
+        FirstPostStrategy::Pinned => {
+            if let Some(pinned) = pinned_post() {
+                return Ok(Some(pinned));
+            }
+            log::warn!("Pinned first post strategy without a valid `PINNED_POST`");
+            fetch_top_posts(false, 1).await?.into_iter().next()
         }
     };
-    let Some(top_item) = top_items.first() else {
+    let Some(top_item) = top_item else {
         return Ok(None);
     };
 
@@ -60,9 +149,40 @@ async fn get_top_post_id() -> Result<Option<(Principal, u64)>, ServerFnError> {
 //     Ok(Some((top_item.0, top_item.1)))
 // }
 
+/// Resume the feed from the last viewed position in this session
+/// falls back to the next post in the queue if the saved post is no longer available
+async fn restore_feed_position() -> Option<(Principal, u64)> {
+    let (feed_position, set_feed_position, _) = use_feed_position_store();
+    let pos = feed_position.get_untracked()?;
+    if Some(pos.feed.as_str()) != get_feed_component_identifier()() {
+        set_feed_position(None);
+        return None;
+    }
+
+    let canisters = unauth_canisters();
+    if let Ok(Some(post)) = get_post_uid(&canisters, pos.canister_id, pos.post_id).await {
+        return Some((post.canister_id, post.post_id));
+    }
+
+    let post_view_ctx: PostViewCtx = expect_context();
+    let Some(nearest) = post_view_ctx.post_after(pos.idx) else {
+        set_feed_position(None);
+        return None;
+    };
+    Some((nearest.canister_id, nearest.post_id))
+}
+
 #[component]
 pub fn RootPage() -> impl IntoView {
-    let target_post = create_resource(|| (), |_| get_top_post_id());
+    let target_post = create_resource(
+        || (),
+        |_| async move {
+            if let Some(post) = restore_feed_position().await {
+                return Ok(Some(post));
+            }
+            get_top_post_id().await
+        },
+    );
 
     view! {
         <Suspense fallback=FullScreenSpinner>
@@ -1,17 +1,27 @@
+mod sessions;
+
+use crate::auth::refresh_token_expiry;
 use crate::component::back_btn::BackButton;
 use crate::component::canisters_prov::AuthCansProvider;
 use crate::component::title::Title;
+use crate::component::toast::Toasts;
 use crate::component::{social::*, toggle::Toggle};
 use crate::consts::NOTIFICATIONS_ENABLED_STORE;
 use crate::state::auth::account_connected_reader;
+use crate::state::canisters::auth_canisters_store;
+use crate::state::history::HistoryCtx;
+use crate::state::local_storage::{use_data_saver_store, use_onboarding_tour_store};
 use crate::utils::notifications::get_token_for_principal;
 use crate::utils::profile::ProfileDetails;
+use crate::utils::support::SupportBundle;
+use crate::utils::web::copy_to_clipboard_checked;
 use codee::string::FromToStringCodec;
 use leptos::html::Input;
 use leptos::*;
 use leptos_icons::*;
 use leptos_use::storage::use_local_storage;
 use leptos_use::use_event_listener;
+pub use sessions::Sessions;
 
 #[component]
 fn MenuItem(
@@ -139,6 +149,111 @@ fn EnableNotifications(user_details: ProfileDetails) -> impl IntoView {
     }
 }
 
+#[component]
+fn DataSaver() -> impl IntoView {
+    let (data_saver, set_data_saver, _) = use_data_saver_store();
+    let toggle_ref = create_node_ref::<Input>();
+
+    _ = use_event_listener(toggle_ref, ev::change, move |_| {
+        let Some(toggle) = toggle_ref.get_untracked() else {
+            return;
+        };
+        set_data_saver(toggle.checked())
+    });
+
+    view! {
+        <div class="grid grid-cols-2 items-center w-full">
+            <div class="flex flex-col">
+                <div class="flex flex-row gap-4 items-center">
+                    <Icon class="text-2xl" icon=icondata::AiCloudDownloadOutlined/>
+                    <span>Data Saver</span>
+                </div>
+                <span class="text-white/50 text-sm">Load videos only when needed</span>
+            </div>
+            <div class="justify-self-end">
+                <Toggle checked=data_saver node_ref=toggle_ref/>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ReplayOnboardingTour() -> impl IntoView {
+    let (_, set_tour_completed, _) = use_onboarding_tour_store();
+    let navigate = leptos_router::use_navigate();
+
+    view! {
+        <button
+            class="grid grid-cols-3 items-center w-full"
+            on:click=move |_| {
+                set_tour_completed(false);
+                navigate("/", Default::default());
+            }
+        >
+            <div class="flex flex-row gap-4 items-center col-span-2">
+                <Icon class="text-2xl" icon=icondata::AiQuestionCircleOutlined/>
+                <span class="text-wrap text-left">Replay onboarding tour</span>
+            </div>
+            <Icon class="text-2xl justify-self-end" icon=icondata::AiRightOutlined/>
+        </button>
+    }
+}
+
+#[component]
+fn CopySupportInfo() -> impl IntoView {
+    let canisters = auth_canisters_store();
+    let history = use_context::<HistoryCtx>();
+    let refresh_expiry = create_local_resource(|| (), |_| refresh_token_expiry());
+    let toasts = Toasts::get();
+
+    let copy_bundle = move |_| {
+        let recent_routes = history
+            .as_ref()
+            .map(|h| h.history.with_untracked(|h| h.iter().cloned().collect()))
+            .unwrap_or_default();
+        let refresh_expiry = refresh_expiry
+            .get_untracked()
+            .and_then(|r| r.ok())
+            .flatten();
+        let bundle = canisters.with_untracked(|cans| {
+            SupportBundle::new(cans.as_ref(), refresh_expiry, recent_routes)
+        });
+        let copying = copy_to_clipboard_checked(&bundle.to_text());
+        spawn_local(async move {
+            if copying.await.is_some() {
+                toasts.success("Copied! Paste it in your message to support");
+            } else {
+                toasts.error("Couldn't copy the support info");
+            }
+        });
+    };
+
+    view! {
+        <button class="grid grid-cols-3 items-center w-full" on:click=copy_bundle>
+            <div class="flex flex-row gap-4 items-center col-span-2">
+                <Icon class="text-2xl" icon=icondata::BiSupportRegular/>
+                <span class="text-wrap text-left">Copy support info</span>
+            </div>
+            <Icon class="text-2xl justify-self-end" icon=icondata::FaCopyRegular/>
+        </button>
+    }
+}
+
+/// Only shown to connected accounts, anonymous identities live on a single device
+#[component]
+fn ActiveSessions() -> impl IntoView {
+    let (is_connected, _) = account_connected_reader();
+    view! {
+        <Show when=is_connected>
+            <MenuItem
+                href="/settings/sessions"
+                text="Active sessions"
+                icon=icondata::AiLaptopOutlined
+            />
+        </Show>
+    }
+}
+
 #[component]
 pub fn Settings() -> impl IntoView {
     view! {
@@ -156,6 +271,10 @@ pub fn Settings() -> impl IntoView {
                 <AuthCansProvider let:canisters>
                     <EnableNotifications user_details=canisters.profile_details()/>
                 </AuthCansProvider>
+                <DataSaver/>
+                <ActiveSessions/>
+                <ReplayOnboardingTour/>
+                <CopySupportInfo/>
             </div>
             <MenuFooter/>
         </div>
@@ -0,0 +1,183 @@
+use codee::string::FromToStringCodec;
+use leptos::*;
+use leptos_icons::*;
+use leptos_use::storage::use_local_storage;
+
+use crate::{
+    auth::session::{list_sessions, revoke_session, SessionInfo},
+    component::{back_btn::BackButton, modal::Modal, title::Title, toast::Toasts},
+    consts::ACCOUNT_CONNECTED_STORE,
+    page::wallet::cache::use_wallet_cache_store,
+    utils::timestamp::get_date_time,
+};
+
+#[component]
+fn SessionsLoading() -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-4 w-full animate-pulse">
+            {(0..3)
+                .map(|_| view! { <div class="w-full h-16 rounded-xl bg-white/10"></div> })
+                .collect_view()}
+        </div>
+    }
+}
+
+/// Sign out of this device, after confirmation
+/// local state is cleared and the page reloaded, like switching accounts
+#[component]
+fn SignOutCurrent(id: u64) -> impl IntoView {
+    let show_confirm = create_rw_signal(false);
+    let (_, write_account_connected, _) =
+        use_local_storage::<bool, FromToStringCodec>(ACCOUNT_CONNECTED_STORE);
+    let (_, _, clear_wallet_cache) = use_wallet_cache_store();
+    let sign_out = create_action(move |&()| {
+        let clear_wallet_cache = clear_wallet_cache.clone();
+        async move {
+            if let Err(e) = revoke_session(id, true).await {
+                log::warn!("failed to sign out of the current session: {e}");
+                Toasts::get().error("Couldn't sign out, please try again");
+                return;
+            }
+            write_account_connected(false);
+            clear_wallet_cache();
+            _ = window().location().set_href("/");
+        }
+    });
+    let pending = sign_out.pending();
+
+    view! {
+        <button class="text-white/50 text-sm underline" on:click=move |_| show_confirm.set(true)>
+            Sign out
+        </button>
+        <Modal show=show_confirm>
+            <div class="flex flex-col items-center gap-6 py-6 text-white text-center">
+                <span class="text-lg">Sign out of this device?</span>
+                <div class="flex flex-row gap-4">
+                    <button
+                        class="px-6 py-2 rounded-full bg-white/10"
+                        on:click=move |_| show_confirm.set(false)
+                    >
+                        Cancel
+                    </button>
+                    <button
+                        class="px-6 py-2 rounded-full bg-primary-600 disabled:opacity-50"
+                        disabled=pending
+                        on:click=move |_| sign_out.dispatch(())
+                    >
+                        {move || if pending() { "Signing out..." } else { "Sign out" }}
+                    </button>
+                </div>
+            </div>
+        </Modal>
+    }
+}
+
+#[component]
+fn SessionItem(session: SessionInfo, on_revoked: Callback<u64>) -> impl IntoView {
+    let SessionInfo { id, current, .. } = session;
+    let revoke = create_action(move |&()| async move {
+        match revoke_session(id, false).await {
+            Ok(()) => on_revoked(id),
+            Err(e) => {
+                log::warn!("failed to revoke session: {e}");
+                Toasts::get().error("Couldn't revoke the session");
+            }
+        }
+    });
+    let pending = revoke.pending();
+    let last_active = get_date_time((session.last_active_ms / 1000) as u64);
+    let signed_in = get_date_time((session.created_at_ms / 1000) as u64);
+
+    view! {
+        <div class="flex flex-row items-center justify-between gap-4 w-full p-4 rounded-xl bg-white/5">
+            <div class="flex flex-row items-center gap-4 min-w-0">
+                <Icon class="text-2xl shrink-0" icon=icondata::AiLaptopOutlined/>
+                <div class="flex flex-col min-w-0">
+                    <div class="flex flex-row items-center gap-2">
+                        <span class="truncate">{session.device()}</span>
+                        <Show when=move || current>
+                            <span class="shrink-0 px-2 rounded-full text-xs bg-primary-600">
+                                This device
+                            </span>
+                        </Show>
+                    </div>
+                    <span class="text-white/50 text-xs">Active {last_active}</span>
+                    <span class="text-white/50 text-xs">Signed in {signed_in}</span>
+                </div>
+            </div>
+            {if current {
+                view! { <SignOutCurrent id/> }.into_view()
+            } else {
+                view! {
+                    <button
+                        class="shrink-0 text-primary-600 text-sm disabled:opacity-50"
+                        disabled=pending
+                        on:click=move |_| revoke.dispatch(())
+                    >
+                        {move || if pending() { "Revoking..." } else { "Revoke" }}
+                    </button>
+                }
+                    .into_view()
+            }}
+        </div>
+    }
+}
+
+/// Devices signed in to the user's account, any of them can be signed out remotely
+#[component]
+pub fn Sessions() -> impl IntoView {
+    let sessions = create_local_resource(|| (), |_| list_sessions());
+    let revoked = create_rw_signal(Vec::<u64>::new());
+    let on_revoked = Callback::new(move |id| {
+        revoked.update(|r| r.push(id));
+        Toasts::get().success("Session revoked");
+    });
+
+    view! {
+        <div class="min-h-screen w-full flex flex-col text-white pt-2 pb-12 bg-black items-center">
+            <Title justify_center=false>
+                <div class="flex flex-row justify-between">
+                    <BackButton fallback="/settings".to_string()/>
+                    <span class="font-bold text-2xl">Active sessions</span>
+                    <div></div>
+                </div>
+            </Title>
+            <div class="flex flex-col gap-4 w-full px-8 pt-12">
+                <Suspense fallback=SessionsLoading>
+                    {move || {
+                        sessions()
+                            .map(|res| match res {
+                                Ok(sessions) if sessions.is_empty() => {
+                                    view! {
+                                        <span class="text-white/50 text-center">
+                                            No active sessions
+                                        </span>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(sessions) => {
+                                    sessions
+                                        .into_iter()
+                                        .filter(|s| revoked.with(|r| !r.contains(&s.id)))
+                                        .map(|session| view! { <SessionItem session on_revoked/> })
+                                        .collect_view()
+                                }
+                                Err(e) => {
+                                    log::warn!("failed to list sessions: {e}");
+                                    view! {
+                                        <button
+                                            class="text-primary-600"
+                                            on:click=move |_| sessions.refetch()
+                                        >
+                                            "Couldn't load your sessions, tap to retry"
+                                        </button>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Suspense>
+            </div>
+        </div>
+    }
+}
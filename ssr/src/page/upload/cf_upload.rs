@@ -10,6 +10,9 @@ use mock_impl::server_func::*;
 pub use mock_impl::{publish_video, upload_video_stream};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ssr")]
+use super::validators::description_validator;
+
 #[derive(Serialize, Deserialize)]
 pub struct UploadInfo {
     pub uid: String,
@@ -25,11 +28,7 @@ pub async fn get_upload_info(
 ) -> Result<UploadInfo, ServerFnError> {
     // TODO(SECURITY): authenticate creator
 
-    if description.len() < 10 {
-        return Err(ServerFnError::Args(
-            "Description must be at least 10 characters".into(),
-        ));
-    }
+    description_validator(description.clone()).map_err(ServerFnError::Args)?;
     if hashtags.len() > 8 {
         return Err(ServerFnError::Args("Too many hashtags".into()));
     }
@@ -62,7 +61,7 @@ mod cf_impl {
         };
         use leptos::{expect_context, ServerFnError};
 
-        use crate::consts::CF_WATERMARK_UID;
+        use crate::state::cloudflare::CloudflareConfig;
 
         use super::UploadInfo;
         use std::time::Duration;
@@ -74,13 +73,14 @@ mod cf_impl {
             file_name: String,
         ) -> Result<UploadInfo, ServerFnError> {
             let cf_api: CloudflareAuth = expect_context();
+            let cf_config: CloudflareConfig = expect_context();
             let req = DirectUpload::default()
                 .creator(creator.to_text())
                 .add_meta("hashtags", hashtags.join(","))
                 .add_meta("description", description)
                 .add_meta("fileName", file_name)
                 .add_meta("uploadType", "challenge")
-                .watermark(CF_WATERMARK_UID)
+                .watermark(&cf_config.watermark_uid)
                 .max_duration(Duration::from_secs(60));
             let res = cf_api.send_auth(req).await?;
 
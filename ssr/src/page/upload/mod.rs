@@ -4,6 +4,7 @@ mod video_upload;
 
 use crate::{
     component::toggle::ToggleWithLabel,
+    consts::MAX_DESCRIPTION_CHARS,
     state::canisters::auth_canisters_store,
     utils::event_streaming::events::{VideoUploadInitiated, VideoUploadUploadButtonClicked},
 };
@@ -13,7 +14,7 @@ use leptos::{
     *,
 };
 
-use validators::{description_validator, hashtags_validator};
+use validators::{description_len, description_validator, hashtags_validator};
 use video_upload::{FileWithUrl, PreVideoUpload, VideoUploader};
 
 #[derive(Clone)]
@@ -29,6 +30,7 @@ struct UploadParams {
 fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>>) -> impl IntoView {
     let description_err = create_rw_signal(String::new());
     let desc_err_memo = create_memo(move |_| description_err());
+    let desc_len = create_rw_signal(0usize);
     let hashtags = create_rw_signal(Vec::new());
     let hashtags_err = create_rw_signal(String::new());
     let hashtags_err_memo = create_memo(move |_| hashtags_err());
@@ -110,6 +112,7 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>>) -> impl Into
                     _ref=desc
                     on:input=move |ev| {
                         let desc = event_target_value(&ev);
+                        desc_len.set(description_len(&desc));
                         description_err.set(description_validator(desc).err().unwrap_or_default());
                     }
 
@@ -117,6 +120,13 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>>) -> impl Into
                     rows=3
                     placeholder="Write your description here.."
                 ></textarea>
+                <span
+                    class="self-end text-xs"
+                    class=("text-red-500", move || desc_len() > MAX_DESCRIPTION_CHARS)
+                    class=("text-neutral-500", move || desc_len() <= MAX_DESCRIPTION_CHARS)
+                >
+                    {move || format!("{}/{MAX_DESCRIPTION_CHARS}", desc_len())}
+                </span>
             </div>
             <div class="flex flex-col gap-y-2">
                 <Show
@@ -1,8 +1,21 @@
+use crate::consts::MAX_DESCRIPTION_CHARS;
+
+/// Length of the description as shown to the user
+/// counts characters rather than bytes, so emojis and non-latin scripts aren't penalized
+pub fn description_len(desc: &str) -> usize {
+    desc.chars().count()
+}
+
 pub fn description_validator(desc: String) -> Result<(), String> {
+    let len = description_len(&desc);
     if desc.is_empty() {
         return Err("Description is required".into());
-    } else if desc.len() < 10 {
+    } else if len < 10 {
         return Err("Description must be at least 10 characters".into());
+    } else if len > MAX_DESCRIPTION_CHARS {
+        return Err(format!(
+            "Description must be at most {MAX_DESCRIPTION_CHARS} characters"
+        ));
     }
 
     Ok(())
@@ -4,7 +4,10 @@ use super::{
 };
 use crate::{
     component::modal::Modal,
-    state::canisters::{auth_canisters_store, authenticated_canisters, Canisters},
+    state::{
+        auth::anonymous_browse,
+        canisters::{auth_canisters_store, authenticated_canisters, Canisters},
+    },
     try_or_redirect_opt,
     utils::{
         event_streaming::events::{
@@ -313,7 +316,9 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
             Some(())
         }
     });
-    let cans_res = authenticated_canisters();
+    let auth_cans = authenticated_canisters();
+    let anon_browse = anonymous_browse();
+    create_effect(move |_| anon_browse.request_identity());
 
     view! {
         <div class="flex flex-col justify-start self-center w-3/4 mb-8 lg:mb-0 lg:pb-12 lg:max-h-full lg:w-1/2 basis-full lg:basis-5/12">
@@ -339,7 +344,7 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
                 <Suspense>
                     {move || {
                         let uid = upload_action().flatten()?;
-                        let canisters = cans_res()?.ok()?.canisters().ok()?;
+                        let canisters = auth_cans.resolved()?.ok()??;
                         publish_action.dispatch((canisters, uid));
                         Some(())
                     }}
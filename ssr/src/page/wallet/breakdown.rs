@@ -0,0 +1,153 @@
+use leptos::*;
+use leptos_icons::*;
+
+use crate::{state::canisters::Canisters, utils::web::copy_to_clipboard};
+
+use super::{
+    history_scan::HistoryScans,
+    txn::{TxnInfo, TxnTag},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum BreakdownStatus {
+    Loading,
+    /// The whole history was aggregated
+    Done,
+    /// Only the latest transactions were aggregated, see [HistoryScans]
+    Truncated,
+    /// Fetching the history failed, no totals are shown
+    Failed,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+struct BalanceBreakdown {
+    earned: u64,
+    spent: u64,
+    received: u64,
+    sent: u64,
+}
+
+impl BalanceBreakdown {
+    fn new(txns: &[TxnInfo]) -> Self {
+        let mut breakdown = Self::default();
+        txns.iter().for_each(|txn| breakdown.add(txn));
+        breakdown
+    }
+
+    fn add(&mut self, txn: &TxnInfo) {
+        use TxnTag::*;
+        let total = match txn.tag {
+            SignupBonus | Referral | Winnings | Commission => &mut self.earned,
+            BetPlaced => &mut self.spent,
+            HotorNotAccountTransfer => &mut self.received,
+            Transfer => &mut self.sent,
+        };
+        *total = total.saturating_add(txn.amount);
+    }
+
+    fn to_text(self) -> String {
+        format!(
+            "Earned: {} COYNs\nSpent: {} COYNs\nReceived: {} COYNs\nSent: {} COYNs",
+            self.earned, self.spent, self.received, self.sent
+        )
+    }
+}
+
+#[component]
+fn BreakdownItem(label: &'static str, #[prop(into)] amount: Signal<u64>) -> impl IntoView {
+    view! {
+        <div class="flex flex-row w-full justify-between">
+            <span class="text-white/50">{label}</span>
+            <span>{amount} COYNs</span>
+        </div>
+    }
+}
+
+#[component]
+fn BreakdownLoader(canisters: Canisters<true>) -> impl IntoView {
+    // Shares the summary's scan, which has usually finished by the time this is expanded
+    let scan = HistoryScans::get(canisters);
+    let breakdown = create_memo(move |_| {
+        scan.with(|res| match res {
+            Some(Ok(scan)) => BalanceBreakdown::new(&scan.txns),
+            _ => BalanceBreakdown::default(),
+        })
+    });
+    let status = move || {
+        scan.with(|res| match res {
+            None => BreakdownStatus::Loading,
+            Some(Ok(scan)) if scan.truncated => BreakdownStatus::Truncated,
+            Some(Ok(_)) => BreakdownStatus::Done,
+            Some(Err(_)) => BreakdownStatus::Failed,
+        })
+    };
+
+    let copied = create_rw_signal(false);
+    let status_note = move || {
+        scan.with(|res| match res {
+            Some(Ok(scan)) if scan.truncated => Some(format!(
+                "Totals of your latest {} transactions",
+                scan.txns.len()
+            )),
+            Some(Err(_)) => Some("Couldn't load your history".to_string()),
+            _ => None,
+        })
+    };
+
+    view! {
+        <div class="flex flex-col w-full gap-2 text-sm md:text-md">
+            <BreakdownItem label="Earned" amount=Signal::derive(move || breakdown().earned)/>
+            <BreakdownItem label="Spent" amount=Signal::derive(move || breakdown().spent)/>
+            <BreakdownItem label="Received" amount=Signal::derive(move || breakdown().received)/>
+            <BreakdownItem label="Sent" amount=Signal::derive(move || breakdown().sent)/>
+            {move || {
+                status_note()
+                    .map(|note| view! { <span class="text-white/50 text-xs md:text-sm">{note}</span> })
+            }}
+            <Show
+                when=move || status() != BreakdownStatus::Loading
+                fallback=move || {
+                    view! {
+                        <span class="text-white/50 text-xs md:text-sm animate-pulse">
+                            Loading transactions...
+                        </span>
+                    }
+                }
+            >
+                <button
+                    class="flex flex-row gap-2 items-center self-end text-primary-600"
+                    on:click=move |_| {
+                        copied.set(copy_to_clipboard(&breakdown.get_untracked().to_text()).is_some());
+                    }
+                >
+                    <Icon icon=icondata::FaCopyRegular/>
+                    {move || if copied() { "Copied!" } else { "Copy breakdown" }}
+                </button>
+            </Show>
+        </div>
+    }
+}
+
+/// Earned vs spent totals, computed from the transaction history
+#[component]
+pub fn BalanceDetails(canisters: Canisters<true>) -> impl IntoView {
+    let expanded = create_rw_signal(false);
+    let canisters = store_value(canisters);
+
+    view! {
+        <div class="flex flex-col w-full md:w-5/12 items-center gap-2 mt-2">
+            <button
+                class="flex flex-row gap-1 items-center text-white/50 text-sm md:text-md"
+                on:click=move |_| expanded.update(|e| *e = !*e)
+            >
+                Details
+                <Icon icon=Signal::derive(move || {
+                    if expanded() { icondata::AiUpOutlined } else { icondata::AiDownOutlined }
+                })/>
+            </button>
+            <Show when=expanded>
+                <BreakdownLoader canisters=canisters.get_value()/>
+            </Show>
+        </div>
+    }
+}
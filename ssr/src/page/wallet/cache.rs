@@ -0,0 +1,133 @@
+//! Last known wallet state, persisted across visits
+//! rendered immediately on load and revalidated in the background
+use codee::string::JsonSerdeCodec;
+use leptos::*;
+use leptos_use::storage::use_local_storage;
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::{consts::WALLET_CACHE_STORE, utils::current_epoch};
+
+use super::txn::TxnInfo;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct CachedProfile {
+    pub display_name: String,
+    pub profile_pic: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct WalletSnapshot {
+    pub profile: Option<CachedProfile>,
+    pub balance: Option<u64>,
+    pub recent_txns: Option<Vec<TxnInfo>>,
+    /// Time of the last update, since the unix epoch
+    pub updated_at: Duration,
+}
+
+pub fn use_wallet_cache_store() -> (
+    Signal<Option<WalletSnapshot>>,
+    WriteSignal<Option<WalletSnapshot>>,
+    impl Fn() + Clone,
+) {
+    use_local_storage::<Option<WalletSnapshot>, JsonSerdeCodec>(WALLET_CACHE_STORE)
+}
+
+/// Sections of the wallet that were revalidated in this visit
+#[derive(Clone, Copy, Default)]
+struct Revalidated {
+    profile: bool,
+    balance: bool,
+    recent_txns: bool,
+}
+
+impl Revalidated {
+    fn all(self) -> bool {
+        self.profile && self.balance && self.recent_txns
+    }
+}
+
+/// Stale-while-revalidate cache for the wallet page
+#[derive(Clone, Copy)]
+pub struct WalletCache {
+    store: Signal<Option<WalletSnapshot>>,
+    set_store: WriteSignal<Option<WalletSnapshot>>,
+    /// Local storage is unavailable during SSR,
+    /// cached data is only rendered after hydration to avoid mismatches
+    hydrated: RwSignal<bool>,
+    revalidated: RwSignal<Revalidated>,
+}
+
+impl WalletCache {
+    pub fn new() -> Self {
+        let (store, set_store, _) = use_wallet_cache_store();
+        let hydrated = create_rw_signal(false);
+        create_effect(move |_| hydrated.set(true));
+
+        Self {
+            store,
+            set_store,
+            hydrated,
+            revalidated: create_rw_signal(Revalidated::default()),
+        }
+    }
+
+    /// Cached snapshot, `None` if nothing is cached
+    /// or everything was already revalidated
+    pub fn stale(&self) -> Option<WalletSnapshot> {
+        if !self.hydrated.get() || self.revalidated.with(|r| r.all()) {
+            return None;
+        }
+        self.store.get()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut WalletSnapshot), mark: impl FnOnce(&mut Revalidated)) {
+        self.set_store.update(|s| {
+            let snapshot = s.get_or_insert_with(WalletSnapshot::default);
+            f(snapshot);
+            snapshot.updated_at = current_epoch();
+        });
+        self.revalidated.update(mark);
+    }
+
+    pub fn set_profile(&self, profile: CachedProfile) {
+        self.update(|s| s.profile = Some(profile), |r| r.profile = true);
+    }
+
+    pub fn set_balance(&self, balance: u64) {
+        self.update(|s| s.balance = Some(balance), |r| r.balance = true);
+    }
+
+    pub fn set_recent_txns(&self, txns: Vec<TxnInfo>) {
+        self.update(|s| s.recent_txns = Some(txns), |r| r.recent_txns = true);
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Indicator shown while cached data is being rendered
+#[component]
+pub fn StaleIndicator(cache: WalletCache) -> impl IntoView {
+    view! {
+        {move || {
+            cache
+                .stale()
+                .map(|snapshot| {
+                    let age = current_epoch().saturating_sub(snapshot.updated_at);
+                    view! {
+                        <div class="self-center px-3 py-1 rounded-full bg-white/10 text-white/50 text-xs md:text-sm animate-pulse">
+                            {format!("Last updated {} ago, refreshing...", format_age(age))}
+                        </div>
+                    }
+                })
+        }}
+    }
+}
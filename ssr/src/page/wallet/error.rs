@@ -0,0 +1,48 @@
+use ic_agent::AgentError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::utils::ic::CallError;
+
+/// Failure to load wallet data
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+pub enum WalletError {
+    /// The replica could not be reached or rejected the request
+    #[error("network error: {0}")]
+    Agent(String),
+    #[error("timed out waiting for a response")]
+    Timeout,
+    /// The response didn't match the expected candid types
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+    /// The canister rejected the call or trapped
+    #[error("canister error: {0}")]
+    Canister(String),
+}
+
+impl WalletError {
+    /// Whether retrying the same call may succeed
+    pub fn retryable(&self) -> bool {
+        matches!(self, Self::Agent(_) | Self::Timeout)
+    }
+
+    pub fn user_message(&self) -> &'static str {
+        match self {
+            Self::Agent(_) | Self::Timeout => "Network error",
+            Self::Decode(_) | Self::Canister(_) => "Balance unavailable",
+        }
+    }
+}
+
+impl From<AgentError> for WalletError {
+    fn from(e: AgentError) -> Self {
+        match e {
+            AgentError::TimeoutWaitingForResponse() => Self::Timeout,
+            AgentError::CandidError(e) => Self::Decode(e.to_string()),
+            e => match CallError::from(e) {
+                CallError::Replica(msg) => Self::Agent(msg),
+                CallError::Canister(msg) => Self::Canister(msg),
+            },
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Latest transactions of the wallet's user, scanned once per principal
+//! and shared by the summary and the breakdown instead of each scanning the history
+use std::collections::HashMap;
+
+use candid::Principal;
+use leptos::*;
+
+use crate::state::canisters::Canisters;
+
+use super::txn::{
+    provider::{get_history_provider, HistoryProvider},
+    HistoryScan,
+};
+
+pub type ScanResource = Resource<(), Result<HistoryScan, String>>;
+
+/// Provided by the wallet, scans are kept for as long as it's mounted
+#[derive(Clone, Copy)]
+pub struct HistoryScans {
+    owner: Owner,
+    scans: StoredValue<HashMap<Principal, ScanResource>>,
+}
+
+impl Default for HistoryScans {
+    fn default() -> Self {
+        Self {
+            owner: Owner::current().expect("history scans must be created in a reactive owner"),
+            scans: store_value(HashMap::new()),
+        }
+    }
+}
+
+impl HistoryScans {
+    /// Scan of the history of `canisters`' user, started on first use
+    /// local, so the wallet never waits for it while rendering on the server
+    pub fn get(canisters: Canisters<true>) -> ScanResource {
+        use_context::<Self>().unwrap_or_default().scan(canisters)
+    }
+
+    fn scan(&self, canisters: Canisters<true>) -> ScanResource {
+        let principal = canisters.user_principal();
+        if let Some(scan) = self.scans.with_value(|s| s.get(&principal).copied()) {
+            return scan;
+        }
+        // Owned by the wallet, so that it outlives the component that started it
+        let scan = with_owner(self.owner, move || {
+            create_local_resource(
+                || (),
+                move |_| {
+                    let provider = get_history_provider(canisters.clone());
+                    async move {
+                        provider.scan_history().await.map_err(|e| {
+                            log::warn!("failed to scan history: {e}");
+                            e.to_string()
+                        })
+                    }
+                },
+            )
+        });
+        self.scans.update_value(|s| {
+            s.insert(principal, scan);
+        });
+        scan
+    }
+}
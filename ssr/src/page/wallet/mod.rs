@@ -1,38 +1,83 @@
+mod breakdown;
+pub mod cache;
+mod error;
+mod history_scan;
+mod preview;
+mod reward;
+mod summary;
 pub mod transactions;
-mod txn;
+pub mod txn;
 use leptos::*;
+use leptos_icons::*;
+use leptos_use::use_throttle_fn;
 
 use crate::{
     component::{
-        back_btn::BackButton,
-        bullet_loader::BulletLoader,
-        canisters_prov::{AuthCansProvider, WithAuthCans},
-        connect::ConnectLogin,
-        infinite_scroller::{CursoredDataProvider, KeyedData},
+        animated_balance::AnimatedBalance, avatar::Avatar, back_btn::BackButton,
+        bullet_loader::BulletLoader, canisters_prov::AuthCansProvider, connect::ConnectLogin,
+        copy_on_hold::CopyOnHold, infinite_scroller::CursoredDataProvider,
+    },
+    consts::COYNS_DECIMALS,
+    page::logout::SwitchAccount,
+    state::{
+        app_events::{AppEvent, AppEvents},
+        auth::{account_connected_reader, ensure_valid_delegation},
+        canisters::Canisters,
+        profile_details::use_profile_details,
+    },
+    utils::{
+        call_limit::{limit_call, CallPriority},
+        i18n::t,
+        preview::use_preview_mode,
+        profile::{avatar_seed, fallback_propic_url, AvatarStyle},
+        support::record_error,
+        token,
     },
-    state::{auth::account_connected_reader, canisters::Canisters},
-    utils::profile::ProfileDetails,
 };
-use txn::{provider::get_history_provider, TxnView};
+use breakdown::BalanceDetails;
+use cache::{CachedProfile, StaleIndicator, WalletCache};
+pub use error::WalletError;
+use history_scan::HistoryScans;
+use preview::PreviewWallet;
+use reward::{use_new_txn_poll, CoinDrop};
+use summary::WalletSummary;
+use txn::{provider::get_history_provider, TxnGroups, TxnInfo};
 
 #[component]
-fn ProfileGreeter(details: ProfileDetails) -> impl IntoView {
+fn ProfileGreeter(canisters: Canisters<true>) -> impl IntoView {
     // let (is_connected, _) = account_connected_reader();
+    let details = use_profile_details(canisters);
+    let fallback_propic = details
+        .with_untracked(|d| fallback_propic_url(&avatar_seed(d.principal), AvatarStyle::default()));
+    if let Some(cache) = use_context::<WalletCache>() {
+        create_effect(move |_| {
+            details.with(|d| {
+                cache.set_profile(CachedProfile {
+                    display_name: d.display_name_or_fallback(),
+                    profile_pic: d.profile_pic_or_random(),
+                })
+            })
+        });
+    }
 
     view! {
         <div class="flex flex-col">
-            <span class="text-white/50 text-md">Welcome!</span>
-            <span
+            <span class="text-white/50 text-md">{t("wallet.welcome")}</span>
+            <CopyOnHold
                 class="text-white text-lg md:text-xl truncate"
+                text=details.with_untracked(|d| d.principal.to_text())
+                copied_message=t("wallet.principal_copied")
+            >
                 // TEMP: Workaround for hydration bug until leptos 0.7
                 // class=("md:w-5/12", move || !is_connected())
-            >
-                {details.display_name_or_fallback()}
-            </span>
-        </div>
-        <div class="w-16 aspect-square overflow-clip justify-self-end rounded-full">
-            <img class="h-full w-full object-cover" src=details.profile_pic_or_random()/>
+                {move || details.with(|d| d.display_name_or_fallback())}
+            </CopyOnHold>
         </div>
+        <Avatar
+            class="w-16 justify-self-end"
+            src=Signal::derive(move || details.with(|d| d.profile_pic_or_random()))
+            fallback=fallback_propic
+        />
     }
 }
 
@@ -40,88 +85,487 @@ fn ProfileGreeter(details: ProfileDetails) -> impl IntoView {
 fn FallbackGreeter() -> impl IntoView {
     view! {
         <div class="flex flex-col">
-            <span class="text-white/50 text-md">Welcome!</span>
+            <span class="text-white/50 text-md">{t("wallet.welcome")}</span>
             <div class="w-3/4 rounded-full py-2 bg-white/40 animate-pulse"></div>
         </div>
         <div class="w-16 aspect-square overflow-clip rounded-full justify-self-end bg-white/40 animate-pulse"></div>
     }
 }
 
+/// Last known profile if cached, skeleton otherwise
+#[component]
+fn CachedGreeter(cache: WalletCache) -> impl IntoView {
+    move || {
+        match cache.stale().and_then(|s| s.profile) {
+        Some(profile) => view! {
+            <div class="flex flex-col">
+                <span class="text-white/50 text-md">{t("wallet.welcome")}</span>
+                <span class="text-white/50 text-lg md:text-xl truncate">{profile.display_name}</span>
+            </div>
+            <div class="w-16 aspect-square overflow-clip justify-self-end rounded-full opacity-50">
+                <img class="h-full w-full object-cover" src=profile.profile_pic/>
+            </div>
+        }
+        .into_view(),
+        None => view! { <FallbackGreeter/> }.into_view(),
+    }
+    }
+}
+
 const RECENT_TXN_CNT: usize = 10;
 
+/// Minimum interval between manual refreshes
+const WALLET_REFRESH_THROTTLE_MS: f64 = 2000.0;
+
+/// Pull distance (in pixels) required to trigger a refresh
+const PULL_TO_REFRESH_PX: i32 = 80;
+
+/// Manual refresh of the wallet, see [LiveWallet]
+#[derive(Clone, Copy)]
+struct WalletRefresh(Trigger);
+
 #[component]
 fn BalanceFallback() -> impl IntoView {
     view! { <div class="w-1/4 rounded-full py-3 mt-1 bg-white/30 animate-pulse"></div> }
 }
 
+/// Last known balance if cached, skeleton otherwise
 #[component]
-pub fn Wallet() -> impl IntoView {
-    let (is_connected, _) = account_connected_reader();
+fn CachedBalance(cache: WalletCache) -> impl IntoView {
+    move || match cache.stale().and_then(|s| s.balance) {
+        Some(bal) => view! { <div class="text-xl lg:text-2xl text-white/50">
+            {token::Balance::coyns(bal).to_string()}
+        </div> }
+        .into_view(),
+        None => view! { <BalanceFallback/> }.into_view(),
+    }
+}
 
-    let balance_fetch = |cans: Canisters<true>| async move {
-        let Ok(user) = cans.authenticated_user().await else {
-            return "Error".to_string();
-        };
+#[component]
+fn BalanceError(e: WalletError, retry: impl Fn() + 'static) -> impl IntoView {
+    view! {
+        <div class="flex flex-col items-center gap-1">
+            <span class="text-xl lg:text-2xl text-white/50">{e.user_message()}</span>
+            {e
+                .retryable()
+                .then(|| {
+                    view! {
+                        <button
+                            class="text-primary-600 text-sm md:text-md"
+                            on:click=move |_| retry()
+                        >
+                            {t("wallet.tap_to_retry")}
+                        </button>
+                    }
+                })}
+        </div>
+    }
+}
 
-        user.get_utility_token_balance()
-            .await
-            .map(|b| b.to_string())
-            .unwrap_or("Error".to_string())
-    };
-    let history_fetch = |cans: Canisters<true>| {
-        let history_prov = get_history_provider(cans);
-        async move {
-            let page = history_prov.get_by_cursor(0, RECENT_TXN_CNT).await;
+/// Utility token balance, refetched on [AppEvent::BalanceChanged]
+#[component]
+fn Balance(canisters: Canisters<true>) -> impl IntoView {
+    let balance = create_resource(
+        || (),
+        move |_| {
+            let cans = canisters.clone();
+            async move {
+                let res = async {
+                    let user = cans.authenticated_user().await?;
+                    limit_call(CallPriority::User, user.get_utility_token_balance()).await
+                }
+                .await;
+
+                res.map_err(|e| {
+                    let e = WalletError::from(e);
+                    log::warn!("failed to fetch balance: {e}");
+                    record_error(&e);
+                    e
+                })
+            }
+        },
+    );
+    if let Some(WalletRefresh(refresh)) = use_context() {
+        create_effect(move |prev: Option<()>| {
+            refresh.track();
+            if prev.is_some() {
+                balance.refetch();
+            }
+        });
+    }
+    let cache = use_context::<WalletCache>();
+    create_effect(move |_| {
+        if let (Some(cache), Some(Ok(bal))) = (cache, balance()) {
+            cache.set_balance(bal);
+        }
+    });
+    // Refetches remount the balance, new values are animated from the last one
+    let last_shown = store_value(None::<u64>);
+    AppEvents::get().subscribe(move |ev| {
+        if matches!(
+            ev,
+            AppEvent::BalanceChanged | AppEvent::RewardReceived { .. }
+        ) {
+            balance.refetch();
+        }
+    });
+
+    view! {
+        <Suspense fallback=move || match cache {
+            Some(cache) => view! { <CachedBalance cache/> }.into_view(),
+            None => view! { <BalanceFallback/> }.into_view(),
+        }>
+            {move || {
+                balance()
+                    .map(|bal| match bal {
+                        Ok(bal) => {
+                            let from = last_shown.get_value();
+                            last_shown.set_value(Some(bal));
+                            view! {
+                                <AnimatedBalance
+                                    balance=Signal::derive(move || bal)
+                                    from
+                                    decimals=COYNS_DECIMALS
+                                    class="text-xl lg:text-2xl"
+                                />
+                            }
+                                .into_view()
+                        }
+                        Err(e) => {
+                            view! { <BalanceError e retry=move || balance.refetch()/> }.into_view()
+                        }
+                    })
+            }}
+        </Suspense>
+    }
+}
 
-            page.map(|p| p.data).unwrap_or(vec![])
+/// Layout shared by the live and preview wallets
+#[component]
+fn WalletLayout(
+    #[prop(into)] greeter: ViewFn,
+    #[prop(into, optional)] summary: Option<ViewFn>,
+    #[prop(into)] balance: ViewFn,
+    #[prop(into, optional)] details: Option<ViewFn>,
+    #[prop(into, optional)] login_cta: Option<ViewFn>,
+    #[prop(into)] transactions: ViewFn,
+    /// Enables the refresh button and pull-to-refresh
+    #[prop(into, optional)]
+    on_refresh: Option<Callback<()>>,
+) -> impl IntoView {
+    let pull_start = store_value(None::<i32>);
+    let pull = create_rw_signal(0);
+    let on_touchstart = move |ev: ev::TouchEvent| {
+        // Only at the top of the page, otherwise it's a regular scroll
+        if window().scroll_y().unwrap_or_default() > 0.0 {
+            return;
+        }
+        pull_start.set_value(ev.touches().get(0).map(|t| t.client_y()));
+    };
+    let on_touchmove = move |ev: ev::TouchEvent| {
+        let (Some(start), Some(touch)) = (pull_start.get_value(), ev.touches().get(0)) else {
+            return;
+        };
+        pull.set((touch.client_y() - start).max(0));
+    };
+    let on_touchend = move |_| {
+        if pull.get_untracked() >= PULL_TO_REFRESH_PX {
+            if let Some(on_refresh) = on_refresh {
+                on_refresh(());
+            }
         }
+        pull_start.set_value(None);
+        pull.set(0);
     };
 
     view! {
-        <div>
+        <div
+            on:touchstart=move |ev| {
+                if on_refresh.is_some() {
+                    on_touchstart(ev)
+                }
+            }
+            on:touchmove=on_touchmove
+            on:touchend=on_touchend
+        >
+            <Show when=move || { pull() > 0 }>
+                <div class="flex flex-row w-full justify-center py-2 text-white/50 text-sm">
+                    {move || {
+                        if pull() >= PULL_TO_REFRESH_PX {
+                            t("wallet.release_to_refresh")
+                        } else {
+                            t("wallet.pull_to_refresh")
+                        }
+                    }}
+                </div>
+            </Show>
             <div class="top-0 bg-black text-white w-full items-center z-50 pt-4 pl-4">
                 <div class="flex flex-row justify-start">
                     <BackButton fallback="/".to_string()/>
                 </div>
             </div>
             <div class="flex flex-col w-dvw min-h-dvh bg-black gap-4 px-4 pt-4 pb-12">
-                <div class="grid grid-cols-2 grid-rows-1 items-center w-full">
-                    <AuthCansProvider fallback=FallbackGreeter let:cans>
-                        <ProfileGreeter details=cans.profile_details()/>
-                    </AuthCansProvider>
-                </div>
+                <div class="grid grid-cols-2 grid-rows-1 items-center w-full">{greeter.run()}</div>
+                {summary.map(|s| s.run())}
                 <div class="flex flex-col w-full items-center mt-6 text-white">
-                    <span class="text-md lg:text-lg uppercase">Your Coyns Balance</span>
-                    <WithAuthCans fallback=BalanceFallback with=balance_fetch let:bal>
-                        <div class="text-xl lg:text-2xl">{bal.1}</div>
-                    </WithAuthCans>
+                    <span class="text-md lg:text-lg uppercase">{t("wallet.balance")}</span>
+                    {balance.run()}
+                    {details.map(|d| d.run())}
                 </div>
-                <Show when=move || !is_connected()>
-                    <div class="flex flex-col w-full py-5 items-center">
-                        <div class="flex flex-row w-9/12 md:w-5/12 items-center">
-                            <ConnectLogin
-                                login_text="Login to claim your COYNs"
-                                cta_location="wallet"
-                            />
-                        </div>
-                    </div>
-                </Show>
+                {login_cta.map(|l| l.run())}
                 <div class="flex flex-col w-full gap-2">
                     <div class="flex flex-row w-full items-end justify-between">
-                        <span class="text-white text-sm md:text-md">Recent Transactions</span>
-                        <a href="/transactions" class="text-white/50 text-md md:text-lg">
-                            See All
+                        <div class="flex flex-row gap-2 items-center">
+                            <span class="text-white text-sm md:text-md">{t("wallet.recent_txns")}</span>
+                            {on_refresh
+                                .map(|on_refresh| {
+                                    view! {
+                                        <button
+                                            class="text-white/50 text-md md:text-lg"
+                                            aria-label=t("wallet.refresh")
+                                            on:click=move |_| on_refresh(())
+                                        >
+                                            <Icon icon=icondata::AiReloadOutlined/>
+                                        </button>
+                                    }
+                                })}
+                        </div>
+                        <a href="/wallet/transactions" class="text-white/50 text-md md:text-lg">
+                            {t("wallet.see_all")}
                         </a>
                     </div>
-                    <div class="flex flex-col divide-y divide-white/10">
-                        <WithAuthCans fallback=BulletLoader with=history_fetch let:history>
-                            <For each=move || history.1.clone() key=|inf| inf.key() let:info>
-                                <TxnView info/>
-                            </For>
-                        </WithAuthCans>
+                    <div class="flex flex-col">{transactions.run()}</div>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn RecentTxns(canisters: Canisters<true>, txns: Vec<TxnInfo>) -> impl IntoView {
+    let txns = create_rw_signal(txns);
+    let highlighted = use_new_txn_poll(canisters, txns, RECENT_TXN_CNT);
+    let cache = use_context::<WalletCache>();
+    create_effect(move |_| {
+        if let Some(cache) = cache {
+            cache.set_recent_txns(txns());
+        }
+    });
+
+    view! { {move || view! { <TxnGroups txns=txns() highlighted=highlighted()/> }} }
+}
+
+#[component]
+fn TxnsEmpty() -> impl IntoView {
+    view! {
+        <div class="flex flex-col w-full items-center gap-2 py-6 text-white/50">
+            <Icon class="text-4xl" icon=icondata::BiWalletRegular/>
+            <span class="text-sm md:text-md">{t("wallet.no_txns")}</span>
+        </div>
+    }
+}
+
+#[component]
+fn TxnsError(retry: impl Fn() + 'static) -> impl IntoView {
+    view! {
+        <div class="flex flex-col w-full items-center gap-1 py-6">
+            <span class="text-white/50 text-sm md:text-md">{t("wallet.txns_failed")}</span>
+            <button class="text-primary-600 text-sm md:text-md" on:click=move |_| retry()>
+                {t("wallet.tap_to_retry")}
+            </button>
+        </div>
+    }
+}
+
+/// Most recent transactions, distinguishing loading, empty and failed states
+#[component]
+fn RecentHistory(canisters: Canisters<true>) -> impl IntoView {
+    let cans = canisters.clone();
+    let history = create_resource(
+        || (),
+        move |_| {
+            let history_prov = get_history_provider(cans.clone());
+            async move {
+                history_prov
+                    .get_by_cursor(0, RECENT_TXN_CNT)
+                    .await
+                    .map(|p| p.data)
+                    .map_err(|e| {
+                        log::warn!("failed to fetch recent transactions: {e}");
+                        e.to_string()
+                    })
+            }
+        },
+    );
+    let cache = use_context::<WalletCache>();
+
+    view! {
+        <Suspense fallback=move || match cache {
+            Some(cache) => view! { <CachedTxns cache/> }.into_view(),
+            None => view! { <BulletLoader/> }.into_view(),
+        }>
+            {move || {
+                history()
+                    .map(|res| match res {
+                        Ok(txns) if txns.is_empty() => view! { <TxnsEmpty/> }.into_view(),
+                        Ok(txns) => {
+                            view! { <RecentTxns canisters=canisters.clone() txns/> }.into_view()
+                        }
+                        Err(_) => view! { <TxnsError retry=move || history.refetch()/> }.into_view(),
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+/// Last known transactions if cached, loader otherwise
+#[component]
+fn CachedTxns(cache: WalletCache) -> impl IntoView {
+    move || match cache.stale().and_then(|s| s.recent_txns) {
+        Some(txns) => view! {
+            <div class="flex flex-col opacity-50">
+                <TxnGroups txns/>
+            </div>
+        }
+        .into_view(),
+        None => view! { <BulletLoader/> }.into_view(),
+    }
+}
+
+#[component]
+fn LiveWallet() -> impl IntoView {
+    // Balance and history calls would fail with an expired delegation
+    create_effect(move |_| _ = ensure_valid_delegation());
+    // Returning users see their last known wallet while it revalidates
+    let cache = WalletCache::new();
+    provide_context(cache);
+    // Shared by the summary and the breakdown
+    provide_context(HistoryScans::default());
+
+    let refresh = create_trigger();
+    provide_context(WalletRefresh(refresh));
+    // Rapid taps must not spawn overlapping canister calls
+    let throttled_refresh = use_throttle_fn(move || refresh.notify(), WALLET_REFRESH_THROTTLE_MS);
+    let on_refresh = Callback::new(move |()| {
+        throttled_refresh();
+    });
+
+    view! {
+        <WalletLayout
+            greeter=|| {
+                view! {
+                    <AuthCansProvider fallback=move || view! { <CachedGreeter cache/> } let:cans>
+                        <ProfileGreeter canisters=cans/>
+                    </AuthCansProvider>
+                }
+            }
+            summary=|| {
+                view! {
+                    <AuthCansProvider let:cans>
+                        <WalletSummary canisters=cans/>
+                    </AuthCansProvider>
+                }
+            }
+            balance=|| {
+                view! {
+                    <StaleIndicator cache/>
+                    <div class="relative flex flex-col items-center">
+                        <CoinDrop/>
+                        <AuthCansProvider fallback=move || view! { <CachedBalance cache/> } let:cans>
+                            <Balance canisters=cans/>
+                        </AuthCansProvider>
+                    </div>
+                }
+            }
+            details=|| {
+                view! {
+                    <AuthCansProvider let:cans>
+                        <BalanceDetails canisters=cans/>
+                    </AuthCansProvider>
+                }
+            }
+            login_cta=|| {
+                view! {
+                    <div class="flex flex-row w-full justify-center">
+                        <SwitchAccount/>
                     </div>
+                }
+            }
+            on_refresh
+            transactions=move || {
+                // Remount to refetch, showing the loader in the meantime
+                move || {
+                    refresh.track();
+                    view! {
+                        <AuthCansProvider fallback=move || view! { <CachedTxns cache/> } let:cans>
+                            <RecentHistory canisters=cans/>
+                        </AuthCansProvider>
+                    }
+                }
+            }
+        />
+    }
+}
+
+/// Shown instead of the wallet until the user logs in
+#[component]
+fn ConnectWallet() -> impl IntoView {
+    view! {
+        <div class="flex flex-col w-dvw min-h-dvh bg-black gap-4 px-4 pt-4 pb-12">
+            <div class="flex flex-row justify-start">
+                <BackButton fallback="/".to_string()/>
+            </div>
+            <div class="flex flex-col flex-1 w-full items-center justify-center gap-6 text-white">
+                <Icon class="text-6xl text-primary-600" icon=icondata::BiWalletRegular/>
+                <div class="flex flex-col items-center gap-2 text-center">
+                    <span class="text-xl md:text-2xl font-semibold">
+                        {t("wallet.connect_title")}
+                    </span>
+                    <span class="text-white/50 text-sm md:text-md">
+                        {t("wallet.connect_subtitle")}
+                    </span>
+                </div>
+                <div class="flex flex-row w-9/12 md:w-5/12 items-center">
+                    <ConnectLogin login_text=t("wallet.connect_cta") cta_location="wallet"/>
                 </div>
             </div>
         </div>
     }
 }
+
+/// Wallet of the logged in user, [ConnectWallet] otherwise
+/// the wallet's resources are created (and fetched) once the user connects
+#[component]
+fn ConnectedWallet() -> impl IntoView {
+    let (is_connected, _) = account_connected_reader();
+    // The connection status is only known after hydration
+    // the wallet skeleton is rendered until then
+    let hydrated = create_rw_signal(false);
+    create_effect(move |_| hydrated.set(true));
+
+    view! {
+        <Show when=move || is_connected() || !hydrated() fallback=ConnectWallet>
+            <LiveWallet/>
+        </Show>
+    }
+}
+
+#[component]
+pub fn Wallet() -> impl IntoView {
+    let preview = use_preview_mode();
+
+    view! {
+        <Suspense>
+            {move || {
+                preview()
+                    .map(|preview| {
+                        if preview {
+                            view! { <PreviewWallet/> }.into_view()
+                        } else {
+                            view! { <ConnectedWallet/> }.into_view()
+                        }
+                    })
+            }}
+        </Suspense>
+    }
+}
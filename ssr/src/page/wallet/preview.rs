@@ -0,0 +1,68 @@
+use candid::Principal;
+use leptos::*;
+use web_time::Duration;
+
+use crate::utils::{current_epoch, profile::propic_from_principal, token::Balance};
+
+use super::{
+    txn::{TxnGroups, TxnInfo, TxnTag},
+    WalletLayout,
+};
+
+const SAMPLE_DISPLAY_NAME: &str = "Sample Creator";
+const SAMPLE_BALANCE: u64 = 12_450;
+
+/// Fixed sample history, (tag, amount, hours ago)
+const SAMPLE_TXNS: [(TxnTag, u64, u64); 6] = [
+    (TxnTag::Winnings, 400, 2),
+    (TxnTag::BetPlaced, 200, 3),
+    (TxnTag::Commission, 45, 9),
+    (TxnTag::Referral, 500, 26),
+    (TxnTag::BetPlaced, 100, 30),
+    (TxnTag::SignupBonus, 1000, 72),
+];
+
+fn sample_txns() -> Vec<TxnInfo> {
+    let now = current_epoch();
+    SAMPLE_TXNS
+        .into_iter()
+        .enumerate()
+        .map(|(id, (tag, amount, hours_ago))| TxnInfo {
+            tag,
+            amount,
+            id: id as u64,
+            timestamp: now.saturating_sub(Duration::from_secs(hours_ago * 3600)),
+            counterparty: None,
+            post: None,
+        })
+        .collect()
+}
+
+/// Wallet rendered with sample data, see [crate::utils::preview]
+#[component]
+pub fn PreviewWallet() -> impl IntoView {
+    view! {
+        <WalletLayout
+            greeter=|| {
+                view! {
+                    <div class="flex flex-col">
+                        <span class="text-white/50 text-md">Welcome!</span>
+                        <span class="text-white text-lg md:text-xl truncate">
+                            {SAMPLE_DISPLAY_NAME}
+                        </span>
+                    </div>
+                    <div class="w-16 aspect-square overflow-clip justify-self-end rounded-full">
+                        <img
+                            class="h-full w-full object-cover"
+                            src=propic_from_principal(Principal::anonymous())
+                        />
+                    </div>
+                }
+            }
+            balance=|| view! { <div class="text-xl lg:text-2xl">{Balance::coyns(SAMPLE_BALANCE).to_string()}</div> }
+            transactions=|| {
+                view! { <TxnGroups txns=sample_txns()/> }
+            }
+        />
+    }
+}
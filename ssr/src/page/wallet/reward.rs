@@ -0,0 +1,138 @@
+//! Live updates of the recent transactions
+//! new rewards drop into the balance and are highlighted in the list
+use std::collections::HashSet;
+
+use gloo::timers::callback::Timeout;
+use leptos::*;
+use leptos_icons::*;
+
+use crate::{
+    component::infinite_scroller::CursoredDataProvider,
+    state::{
+        app_events::{AppEvent, AppEvents},
+        canisters::Canisters,
+    },
+};
+
+use super::txn::{provider::get_history_provider, TxnInfo};
+
+/// Interval between checks for new transactions
+const NEW_TXN_POLL_INTERVAL_MS: u64 = 30_000;
+/// Duration (in milliseconds) of the highlight on new rewards
+pub const REWARD_HIGHLIGHT_MS: u32 = 3000;
+/// Duration (in milliseconds) of the coin drop animation
+const COIN_DROP_MS: u32 = 1200;
+
+/// Poll for transactions newer than the ones in `txns`
+///
+/// New transactions are prepended to `txns`, rewards among them are
+/// added to the returned set until their highlight has faded
+/// The set is owned by the calling component, so highlights never replay
+/// after navigating away and back
+pub fn use_new_txn_poll(
+    canisters: Canisters<true>,
+    txns: RwSignal<Vec<TxnInfo>>,
+    page_size: usize,
+) -> RwSignal<HashSet<u64>> {
+    let highlighted = create_rw_signal(HashSet::<u64>::new());
+    let polling = store_value(false);
+    let provider = store_value(get_history_provider(canisters));
+
+    let poll = move || {
+        if polling.get_value() {
+            return;
+        }
+        polling.set_value(true);
+        let provider = provider.get_value();
+        spawn_local(async move {
+            let res = provider.get_by_cursor(0, page_size).await;
+            _ = polling.try_set_value(false);
+            let page = match res {
+                Ok(page) => page.data,
+                Err(e) => {
+                    log::warn!("failed to poll for new transactions: {e}");
+                    return;
+                }
+            };
+            let Some(known) =
+                txns.try_with_untracked(|t| t.iter().map(|t| t.id).collect::<HashSet<_>>())
+            else {
+                return;
+            };
+            let new: Vec<_> = page
+                .iter()
+                .filter(|t| !known.contains(&t.id))
+                .copied()
+                .collect();
+            if new.is_empty() {
+                return;
+            }
+
+            let rewards: Vec<_> = new.iter().filter(|t| t.tag.is_reward()).collect();
+            let reward_total = rewards
+                .iter()
+                .fold(0u64, |acc, t| acc.saturating_add(t.amount));
+            let reward_ids: Vec<_> = rewards.iter().map(|t| t.id).collect();
+            batch(|| {
+                txns.set(page);
+                highlighted.update(|h| h.extend(reward_ids.iter().copied()));
+            });
+            Timeout::new(REWARD_HIGHLIGHT_MS, move || {
+                _ = highlighted.try_update(|h| reward_ids.iter().for_each(|id| _ = h.remove(id)));
+            })
+            .forget();
+
+            let events = AppEvents::get();
+            if reward_total > 0 {
+                events.publish(AppEvent::RewardReceived {
+                    amount: reward_total,
+                });
+            } else {
+                events.publish(AppEvent::BalanceChanged);
+            }
+        });
+    };
+
+    if let Ok(handle) = set_interval_with_handle(
+        poll,
+        std::time::Duration::from_millis(NEW_TXN_POLL_INTERVAL_MS),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    highlighted
+}
+
+/// Coin dropping into the balance on [AppEvent::RewardReceived]
+#[component]
+pub fn CoinDrop() -> impl IntoView {
+    let drop = create_rw_signal(None::<(u64, u64)>);
+    AppEvents::get().subscribe(move |ev| {
+        let &AppEvent::RewardReceived { amount } = ev else {
+            return;
+        };
+        let seq = drop.with_untracked(|d| d.map_or(0, |(_, seq)| seq + 1));
+        drop.set(Some((amount, seq)));
+        Timeout::new(COIN_DROP_MS, move || {
+            // Only clear if no newer drop replaced this one
+            if drop.try_get_untracked().flatten().map(|(_, s)| s) == Some(seq) {
+                _ = drop.try_set(None);
+            }
+        })
+        .forget();
+    });
+
+    view! {
+        {move || {
+            drop()
+                .map(|(amount, _)| {
+                    view! {
+                        <div class="absolute -top-8 inset-x-0 flex flex-row gap-1 justify-center items-center text-primary-600 font-semibold pointer-events-none animate-coin-drop">
+                            <Icon icon=icondata::BiCoinRegular/>
+                            {format!("+{amount}")}
+                        </div>
+                    }
+                })
+        }}
+    }
+}
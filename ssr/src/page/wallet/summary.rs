@@ -0,0 +1,113 @@
+use leptos::*;
+
+use crate::{
+    state::canisters::Canisters,
+    utils::{current_epoch, format_count},
+};
+
+use super::{
+    history_scan::HistoryScans,
+    txn::{SummaryPeriod, TxnSummary},
+};
+
+fn format_net(net: i128) -> String {
+    let sign = if net < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_count(net.unsigned_abs() as u64))
+}
+
+#[component]
+fn SummaryStat(
+    label: &'static str,
+    value: String,
+    #[prop(optional)] class: &'static str,
+) -> impl IntoView {
+    view! {
+        <div class="flex flex-col flex-1 items-center gap-1">
+            <span class="text-white/50 text-xs md:text-sm">{label}</span>
+            <span class=format!("text-lg md:text-xl font-semibold {class}")>{value}</span>
+        </div>
+    }
+}
+
+#[component]
+fn SummaryStats(summary: TxnSummary) -> impl IntoView {
+    let net = summary.net();
+    view! {
+        <div class="flex flex-row w-full">
+            <SummaryStat label="Earned" value=format_count(summary.earned)/>
+            <SummaryStat label="Spent" value=format_count(summary.spent)/>
+            <SummaryStat
+                label="Net"
+                value=format_net(net)
+                class=if net < 0 { "text-red-600" } else { "text-green-600" }
+            />
+        </div>
+        {summary
+            .truncated
+            .then(|| {
+                view! {
+                    <span class="self-center text-white/50 text-xs">
+                        Only your latest transactions are included
+                    </span>
+                }
+            })}
+    }
+}
+
+/// Earned, spent and net totals over a selectable period
+#[component]
+pub fn WalletSummary(canisters: Canisters<true>) -> impl IntoView {
+    let period = create_rw_signal(SummaryPeriod::default());
+    // Switching periods re-aggregates the same scan instead of fetching again
+    let scan = HistoryScans::get(canisters);
+    let summary = move || {
+        scan.with(|res| {
+            res.as_ref().map(|res| {
+                res.as_ref()
+                    .map(|scan| scan.summary(period(), current_epoch()))
+                    .map_err(|_| ())
+            })
+        })
+    };
+
+    view! {
+        <div class="flex flex-col w-full gap-3 p-4 rounded-xl bg-white/5 text-white">
+            <div class="flex flex-row gap-2 self-center">
+                {SummaryPeriod::ALL
+                    .into_iter()
+                    .map(|p| {
+                        view! {
+                            <button
+                                class="px-3 py-1 rounded-full text-xs md:text-sm whitespace-nowrap"
+                                class=("bg-primary-600", move || period() == p)
+                                class=("bg-white/10", move || period() != p)
+                                class=("text-white/50", move || period() != p)
+                                on:click=move |_| period.set(p)
+                            >
+                                {p.label()}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+            <Suspense fallback=|| {
+                view! { <div class="w-full rounded-full py-4 bg-white/10 animate-pulse"></div> }
+            }>
+                {move || {
+                    summary()
+                        .map(|res| match res {
+                            Ok(summary) => view! { <SummaryStats summary/> }.into_view(),
+                            Err(_) => {
+                                view! {
+                                    <span class="self-center text-white/50 text-sm">
+                                        Summary unavailable
+                                    </span>
+                                }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
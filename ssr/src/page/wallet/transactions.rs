@@ -1,27 +1,103 @@
 use leptos::*;
+use leptos_icons::Icon;
+use leptos_router::*;
+use leptos_use::{use_event_listener, use_throttle_fn_with_arg, use_window};
 
 use crate::{
     component::{
         back_btn::BackButton, bullet_loader::BulletLoader, canisters_prov::AuthCansProvider,
         infinite_scroller::InfiniteScroller, title::Title,
     },
-    state::canisters::Canisters,
+    state::{
+        canisters::Canisters,
+        local_storage::{use_list_scroll_store, ListScroll},
+    },
+    utils::i18n::t,
 };
 
-use super::txn::{provider::get_history_provider, TxnView};
+use super::txn::{
+    provider::{
+        get_filtered_history_provider, get_history_provider, get_listed_history_provider,
+        HistoryProvider,
+    },
+    ListedTxnView, SwipedTxn, TxnDetails, TxnFilter,
+};
 
 const FETCH_CNT: usize = 15;
+const SCROLL_SAVE_THROTTLE_MS: f64 = 500.0;
+
+/// Key of a transaction list in the [ListScroll] store
+fn scroll_key(path: &str, filter: TxnFilter) -> String {
+    format!("{path}#{filter:?}")
+}
 
 #[component]
-pub fn TransactionList(canisters: Canisters<true>) -> impl IntoView {
-    let provider = get_history_provider(canisters);
+pub fn TransactionList(canisters: Canisters<true>, filter: TxnFilter) -> impl IntoView {
+    let provider = get_listed_history_provider(get_filtered_history_provider(canisters, filter));
+    SwipedTxn::provide();
+
+    let key = scroll_key(&use_location().pathname.get_untracked(), filter);
+    let (scroll_store, set_scroll_store, _) = use_list_scroll_store();
+    let saved = scroll_store.with_untracked(|s| s.get(&key).copied());
+    let loaded = store_value(0usize);
+    // scroll events are ignored until the saved position is restored
+    let restored = store_value(saved.is_none());
+
+    let on_load = move |count: usize| {
+        loaded.set_value(count);
+        if restored.get_value() {
+            return;
+        }
+        restored.set_value(true);
+        let Some(saved) = saved else {
+            return;
+        };
+        request_animation_frame(move || window().scroll_to_with_x_and_y(0.0, saved.offset));
+    };
+
+    let save_scroll = use_throttle_fn_with_arg(
+        move |pos: ListScroll| {
+            let key = key.clone();
+            set_scroll_store.update(|s| {
+                s.insert(key, pos);
+            })
+        },
+        SCROLL_SAVE_THROTTLE_MS,
+    );
+    _ = use_event_listener(use_window(), ev::scroll, move |_| {
+        if !restored.get_value() {
+            return;
+        }
+        save_scroll(ListScroll {
+            offset: window().scroll_y().unwrap_or_default(),
+            items: loaded.get_value(),
+        });
+    });
+
     view! {
         <div class="flex flex-col w-full items-center">
             <InfiniteScroller
                 provider
                 fetch_count=FETCH_CNT
-                children=|info, _ref| {
-                    view! { <TxnView info _ref=_ref.unwrap_or_default()/> }
+                initial_count=saved.map(|s| s.items).unwrap_or_default()
+                on_load
+                children=|txn, _ref| {
+                    view! { <ListedTxnView txn _ref=_ref.unwrap_or_default()/> }
+                }
+                empty_content=move || {
+                    let text = if filter == TxnFilter::All {
+                        t("wallet.no_txns")
+                    } else {
+                        t("wallet.no_txns_of_type")
+                    };
+                    view! { <span class="py-4 text-white/50 text-sm md:text-md">{text}</span> }
+                }
+                end_content=|| {
+                    view! {
+                        <span class="py-4 text-white/50 text-sm md:text-md">
+                            {t("wallet.end_of_history")}
+                        </span>
+                    }
                 }
             />
 
@@ -29,20 +105,144 @@ pub fn TransactionList(canisters: Canisters<true>) -> impl IntoView {
     }
 }
 
+#[component]
+fn FilterChips(filter: RwSignal<TxnFilter>) -> impl IntoView {
+    view! {
+        <div class="flex flex-row w-full gap-2 overflow-x-auto">
+            {TxnFilter::ALL
+                .into_iter()
+                .map(|f| {
+                    view! {
+                        <button
+                            class="px-4 py-1 rounded-full text-sm md:text-md whitespace-nowrap"
+                            class=("bg-primary-600", move || filter() == f)
+                            class=("text-white", move || filter() == f)
+                            class=("bg-white/10", move || filter() != f)
+                            class=("text-white/50", move || filter() != f)
+                            on:click=move |_| filter.set(f)
+                        >
+                            {f.label()}
+                        </button>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
 #[component]
 pub fn Transactions() -> impl IntoView {
+    let filter = create_rw_signal(TxnFilter::default());
+    let refresh = create_trigger();
+    let (_, set_scroll_store, _) = use_list_scroll_store();
+
+    // Start over from the latest transactions, forgetting the saved scroll positions
+    let on_refresh = move |_| {
+        let path = use_location().pathname.get_untracked();
+        set_scroll_store.update(|s| {
+            s.retain(|key, _| key.split_once('#').map(|(p, _)| p) != Some(path.as_str()))
+        });
+        window().scroll_to_with_x_and_y(0.0, 0.0);
+        refresh.notify();
+    };
+
     view! {
         <div class="flex items-center flex-col w-dvw min-h-dvh gap-10 bg-black pt-4 px-4 pb-12">
             <Title justify_center=false>
                 <div class="flex flex-row justify-between">
                     <BackButton fallback="/wallet".to_string()/>
                     <span class="text-xl text-white font-bold">Transactions</span>
-                    <div></div>
+                    <button on:click=on_refresh class="text-xl text-white">
+                        <Icon icon=icondata::AiReloadOutlined/>
+                    </button>
                 </div>
             </Title>
+            <FilterChips filter/>
             <AuthCansProvider fallback=BulletLoader let:canisters>
-                <TransactionList canisters/>
+                {move || {
+                    refresh.track();
+                    let canisters = canisters.clone();
+                    view! { <TransactionList canisters filter=filter()/> }
+                }}
             </AuthCansProvider>
         </div>
     }
 }
+
+#[derive(Params, PartialEq)]
+struct TxnParams {
+    id: u64,
+}
+
+#[component]
+fn TxnNotFound() -> impl IntoView {
+    view! {
+        <div class="flex flex-col items-center gap-2 py-6 text-white/50 text-sm md:text-md">
+            <span>Transaction not found</span>
+            <a href="/wallet/transactions" class="text-primary-600">
+                See all transactions
+            </a>
+        </div>
+    }
+}
+
+#[component]
+fn TxnLoader(canisters: Canisters<true>, id: u64) -> impl IntoView {
+    let txn = create_resource(
+        || (),
+        move |_| {
+            let provider = get_history_provider(canisters.clone());
+            async move {
+                provider.get_transaction(id).await.unwrap_or_else(|e| {
+                    log::warn!("failed to look up transaction {id}: {e}");
+                    None
+                })
+            }
+        },
+    );
+
+    view! {
+        <Suspense fallback=BulletLoader>
+            {move || {
+                txn()
+                    .map(|txn| match txn {
+                        Some(info) => view! { <TxnDetails info/> }.into_view(),
+                        None => view! { <TxnNotFound/> }.into_view(),
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+/// Single transaction of the current user, linkable from support requests
+/// transactions of other users are never found
+#[component]
+pub fn TransactionDetails() -> impl IntoView {
+    let params = use_params::<TxnParams>();
+    let id = move || params.with(|p| p.as_ref().ok().map(|p| p.id));
+
+    view! {
+        <div class="flex items-center flex-col w-dvw min-h-dvh gap-10 bg-black pt-4 px-4 pb-12">
+            <Title justify_center=false>
+                <div class="flex flex-row justify-between">
+                    <BackButton fallback="/wallet/transactions".to_string()/>
+                    <span class="text-xl text-white font-bold">Transaction</span>
+                    <div></div>
+                </div>
+            </Title>
+            <div class="flex flex-col w-full md:w-5/12">
+                {move || match id() {
+                    Some(id) => {
+                        view! {
+                            <AuthCansProvider fallback=BulletLoader let:canisters>
+                                <TxnLoader canisters id/>
+                            </AuthCansProvider>
+                        }
+                            .into_view()
+                    }
+                    None => view! { <TxnNotFound/> }.into_view(),
+                }}
+            </div>
+        </div>
+    }
+}
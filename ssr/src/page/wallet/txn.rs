@@ -1,10 +1,25 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+};
 
+use candid::Principal;
 use leptos::*;
 use leptos_icons::Icon;
+use leptos_use::{use_event_listener, use_window};
 use serde::{Deserialize, Serialize};
+use web_time::Duration;
 
-use crate::component::infinite_scroller::KeyedData;
+use crate::{
+    component::{infinite_scroller::KeyedData, modal::Modal, toast::Toasts},
+    utils::{
+        current_epoch,
+        i18n::t,
+        posts::get_base_url,
+        timestamp::{get_date_time, get_day_month, get_day_month_year, to_local_epoch_secs},
+        web::{copy_to_clipboard_checked, share_url},
+    },
+};
 
 #[derive(Clone, Copy)]
 pub enum TxnDirection {
@@ -24,6 +39,17 @@ impl TxnDirection {
     }
 }
 
+impl TxnDirection {
+    fn to_text(self) -> &'static str {
+        use TxnDirection::*;
+        match self {
+            Bonus => t("txn.direction.minted"),
+            Added => t("txn.direction.received"),
+            Deducted => t("txn.direction.sent"),
+        }
+    }
+}
+
 impl From<TxnDirection> for &'static icondata_core::IconData {
     fn from(val: TxnDirection) -> Self {
         use TxnDirection::*;
@@ -35,7 +61,7 @@ impl From<TxnDirection> for &'static icondata_core::IconData {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TxnTag {
     BetPlaced,
     SignupBonus,
@@ -61,19 +87,75 @@ impl TxnTag {
     fn to_text(self) -> &'static str {
         use TxnTag::*;
         match self {
-            BetPlaced => "Vote Placement",
-            SignupBonus => "Joining Bonus",
-            Referral => "Referral Reward",
-            Winnings => "Vote Winnings",
-            Commission => "Vote Commission",
-            Transfer => "Transfer",
-            HotorNotAccountTransfer => "HotorNot Account Transfer",
+            BetPlaced => t("txn.tag.bet_placed"),
+            SignupBonus => t("txn.tag.signup_bonus"),
+            Referral => t("txn.tag.referral"),
+            Winnings => t("txn.tag.winnings"),
+            Commission => t("txn.tag.commission"),
+            Transfer => t("txn.tag.transfer"),
+            HotorNotAccountTransfer => t("txn.tag.hon_transfer"),
         }
     }
 
     fn icondata(self) -> &'static icondata_core::IconData {
         TxnDirection::from(self).into()
     }
+
+    /// Tokens earned or minted for the user
+    pub fn is_reward(self) -> bool {
+        use TxnTag::*;
+        matches!(self, SignupBonus | Referral | Winnings | Commission)
+    }
+
+    /// Incoming transactions earned by creating content
+    pub fn is_creator_earning(self) -> bool {
+        matches!(self, TxnTag::Commission)
+    }
+}
+
+/// Transaction categories that the history can be filtered by
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum TxnFilter {
+    #[default]
+    All,
+    /// Transfers and vote placements
+    Sent,
+    Received,
+    /// Vote winnings and commissions
+    Rewards,
+    /// Tokens minted for signups and referrals
+    Mints,
+}
+
+impl TxnFilter {
+    pub const ALL: [TxnFilter; 5] = [
+        TxnFilter::All,
+        TxnFilter::Sent,
+        TxnFilter::Received,
+        TxnFilter::Rewards,
+        TxnFilter::Mints,
+    ];
+
+    pub fn matches(self, tag: TxnTag) -> bool {
+        use TxnTag::*;
+        match self {
+            TxnFilter::All => true,
+            TxnFilter::Sent => matches!(tag, BetPlaced | Transfer),
+            TxnFilter::Received => matches!(tag, HotorNotAccountTransfer),
+            TxnFilter::Rewards => matches!(tag, Winnings | Commission),
+            TxnFilter::Mints => matches!(tag, SignupBonus | Referral),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TxnFilter::All => t("txn.filter.all"),
+            TxnFilter::Sent => t("txn.filter.sent"),
+            TxnFilter::Received => t("txn.filter.received"),
+            TxnFilter::Rewards => t("txn.filter.rewards"),
+            TxnFilter::Mints => t("txn.filter.mints"),
+        }
+    }
 }
 
 impl Display for TxnTag {
@@ -82,11 +164,19 @@ impl Display for TxnTag {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TxnInfo {
     pub tag: TxnTag,
     pub amount: u64,
     pub id: u64,
+    /// Time of the transaction, since the unix epoch
+    pub timestamp: Duration,
+    /// The other account involved in transfers
+    #[serde(default)]
+    pub counterparty: Option<Principal>,
+    /// Post (canister id, post id) the vote or reward was for
+    #[serde(default)]
+    pub post: Option<(Principal, u64)>,
 }
 
 impl KeyedData for TxnInfo {
@@ -98,38 +188,591 @@ impl KeyedData for TxnInfo {
 }
 
 #[component]
-pub fn TxnView(info: TxnInfo, #[prop(optional)] _ref: NodeRef<html::Div>) -> impl IntoView {
+fn TxnDetail(label: &'static str, children: Children) -> impl IntoView {
+    view! {
+        <div class="flex flex-row w-full gap-4 justify-between items-center">
+            <span class="shrink-0 text-white/50">{label}</span>
+            {children()}
+        </div>
+    }
+}
+
+/// Shareable link to a transaction, see [crate::page::wallet::transactions::TransactionDetails]
+pub fn txn_url(id: u64) -> String {
+    format!("{}/wallet/txn/{id}", get_base_url())
+}
+
+#[component]
+fn TxnLinkButton(id: u64) -> impl IntoView {
+    let toasts = Toasts::get();
+    let share = move |_| {
+        let url = txn_url(id);
+        let copy = move |url: String| {
+            let copying = copy_to_clipboard_checked(&url);
+            spawn_local(async move {
+                if copying.await.is_some() {
+                    toasts.success(t("txn.link_copied"));
+                } else {
+                    toasts.error(t("txn.link_copy_failed"));
+                }
+            });
+        };
+        // Sharing must start synchronously with the click, see [share_url]
+        let Some(sharing) = share_url(&url) else {
+            copy(url);
+            return;
+        };
+        spawn_local(async move {
+            if sharing.await.needs_fallback() {
+                copy(url);
+            }
+        });
+    };
+
+    view! {
+        <button class="flex flex-row gap-2 items-center self-center text-primary-600" on:click=share>
+            <Icon icon=icondata::AiLinkOutlined/>
+            {t("txn.copy_link")}
+        </button>
+    }
+}
+
+#[component]
+pub fn TxnDetails(info: TxnInfo) -> impl IntoView {
+    let direction = TxnDirection::from(info.tag);
+    let toasts = Toasts::get();
+    let copy_counterparty = move |principal: &str| {
+        let copying = copy_to_clipboard_checked(principal);
+        spawn_local(async move {
+            if copying.await.is_some() {
+                toasts.success(t("txn.counterparty_copied"));
+            } else {
+                toasts.error(t("txn.counterparty_copy_failed"));
+            }
+        });
+    };
+
+    view! {
+        <div class="flex flex-col w-full gap-3 text-sm md:text-md text-white">
+            <span class="text-lg font-semibold self-center">{info.tag.to_text()}</span>
+            <TxnDetail label=t("txn.amount")>
+                <span>{info.amount} COYNs</span>
+            </TxnDetail>
+            <TxnDetail label=t("txn.token")>
+                <span>COYNs</span>
+            </TxnDetail>
+            <TxnDetail label=t("txn.direction")>
+                <span>{direction.to_text()}</span>
+            </TxnDetail>
+            <TxnDetail label=t("txn.time")>
+                <span class="text-right">{get_date_time(info.timestamp.as_secs())}</span>
+            </TxnDetail>
+            {info
+                .counterparty
+                .map(|principal| {
+                    let principal = principal.to_text();
+                    view! {
+                        <TxnDetail label=t("txn.counterparty")>
+                            <button
+                                class="flex flex-row gap-2 items-center min-w-0"
+                                on:click={
+                                    let principal = principal.clone();
+                                    move |_| copy_counterparty(&principal)
+                                }
+                            >
+                                <span class="truncate">{principal}</span>
+                                <Icon class="shrink-0 text-primary-600" icon=icondata::FaCopyRegular/>
+                            </button>
+                        </TxnDetail>
+                    }
+                })}
+            {info
+                .post
+                .map(|(canister_id, post_id)| {
+                    view! {
+                        <a
+                            class="self-center text-primary-600 underline"
+                            href=format!("/hot-or-not/{canister_id}/{post_id}")
+                        >
+                            {t("txn.view_post")}
+                        </a>
+                    }
+                })}
+            <TxnLinkButton id=info.id/>
+        </div>
+    }
+}
+
+/// Width (in pixels) of the quick actions revealed by swiping a row
+const TXN_ACTIONS_PX: i32 = 160;
+
+/// Movement (in pixels) before a touch is treated as a swipe or a scroll
+const SWIPE_SLOP_PX: i32 = 10;
+
+/// Transaction whose quick actions are revealed
+/// at most one row is swiped open at a time
+#[derive(Clone, Copy)]
+pub struct SwipedTxn(RwSignal<Option<u64>>);
+
+impl SwipedTxn {
+    /// Provide for a list of [TxnView]s
+    /// scrolling the page closes the revealed actions
+    pub fn provide() {
+        let swiped = Self(create_rw_signal(None));
+        provide_context(swiped);
+        _ = use_event_listener(use_window(), ev::scroll, move |_| {
+            if swiped.0.with_untracked(|s| s.is_some()) {
+                swiped.0.set(None);
+            }
+        });
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SwipeAxis {
+    Undecided,
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Copy)]
+struct SwipeStart {
+    x: i32,
+    y: i32,
+    /// Offset of the row when the touch started
+    offset: i32,
+    axis: SwipeAxis,
+}
+
+#[component]
+fn TxnQuickActions(
+    info: TxnInfo,
+    selected: RwSignal<bool>,
+    swiped: RwSignal<Option<u64>>,
+) -> impl IntoView {
+    let toasts = Toasts::get();
+    let copy_counterparty = move |principal: Principal| {
+        swiped.set(None);
+        let copying = copy_to_clipboard_checked(&principal.to_text());
+        spawn_local(async move {
+            if copying.await.is_some() {
+                toasts.success(t("txn.counterparty_copied"));
+            } else {
+                toasts.error(t("txn.counterparty_copy_failed"));
+            }
+        });
+    };
+
+    view! {
+        <div
+            class="absolute inset-y-0 right-0 flex flex-row text-white text-xs md:text-sm"
+            style:width=format!("{TXN_ACTIONS_PX}px")
+        >
+            <button
+                class="flex flex-col flex-1 gap-1 items-center justify-center bg-neutral-800"
+                on:click=move |_| {
+                    swiped.set(None);
+                    selected.set(true);
+                }
+            >
+                <Icon class="text-lg" icon=icondata::AiInfoCircleOutlined/>
+                {t("txn.details")}
+            </button>
+            {info
+                .counterparty
+                .map(|principal| {
+                    view! {
+                        <button
+                            class="flex flex-col flex-1 gap-1 items-center justify-center bg-primary-600"
+                            on:click=move |_| copy_counterparty(principal)
+                        >
+                            <Icon class="text-lg" icon=icondata::FaCopyRegular/>
+                            {t("txn.copy")}
+                        </button>
+                    }
+                })}
+        </div>
+    }
+}
+
+#[component]
+pub fn TxnView(
+    info: TxnInfo,
+    #[prop(optional)] _ref: NodeRef<html::Div>,
+    /// Briefly highlights the transaction, for newly arrived rewards
+    #[prop(optional)]
+    highlight: bool,
+) -> impl IntoView {
     let direction = TxnDirection::from(info.tag);
     let bal_res = format!(
         "{} {}",
         if direction.positive() { "+" } else { "-" },
         info.amount
     );
+    let selected = create_rw_signal(false);
+
+    // Swiping left reveals the quick actions,
+    // rows outside a list providing [SwipedTxn] are never swiped open
+    let swiped = use_context::<SwipedTxn>().map(|s| s.0);
+    let is_open = move || swiped.is_some_and(|s| s.with(|s| *s == Some(info.id)));
+    let swipe = store_value(None::<SwipeStart>);
+    let drag_offset = create_rw_signal(None::<i32>);
+    // A swipe ends with a click on the row, which must not open the details
+    let swallow_click = store_value(false);
+
+    let offset = move || drag_offset().unwrap_or(if is_open() { -TXN_ACTIONS_PX } else { 0 });
+
+    let on_touchstart = move |ev: ev::TouchEvent| {
+        let Some(touch) = ev.touches().get(0) else {
+            return;
+        };
+        swipe.set_value(Some(SwipeStart {
+            x: touch.client_x(),
+            y: touch.client_y(),
+            offset: offset(),
+            axis: SwipeAxis::Undecided,
+        }));
+    };
+    let on_touchmove = move |ev: ev::TouchEvent| {
+        let (Some(mut start), Some(touch)) = (swipe.get_value(), ev.touches().get(0)) else {
+            return;
+        };
+        let dx = touch.client_x() - start.x;
+        let dy = touch.client_y() - start.y;
+        if start.axis == SwipeAxis::Undecided {
+            if dx.abs() > SWIPE_SLOP_PX && dx.abs() > dy.abs() {
+                start.axis = SwipeAxis::Horizontal;
+            } else if dy.abs() > SWIPE_SLOP_PX {
+                start.axis = SwipeAxis::Vertical;
+            }
+            swipe.set_value(Some(start));
+        }
+        if start.axis != SwipeAxis::Horizontal {
+            return;
+        }
+        drag_offset.set(Some((start.offset + dx).clamp(-TXN_ACTIONS_PX, 0)));
+    };
+    let on_touchend = move |_| {
+        let Some(start) = swipe.get_value() else {
+            return;
+        };
+        swipe.set_value(None);
+        let Some(dragged) = drag_offset.get_untracked() else {
+            return;
+        };
+        drag_offset.set(None);
+        swallow_click.set_value(start.axis == SwipeAxis::Horizontal);
+        let Some(swiped) = swiped else {
+            return;
+        };
+        // Snap to whichever state is closer
+        if dragged < -TXN_ACTIONS_PX / 2 {
+            swiped.set(Some(info.id));
+        } else if swiped.get_untracked() == Some(info.id) {
+            swiped.set(None);
+        }
+    };
+    let on_click = move |_| {
+        if swallow_click.get_value() {
+            swallow_click.set_value(false);
+            return;
+        }
+        if is_open() {
+            if let Some(swiped) = swiped {
+                swiped.set(None);
+            }
+            return;
+        }
+        selected.set(true);
+    };
 
     view! {
-        <div _ref=_ref class="grid grid-cols-2 grid-rows-1 w-full items-center py-4">
-            <div class="flex flex-row gap-2">
-                <div class="grid grid-cols-1 place-items-center place-content-center p-2 rounded-full text-primary-600 text-xl lg:text-2xl">
-                    <Icon icon=info.tag.icondata()/>
-                </div>
-                <div class="flex flex-col">
-                    <span class="text-md md:text-lg font-semibold text-white">
-                        {info.tag.to_text()}
-                    </span>
-                    <span class="text-sm md:text-md text-white/50">{info.amount} COYNs</span>
+        <Modal show=selected>
+            <TxnDetails info/>
+        </Modal>
+        <div _ref=_ref class="relative w-full overflow-hidden">
+            {swiped.map(|swiped| view! { <TxnQuickActions info selected swiped/> })}
+            <div
+                class="relative bg-black"
+                class=("transition-transform", move || drag_offset.with(|d| d.is_none()))
+                class=("duration-300", move || drag_offset.with(|d| d.is_none()))
+                class=("ease-[cubic-bezier(0.34,1.56,0.64,1)]", move || drag_offset.with(|d| d.is_none()))
+                style="touch-action: pan-y"
+                style:transform=move || format!("translateX({}px)", offset())
+                on:touchstart=on_touchstart
+                on:touchmove=on_touchmove
+                on:touchend=on_touchend
+                on:touchcancel=on_touchend
+            >
+                <div
+                    class="grid grid-cols-2 grid-rows-1 w-full items-center py-4 cursor-pointer"
+                    class=("animate-reward-highlight", highlight)
+                    on:click=on_click
+                >
+                    <div class="flex flex-row gap-2">
+                        <div class="grid grid-cols-1 place-items-center place-content-center p-2 rounded-full text-primary-600 text-xl lg:text-2xl">
+                            <Icon icon=info.tag.icondata()/>
+                        </div>
+                        <div class="flex flex-col">
+                            <span class="text-md md:text-lg font-semibold text-white">
+                                {info.tag.to_text()}
+                            </span>
+                            <span class="text-sm md:text-md text-white/50">{info.amount} COYNs</span>
+                        </div>
+                    </div>
+                    <span class=move || {
+                        if direction.positive() {
+                            "text-green-600 justify-self-end"
+                        } else {
+                            "text-red-600 justify-self-end"
+                        }
+                    }>{bal_res} COYNs</span>
                 </div>
             </div>
-            <span class=move || {
-                if direction.positive() {
-                    "text-green-600 justify-self-end"
-                } else {
-                    "text-red-600 justify-self-end"
-                }
-            }>{bal_res} COYNs</span>
         </div>
     }
 }
 
+const SECS_IN_DAY: u64 = 24 * 60 * 60;
+
+/// Dates older than this (in days) are labelled with their year
+const DAYS_IN_YEAR: u64 = 365;
+
+/// Day of a transaction, relative to today in the user's timezone
+#[derive(Clone, PartialEq, Eq)]
+pub enum DateLabel {
+    Today,
+    Yesterday,
+    /// DD MMM, DD MMM YYYY for dates older than a year
+    Date(String),
+}
+
+impl DateLabel {
+    fn new(timestamp: Duration, now: Duration) -> Self {
+        let local_secs = to_local_epoch_secs(timestamp.as_secs());
+        let day = local_secs / SECS_IN_DAY;
+        let today = to_local_epoch_secs(now.as_secs()) / SECS_IN_DAY;
+        match today.checked_sub(day) {
+            Some(0) => Self::Today,
+            Some(1) => Self::Yesterday,
+            Some(days) if days < DAYS_IN_YEAR => Self::Date(get_day_month(local_secs)),
+            _ => Self::Date(get_day_month_year(local_secs)),
+        }
+    }
+}
+
+impl Display for DateLabel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Today => f.write_str(t("txn.today")),
+            Self::Yesterday => f.write_str(t("txn.yesterday")),
+            Self::Date(date) => f.write_str(date),
+        }
+    }
+}
+
+/// Group transactions (newest first) by the day they were made on
+pub fn group_by_date(txns: Vec<TxnInfo>) -> Vec<(DateLabel, Vec<TxnInfo>)> {
+    group_by_date_at(txns, current_epoch())
+}
+
+fn group_by_date_at(txns: Vec<TxnInfo>, now: Duration) -> Vec<(DateLabel, Vec<TxnInfo>)> {
+    let mut groups = Vec::<(DateLabel, Vec<TxnInfo>)>::new();
+    for txn in txns {
+        let label = DateLabel::new(txn.timestamp, now);
+        match groups.last_mut() {
+            Some((last, group)) if *last == label => group.push(txn),
+            _ => groups.push((label, vec![txn])),
+        }
+    }
+    groups
+}
+
+/// Period covered by a [TxnSummary]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum SummaryPeriod {
+    Week,
+    #[default]
+    Month,
+    All,
+}
+
+impl SummaryPeriod {
+    pub const ALL: [SummaryPeriod; 3] = [
+        SummaryPeriod::Week,
+        SummaryPeriod::Month,
+        SummaryPeriod::All,
+    ];
+
+    pub fn duration(self) -> Option<Duration> {
+        match self {
+            SummaryPeriod::Week => Some(Duration::from_secs(7 * SECS_IN_DAY)),
+            SummaryPeriod::Month => Some(Duration::from_secs(30 * SECS_IN_DAY)),
+            SummaryPeriod::All => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SummaryPeriod::Week => t("txn.period.week"),
+            SummaryPeriod::Month => t("txn.period.month"),
+            SummaryPeriod::All => t("txn.period.all"),
+        }
+    }
+}
+
+/// Aggregates over the transactions of a [SummaryPeriod]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub struct TxnSummary {
+    /// Incoming tokens (rewards, mints and received transfers)
+    pub earned: u64,
+    /// Outgoing tokens (votes and sent transfers)
+    pub spent: u64,
+    /// Only part of the period was scanned
+    pub truncated: bool,
+}
+
+impl TxnSummary {
+    fn add(&mut self, txn: &TxnInfo) {
+        let total = if TxnDirection::from(txn.tag).positive() {
+            &mut self.earned
+        } else {
+            &mut self.spent
+        };
+        *total = total.saturating_add(txn.amount);
+    }
+
+    pub fn net(&self) -> i128 {
+        self.earned as i128 - self.spent as i128
+    }
+}
+
+/// Latest transactions of a history, see [provider::HistoryProvider::scan_history]
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct HistoryScan {
+    /// Newest first
+    pub txns: Vec<TxnInfo>,
+    /// Older transactions weren't scanned
+    pub truncated: bool,
+}
+
+impl HistoryScan {
+    /// Totals of the transactions made within `period`, as of `now`
+    pub fn summary(&self, period: SummaryPeriod, now: Duration) -> TxnSummary {
+        let cutoff = period.duration().map(|d| now.saturating_sub(d));
+        let mut summary = TxnSummary::default();
+        for txn in &self.txns {
+            // Newest first, everything after this is out of the period
+            if cutoff.is_some_and(|c| txn.timestamp < c) {
+                return summary;
+            }
+            summary.add(txn);
+        }
+        summary.truncated = self.truncated;
+        summary
+    }
+}
+
+/// Transactions under sticky date headers
+#[component]
+pub fn TxnGroups(
+    txns: Vec<TxnInfo>,
+    /// Ids of transactions to highlight
+    #[prop(optional)]
+    highlighted: HashSet<u64>,
+) -> impl IntoView {
+    SwipedTxn::provide();
+    let highlighted = store_value(highlighted);
+    let rows = move |txns: Vec<TxnInfo>| {
+        view! {
+            <div class="flex flex-col divide-y divide-white/10">
+                {txns
+                    .into_iter()
+                    .map(|info| {
+                        let highlight = highlighted.with_value(|h| h.contains(&info.id));
+                        view! { <TxnView info highlight/> }
+                    })
+                    .collect_view()}
+            </div>
+        }
+    };
+    // Labels depend on the user's timezone and clock, so transactions are only
+    // grouped on the client, the server renders them under a blank header
+    let hydrated = create_rw_signal(false);
+    create_effect(move |_| hydrated.set(true));
+
+    move || {
+        if !hydrated() {
+            return view! {
+                <div class="flex flex-col w-full">
+                    <span class="sticky top-0 z-[1] py-2 bg-black text-xs md:text-sm">"\u{a0}"</span>
+                    {rows(txns.clone())}
+                </div>
+            }
+            .into_view();
+        }
+        group_by_date(txns.clone())
+            .into_iter()
+            .map(|(label, txns)| {
+                view! {
+                    <div class="flex flex-col w-full">
+                        <DateHeader label/>
+                        {rows(txns)}
+                    </div>
+                }
+            })
+            .collect_view()
+    }
+}
+
+#[component]
+fn DateHeader(label: DateLabel) -> impl IntoView {
+    view! {
+        <span class="sticky top-0 z-[1] py-2 bg-black text-white/50 text-xs md:text-sm">
+            {label.to_string()}
+        </span>
+    }
+}
+
+/// Transaction of a paginated list, with the timestamp of the one listed before it
+#[derive(Clone, Copy)]
+pub struct ListedTxn {
+    pub info: TxnInfo,
+    pub prev_timestamp: Option<Duration>,
+}
+
+impl KeyedData for ListedTxn {
+    type Key = u64;
+
+    fn key(&self) -> Self::Key {
+        self.info.id
+    }
+}
+
+/// Row of a paginated list, under a sticky date header if it's the first of its day
+/// like [TxnGroups], headers are only rendered on the client
+#[component]
+pub fn ListedTxnView(txn: ListedTxn, #[prop(optional)] _ref: NodeRef<html::Div>) -> impl IntoView {
+    let hydrated = create_rw_signal(false);
+    create_effect(move |_| hydrated.set(true));
+    let header = move || {
+        if !hydrated() {
+            return None;
+        }
+        let now = current_epoch();
+        let label = DateLabel::new(txn.info.timestamp, now);
+        let same_day = txn
+            .prev_timestamp
+            .is_some_and(|prev| DateLabel::new(prev, now) == label);
+        (!same_day).then(|| view! { <DateHeader label/> })
+    };
+
+    view! {
+        {header}
+        <TxnView info=txn.info _ref/>
+    }
+}
+
 pub mod provider {
     use crate::{component::infinite_scroller::CursoredDataProvider, state::canisters::Canisters};
 
@@ -149,47 +792,419 @@ pub mod provider {
         }
     }
 
+    /// History provider only returning transactions matching `filter`
+    /// meant for infinite scrolling, pages are paginated by [TxnCursor]
+    /// and must be requested in order
+    ///
+    /// The canister can't filter its history, so pages are fetched and filtered
+    /// client side until enough matching transactions are found
+    /// Sparse categories are expensive, each call scans at most
+    /// [filtered::MAX_SCANNED_PAGES] pages of the full history and may return
+    /// an empty page before the end, which the scroller keeps loading past
+    pub fn get_filtered_history_provider(
+        canisters: Canisters<true>,
+        filter: TxnFilter,
+    ) -> impl CursoredDataProvider<Data = TxnInfo> + Clone {
+        let history = cursored::CursorHistory::new(get_history_provider(canisters));
+        filtered::FilteredHistory::new(history, filter)
+    }
+
+    /// Pairs every transaction with the one listed before it, see [ListedTxn]
+    /// pages must be requested in order
+    pub fn get_listed_history_provider(
+        provider: impl CursoredDataProvider<Data = TxnInfo> + Clone,
+    ) -> impl CursoredDataProvider<Data = ListedTxn> + Clone {
+        listed::ListedHistory::new(provider)
+    }
+
+    /// Position in the history, returned by [HistoryProvider::get_history_cursor]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct TxnCursor {
+        /// Id of the oldest transaction returned so far
+        last_id: u64,
+        /// Offset into the history right after `last_id`, when it was fetched
+        /// newer transactions shift the history, so `last_id` may have moved further
+        offset: usize,
+    }
+
+    pub struct TxnCursorPage {
+        pub data: Vec<TxnInfo>,
+        /// Cursor of the next batch, `None` once the history is exhausted
+        pub next: Option<TxnCursor>,
+    }
+
+    /// Page size used while scanning the history
+    const SCAN_PAGE_SIZE: usize = 50;
+    /// Histories are scanned for at most this many pages
+    /// i.e lookups and summaries only cover the latest 1000 transactions
+    const MAX_SCANNED_PAGES: usize = 20;
+
+    /// Queries over the history, on top of pagination
+    pub(crate) trait HistoryProvider: CursoredDataProvider<Data = TxnInfo> {
+        /// Next batch of (at least `count`, unless exhausted) transactions after `cursor`
+        /// starting from the newest transaction if `cursor` is `None`
+        ///
+        /// Offset based pages ([CursoredDataProvider::get_by_cursor]) repeat transactions
+        /// if new ones arrive between two fetches. The canister only paginates by offset,
+        /// so the last seen id is tracked instead and transactions that aren't older
+        /// are skipped, transaction ids only ever increase
+        async fn get_history_cursor(
+            &self,
+            cursor: Option<TxnCursor>,
+            count: usize,
+        ) -> Result<TxnCursorPage, Self::Error> {
+            let last_id = cursor.map_or(u64::MAX, |c| c.last_id);
+            let mut offset = cursor.map_or(0, |c| c.offset);
+            let mut data = Vec::with_capacity(count);
+            for _ in 0..MAX_SCANNED_PAGES {
+                let page = self.get_by_cursor(offset, offset + count).await?;
+                offset += count;
+                data.extend(page.data.into_iter().filter(|txn| txn.id < last_id));
+                if page.end {
+                    return Ok(TxnCursorPage { data, next: None });
+                }
+                if data.len() >= count {
+                    break;
+                }
+            }
+
+            let next = TxnCursor {
+                last_id: data.last().map_or(last_id, |txn| txn.id),
+                offset,
+            };
+            Ok(TxnCursorPage {
+                data,
+                next: Some(next),
+            })
+        }
+
+        /// Transaction with the given `id`, `None` if the history doesn't contain it
+        ///
+        /// The canister can't fetch transactions by id, so the history is scanned
+        /// from the newest transaction, up to [MAX_SCANNED_PAGES] pages
+        async fn get_transaction(&self, id: u64) -> Result<Option<TxnInfo>, Self::Error> {
+            for page_idx in 0..MAX_SCANNED_PAGES {
+                let start = page_idx * SCAN_PAGE_SIZE;
+                let page = self.get_by_cursor(start, start + SCAN_PAGE_SIZE).await?;
+                if let Some(txn) = page.data.iter().find(|txn| txn.id == id) {
+                    return Ok(Some(*txn));
+                }
+                if page.end || page.data.is_empty() {
+                    break;
+                }
+            }
+            Ok(None)
+        }
+
+        /// Latest transactions, from the newest up to [MAX_SCANNED_PAGES] pages
+        ///
+        /// The canister doesn't aggregate its history, so summaries and breakdowns
+        /// are computed from this, one scan serves every period, see [HistoryScan::summary]
+        async fn scan_history(&self) -> Result<HistoryScan, Self::Error> {
+            let mut scan = HistoryScan::default();
+            for page_idx in 0..MAX_SCANNED_PAGES {
+                let start = page_idx * SCAN_PAGE_SIZE;
+                let page = self.get_by_cursor(start, start + SCAN_PAGE_SIZE).await?;
+                let exhausted = page.end || page.data.is_empty();
+                scan.txns.extend(page.data);
+                if exhausted {
+                    return Ok(scan);
+                }
+            }
+            scan.truncated = true;
+            Ok(scan)
+        }
+    }
+
+    impl<P: CursoredDataProvider<Data = TxnInfo>> HistoryProvider for P {}
+
+    mod cursored {
+        use std::{cell::Cell, rc::Rc};
+
+        use crate::component::infinite_scroller::PageEntry;
+
+        use super::{CursoredDataProvider, HistoryProvider, TxnCursor, TxnInfo};
+
+        /// Adapts [HistoryProvider::get_history_cursor] to offset based consumers
+        /// (e.g the infinite scroller), pages must be requested in order
+        /// requesting the first page restarts from the newest transaction
+        #[derive(Clone)]
+        pub struct CursorHistory<P> {
+            inner: P,
+            cursor: Rc<Cell<Option<TxnCursor>>>,
+        }
+
+        impl<P> CursorHistory<P> {
+            pub fn new(inner: P) -> Self {
+                Self {
+                    inner,
+                    cursor: Rc::new(Cell::new(None)),
+                }
+            }
+        }
+
+        impl<P: CursoredDataProvider<Data = TxnInfo>> CursoredDataProvider for CursorHistory<P> {
+            type Data = TxnInfo;
+            type Error = P::Error;
+
+            async fn get_by_cursor(
+                &self,
+                start: usize,
+                end: usize,
+            ) -> Result<PageEntry<TxnInfo>, P::Error> {
+                let cursor = if start == 0 { None } else { self.cursor.get() };
+                let page = self.inner.get_history_cursor(cursor, end - start).await?;
+                self.cursor.set(page.next);
+                Ok(PageEntry {
+                    data: page.data,
+                    end: page.next.is_none(),
+                })
+            }
+        }
+    }
+
+    pub(super) mod filtered {
+        use std::{cell::RefCell, rc::Rc};
+
+        use crate::component::infinite_scroller::PageEntry;
+
+        use super::{CursoredDataProvider, TxnFilter, TxnInfo};
+
+        pub const MAX_SCANNED_PAGES: usize = 10;
+
+        #[derive(Default)]
+        struct ScanState {
+            /// Offset into the unfiltered history
+            raw_cursor: usize,
+            /// Matches scanned past the end of the last page
+            pending: Vec<TxnInfo>,
+            /// The unfiltered history is exhausted
+            raw_end: bool,
+        }
+
+        /// Pages must be requested in order
+        /// requesting the first page restarts from the newest transaction
+        #[derive(Clone)]
+        pub struct FilteredHistory<P> {
+            inner: P,
+            filter: TxnFilter,
+            state: Rc<RefCell<ScanState>>,
+        }
+
+        impl<P> FilteredHistory<P> {
+            pub fn new(inner: P, filter: TxnFilter) -> Self {
+                Self {
+                    inner,
+                    filter,
+                    state: Rc::default(),
+                }
+            }
+        }
+
+        impl<P: CursoredDataProvider<Data = TxnInfo>> CursoredDataProvider for FilteredHistory<P> {
+            type Data = TxnInfo;
+            type Error = P::Error;
+
+            async fn get_by_cursor(
+                &self,
+                start: usize,
+                end: usize,
+            ) -> Result<PageEntry<TxnInfo>, P::Error> {
+                if self.filter == TxnFilter::All {
+                    return self.inner.get_by_cursor(start, end).await;
+                }
+
+                let count = end - start;
+                if start == 0 {
+                    self.state.replace(ScanState::default());
+                }
+                let (mut raw, mut raw_end, mut data) = {
+                    let mut state = self.state.borrow_mut();
+                    (
+                        state.raw_cursor,
+                        state.raw_end,
+                        std::mem::take(&mut state.pending),
+                    )
+                };
+                for _ in 0..MAX_SCANNED_PAGES {
+                    if raw_end || data.len() >= count {
+                        break;
+                    }
+                    let page = self.inner.get_by_cursor(raw, raw + count).await?;
+                    raw += count;
+                    raw_end = page.end;
+                    data.extend(
+                        page.data
+                            .into_iter()
+                            .filter(|txn| self.filter.matches(txn.tag)),
+                    );
+                }
+
+                let pending = data.split_off(count.min(data.len()));
+                let end = raw_end && pending.is_empty();
+                self.state.replace(ScanState {
+                    raw_cursor: raw,
+                    pending,
+                    raw_end,
+                });
+                Ok(PageEntry { data, end })
+            }
+        }
+    }
+
+    mod listed {
+        use std::{cell::Cell, rc::Rc};
+
+        use crate::component::infinite_scroller::PageEntry;
+
+        use super::{CursoredDataProvider, Duration, ListedTxn, TxnInfo};
+
+        #[derive(Clone)]
+        pub struct ListedHistory<P> {
+            inner: P,
+            /// Timestamp of the last transaction returned so far
+            last_timestamp: Rc<Cell<Option<Duration>>>,
+        }
+
+        impl<P> ListedHistory<P> {
+            pub fn new(inner: P) -> Self {
+                Self {
+                    inner,
+                    last_timestamp: Rc::new(Cell::new(None)),
+                }
+            }
+        }
+
+        impl<P: CursoredDataProvider<Data = TxnInfo>> CursoredDataProvider for ListedHistory<P> {
+            type Data = ListedTxn;
+            type Error = P::Error;
+
+            async fn get_by_cursor(
+                &self,
+                start: usize,
+                end: usize,
+            ) -> Result<PageEntry<ListedTxn>, P::Error> {
+                let page = self.inner.get_by_cursor(start, end).await?;
+                let mut prev_timestamp = if start == 0 {
+                    None
+                } else {
+                    self.last_timestamp.get()
+                };
+                let data = page
+                    .data
+                    .into_iter()
+                    .map(|info| ListedTxn {
+                        info,
+                        prev_timestamp: prev_timestamp.replace(info.timestamp),
+                    })
+                    .collect();
+                self.last_timestamp.set(prev_timestamp);
+                Ok(PageEntry {
+                    data,
+                    end: page.end,
+                })
+            }
+        }
+    }
+
     #[cfg(not(feature = "mock-wallet-history"))]
     mod canister {
-        use super::{Canisters, CursoredDataProvider, TxnInfo, TxnTag};
+        use super::{Canisters, CursoredDataProvider, Duration, TxnInfo, TxnTag};
         use crate::canister::individual_user_template::{
-            HotOrNotOutcomePayoutEvent, MintEvent, Result7, TokenEvent,
+            HotOrNotOutcomePayoutEvent, MintEvent, PlaceBetArg, Result7, StakeEvent, SystemTime,
+            TokenEvent,
+        };
+        use crate::{
+            component::infinite_scroller::PageEntry,
+            utils::{
+                call_limit::CallPriority,
+                ic::{canister_call, CallError},
+            },
         };
-        use crate::component::infinite_scroller::PageEntry;
-        use ic_agent::AgentError;
 
         fn event_to_txn(event: (u64, TokenEvent)) -> Option<TxnInfo> {
-            let (amount, tag) = match event.1 {
-                TokenEvent::Stake { amount, .. } => (amount, TxnTag::BetPlaced),
+            let mut counterparty = None;
+            let mut post = None;
+            let (amount, tag, timestamp) = match event.1 {
+                TokenEvent::Stake {
+                    amount,
+                    details:
+                        StakeEvent::BetOnHotOrNotPost(PlaceBetArg {
+                            post_canister_id,
+                            post_id,
+                            ..
+                        }),
+                    timestamp,
+                } => {
+                    post = Some((post_canister_id, post_id));
+                    (amount, TxnTag::BetPlaced, timestamp)
+                }
                 TokenEvent::Burn => return None,
                 TokenEvent::Mint {
                     amount,
                     details: MintEvent::NewUserSignup { .. },
-                    ..
-                } => (amount, TxnTag::SignupBonus),
+                    timestamp,
+                } => (amount, TxnTag::SignupBonus, timestamp),
                 TokenEvent::Mint {
                     amount,
                     details: MintEvent::Referral { .. },
-                    ..
-                } => (amount, TxnTag::Referral),
-                TokenEvent::Transfer { amount, .. } => (amount, TxnTag::Transfer),
-                TokenEvent::Receive { amount, .. } => (amount, TxnTag::HotorNotAccountTransfer),
+                    timestamp,
+                } => (amount, TxnTag::Referral, timestamp),
+                TokenEvent::Transfer {
+                    amount,
+                    timestamp,
+                    to_account,
+                } => {
+                    counterparty = Some(to_account);
+                    (amount, TxnTag::Transfer, timestamp)
+                }
+                TokenEvent::Receive {
+                    amount,
+                    timestamp,
+                    from_account,
+                } => {
+                    counterparty = Some(from_account);
+                    (amount, TxnTag::HotorNotAccountTransfer, timestamp)
+                }
                 TokenEvent::HotOrNotOutcomePayout {
                     amount,
-                    details: HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet { .. },
-                    ..
-                } => (amount, TxnTag::Commission),
+                    details:
+                        HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet {
+                            post_canister_id,
+                            post_id,
+                            ..
+                        },
+                    timestamp,
+                } => {
+                    post = Some((post_canister_id, post_id));
+                    (amount, TxnTag::Commission, timestamp)
+                }
                 TokenEvent::HotOrNotOutcomePayout {
                     amount,
-                    details: HotOrNotOutcomePayoutEvent::WinningsEarnedFromBet { .. },
-                    ..
-                } => (amount, TxnTag::Winnings),
+                    details:
+                        HotOrNotOutcomePayoutEvent::WinningsEarnedFromBet {
+                            post_canister_id,
+                            post_id,
+                            ..
+                        },
+                    timestamp,
+                } => {
+                    post = Some((post_canister_id, post_id));
+                    (amount, TxnTag::Winnings, timestamp)
+                }
             };
+            let SystemTime {
+                secs_since_epoch,
+                nanos_since_epoch,
+            } = timestamp;
 
             Some(TxnInfo {
                 tag,
                 amount,
                 id: event.0,
+                timestamp: Duration::new(secs_since_epoch, nanos_since_epoch),
+                counterparty,
+                post,
             })
         }
 
@@ -198,20 +1213,22 @@ pub mod provider {
 
         impl CursoredDataProvider for TxnHistory {
             type Data = TxnInfo;
-            type Error = AgentError;
+            type Error = CallError;
 
             async fn get_by_cursor(
                 &self,
                 start: usize,
                 end: usize,
-            ) -> Result<PageEntry<TxnInfo>, AgentError> {
+            ) -> Result<PageEntry<TxnInfo>, CallError> {
                 let user = self.0.authenticated_user().await?;
-                let history = user
-                    .get_user_utility_token_transaction_history_with_pagination(
+                let history = canister_call(
+                    CallPriority::User,
+                    user.get_user_utility_token_transaction_history_with_pagination(
                         start as u64,
                         end as u64,
-                    )
-                    .await?;
+                    ),
+                )
+                .await?;
                 let history = match history {
                     Result7::Ok(v) => v,
                     Result7::Err(_) => vec![],
@@ -261,12 +1278,18 @@ pub mod provider {
                 from: usize,
                 end: usize,
             ) -> Result<PageEntry<TxnInfo>, Infallible> {
-                let mut rand_gen = ChaCha8Rng::seed_from_u64(current_epoch().as_nanos() as u64);
+                let now = current_epoch();
+                let mut rand_gen = ChaCha8Rng::seed_from_u64(now.as_nanos() as u64);
                 let data = (from..end)
-                    .map(|_| TxnInfo {
+                    .map(|idx| TxnInfo {
                         amount: rand_gen.next_u64() % 3001,
                         tag: tag_from_u32(rand_gen.next_u32()),
-                        id: rand_gen.next_u64(),
+                        // Newer transactions have larger ids, like the canister
+                        id: u64::MAX - idx as u64,
+                        // Roughly 6 hours apart, newest first
+                        timestamp: now.saturating_sub(Duration::from_secs(idx as u64 * 6 * 3600)),
+                        counterparty: None,
+                        post: None,
                     })
                     .collect();
                 Ok(PageEntry { data, end: false })
@@ -274,3 +1297,190 @@ pub mod provider {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use web_time::Duration;
+
+    use super::{
+        group_by_date_at,
+        provider::{filtered::FilteredHistory, get_listed_history_provider, HistoryProvider},
+        DateLabel, HistoryScan, ListedTxn, SummaryPeriod, TxnFilter, TxnInfo, TxnTag, SECS_IN_DAY,
+    };
+    use crate::component::infinite_scroller::{CursoredDataProvider, PageEntry};
+
+    /// 15 OCT 2024, 12:00:00 UTC
+    const NOW: u64 = 1_728_993_600;
+
+    fn label(days_ago: u64) -> DateLabel {
+        DateLabel::new(
+            Duration::from_secs(NOW - days_ago * SECS_IN_DAY),
+            Duration::from_secs(NOW),
+        )
+    }
+
+    fn txn(id: u64, days_ago: u64) -> TxnInfo {
+        TxnInfo {
+            id,
+            timestamp: Duration::from_secs(NOW - days_ago * SECS_IN_DAY),
+            tag: TxnTag::BetPlaced,
+            amount: 10,
+            counterparty: None,
+            post: None,
+        }
+    }
+
+    /// History served from memory, newest first
+    #[derive(Clone)]
+    struct MemHistory(Vec<TxnInfo>);
+
+    impl CursoredDataProvider for MemHistory {
+        type Data = TxnInfo;
+        type Error = Infallible;
+
+        async fn get_by_cursor(
+            &self,
+            start: usize,
+            end: usize,
+        ) -> Result<PageEntry<TxnInfo>, Infallible> {
+            let end_idx = end.min(self.0.len());
+            Ok(PageEntry {
+                data: self.0.get(start..end_idx).unwrap_or_default().to_vec(),
+                end: end >= self.0.len(),
+            })
+        }
+    }
+
+    #[test]
+    fn relative_labels() {
+        assert!(label(0) == DateLabel::Today);
+        assert!(label(1) == DateLabel::Yesterday);
+        assert_eq!(label(2).to_string(), "13 OCT");
+    }
+
+    #[test]
+    fn old_dates_include_the_year() {
+        assert_eq!(label(364).to_string(), "17 OCT");
+        assert_eq!(label(365).to_string(), "16 OCT 2023");
+        assert_eq!(label(800).to_string(), "07 AUG 2022");
+    }
+
+    #[test]
+    fn consecutive_days_are_grouped() {
+        let groups = group_by_date_at(
+            vec![txn(1, 0), txn(2, 0), txn(3, 1), txn(4, 3)],
+            Duration::from_secs(NOW),
+        );
+        let ids: Vec<Vec<u64>> = groups
+            .iter()
+            .map(|(_, txns)| txns.iter().map(|t| t.id).collect())
+            .collect();
+        assert_eq!(ids, [vec![1, 2], vec![3], vec![4]]);
+    }
+
+    #[tokio::test]
+    async fn listed_txns_carry_the_previous_timestamp_across_pages() {
+        let history = MemHistory(vec![txn(1, 0), txn(2, 1), txn(3, 1), txn(4, 2)]);
+        let listed = get_listed_history_provider(history);
+        let prev = |page: PageEntry<_>| -> Vec<_> {
+            page.data
+                .iter()
+                .map(|t: &ListedTxn| t.prev_timestamp.map(|p| p.as_secs()))
+                .collect()
+        };
+        let day = |days_ago| Some(NOW - days_ago * SECS_IN_DAY);
+
+        let first = listed.get_by_cursor(0, 2).await.unwrap();
+        assert_eq!(prev(first), [None, day(0)]);
+        let second = listed.get_by_cursor(2, 4).await.unwrap();
+        assert_eq!(prev(second), [day(1), day(1)]);
+
+        // Restarting from the top forgets the previous pages
+        let restarted = listed.get_by_cursor(0, 2).await.unwrap();
+        assert_eq!(prev(restarted), [None, day(0)]);
+    }
+
+    /// Two out of every three transactions are votes, i.e [TxnFilter::Sent]
+    fn mixed_history(len: u64) -> MemHistory {
+        MemHistory(
+            (0..len)
+                .map(|id| TxnInfo {
+                    tag: if id % 3 == 2 {
+                        TxnTag::Winnings
+                    } else {
+                        TxnTag::BetPlaced
+                    },
+                    ..txn(id, 0)
+                })
+                .collect(),
+        )
+    }
+
+    fn ids(page: &PageEntry<TxnInfo>) -> Vec<u64> {
+        page.data.iter().map(|t| t.id).collect()
+    }
+
+    #[tokio::test]
+    async fn filtered_pages_keep_the_unconsumed_matches() {
+        let filtered = FilteredHistory::new(mixed_history(9), TxnFilter::Sent);
+
+        // The second raw page overshoots the count, its last match is kept for later
+        let first = filtered.get_by_cursor(0, 3).await.unwrap();
+        assert_eq!(ids(&first), [0, 1, 3]);
+        assert!(!first.end);
+        let second = filtered.get_by_cursor(3, 6).await.unwrap();
+        assert_eq!(ids(&second), [4, 6, 7]);
+        assert!(second.end);
+    }
+
+    #[tokio::test]
+    async fn filtered_history_restarts_from_the_top() {
+        let filtered = FilteredHistory::new(mixed_history(9), TxnFilter::Sent);
+
+        filtered.get_by_cursor(0, 3).await.unwrap();
+        filtered.get_by_cursor(3, 6).await.unwrap();
+        let restarted = filtered.get_by_cursor(0, 3).await.unwrap();
+        assert_eq!(ids(&restarted), [0, 1, 3]);
+        assert!(!restarted.end);
+    }
+
+    #[tokio::test]
+    async fn history_scans_are_capped() {
+        let scan = mixed_history(60).scan_history().await.unwrap();
+        assert_eq!(scan.txns.len(), 60);
+        assert!(!scan.truncated);
+
+        let scan = mixed_history(1500).scan_history().await.unwrap();
+        assert_eq!(scan.txns.len(), 1000);
+        assert!(scan.truncated);
+    }
+
+    #[test]
+    fn summaries_only_cover_their_period() {
+        let scan = HistoryScan {
+            txns: vec![
+                TxnInfo {
+                    tag: TxnTag::Winnings,
+                    ..txn(1, 0)
+                },
+                txn(2, 3),
+                txn(3, 20),
+                txn(4, 40),
+            ],
+            truncated: true,
+        };
+        let now = Duration::from_secs(NOW);
+
+        let week = scan.summary(SummaryPeriod::Week, now);
+        assert_eq!((week.earned, week.spent), (10, 10));
+        // Older transactions are out of the period anyway
+        assert!(!week.truncated);
+        let month = scan.summary(SummaryPeriod::Month, now);
+        assert_eq!((month.earned, month.spent), (10, 20));
+        let all = scan.summary(SummaryPeriod::All, now);
+        assert_eq!((all.earned, all.spent), (10, 30));
+        assert!(all.truncated);
+    }
+}
@@ -0,0 +1,57 @@
+use candid::Principal;
+use leptos::*;
+
+/// Events published by one part of the app that others may react to
+#[derive(Clone, Debug, PartialEq)]
+pub enum AppEvent {
+    /// Like status of a post was confirmed by the canister
+    Liked {
+        canister_id: Principal,
+        post_id: u64,
+        liked: bool,
+        likes: u64,
+    },
+    /// The authenticated user's balance changed (bets, rewards, etc)
+    BalanceChanged,
+    /// New reward transactions were found, the balance changed as well
+    RewardReceived { amount: u64 },
+}
+
+/// Typed event bus, provided in context
+#[derive(Clone, Copy)]
+pub struct AppEvents(RwSignal<Option<AppEvent>>);
+
+impl Default for AppEvents {
+    fn default() -> Self {
+        Self(create_rw_signal(None))
+    }
+}
+
+impl AppEvents {
+    pub fn get() -> Self {
+        expect_context()
+    }
+
+    pub fn publish(&self, event: AppEvent) {
+        // Subscribers are notified even if the same event is published twice
+        self.0.set(Some(event));
+    }
+
+    /// Run `handler` for every event published after this call
+    /// the subscription is dropped along with the calling component
+    pub fn subscribe(&self, handler: impl Fn(&AppEvent) + 'static) {
+        let event = self.0;
+        create_effect(move |subscribed: Option<()>| {
+            let ev = event.get();
+            // Don't replay the last event to new subscribers
+            if subscribed.is_none() {
+                return;
+            }
+            // Only the bus is tracked, signals read by the handler must not re-run it
+            // the event is cloned out so the handler can publish events itself
+            if let Some(ev) = ev {
+                untrack(|| handler(&ev));
+            }
+        });
+    }
+}
@@ -1,6 +1,8 @@
 use leptos::*;
 use leptos_use::{use_timeout_fn, UseTimeoutFnReturn};
 
+use super::local_storage::use_audio_unmuted_store;
+
 struct DisplayMutedIconTimeout {
     // TODO: use TAIT once stable
     // instead of Dyn dispatch
@@ -47,6 +49,7 @@ impl DisplayMutedIconTimeout {
 pub struct AudioState {
     pub show_mute_icon: RwSignal<bool>,
     pub muted: RwSignal<bool>,
+    set_unmuted: WriteSignal<bool>,
     display_flash: StoredValue<DisplayMutedIconTimeout>,
 }
 
@@ -60,9 +63,14 @@ impl AudioState {
     pub fn new() -> Self {
         let show_mute_icon = create_rw_signal(true);
         let display_flash = StoredValue::new(DisplayMutedIconTimeout::new(show_mute_icon));
+        let muted = create_rw_signal(true);
+        // The preference carries across posts and visits
+        let (unmuted, set_unmuted, _) = use_audio_unmuted_store();
+        create_effect(move |_| muted.set(!unmuted()));
         Self {
             show_mute_icon,
-            muted: create_rw_signal(true),
+            muted,
+            set_unmuted,
             display_flash,
         }
     }
@@ -84,6 +92,8 @@ impl AudioState {
         } else {
             this.display_flash.with_value(|d| d.stop());
         }
-        this.muted.update(|m| *m = !*m);
+        let muted = !this.muted.get_untracked();
+        this.muted.set(muted);
+        this.set_unmuted.set(!muted);
     }
 }
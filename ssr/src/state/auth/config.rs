@@ -0,0 +1,57 @@
+use leptos::*;
+use web_time::Duration;
+
+use crate::consts::auth::{DELEGATION_MAX_AGE, REFRESH_MAX_AGE};
+
+/// Session lifetimes
+///
+/// Read from the environment on the server (`DELEGATION_EXPIRY` and `REFRESH_EXPIRY`, in seconds),
+/// falling back to [DELEGATION_MAX_AGE] and [REFRESH_MAX_AGE] for unset variables
+/// The client build always uses the defaults
+#[derive(Clone, Copy, Debug)]
+pub struct AuthConfig {
+    pub delegation_max_age: Duration,
+    pub refresh_max_age: Duration,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            delegation_max_age: DELEGATION_MAX_AGE,
+            refresh_max_age: REFRESH_MAX_AGE,
+        }
+    }
+}
+
+impl AuthConfig {
+    #[cfg(feature = "ssr")]
+    pub fn from_env() -> Self {
+        fn secs_from_env(var: &str) -> Option<Duration> {
+            let secs = std::env::var(var).ok()?;
+            let secs: u64 = secs
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid `{var}`, expected seconds"));
+            Some(Duration::from_secs(secs))
+        }
+
+        let default = Self::default();
+        let config = Self {
+            delegation_max_age: secs_from_env("DELEGATION_EXPIRY")
+                .unwrap_or(default.delegation_max_age),
+            refresh_max_age: secs_from_env("REFRESH_EXPIRY").unwrap_or(default.refresh_max_age),
+        };
+        // Delegations are minted from the refresh token,
+        // outliving it would keep sessions alive past their expiry
+        assert!(
+            config.delegation_max_age < config.refresh_max_age,
+            "`DELEGATION_EXPIRY` must be shorter than `REFRESH_EXPIRY`"
+        );
+        config
+    }
+
+    /// Configuration provided in context
+    /// defaults outside a reactive owner
+    pub fn get() -> Self {
+        use_context().unwrap_or_default()
+    }
+}
@@ -1,8 +1,16 @@
+pub mod config;
+
 use codee::string::FromToStringCodec;
+use k256::elliptic_curve::JwkEcKey;
 use leptos::*;
 use leptos_use::storage::use_local_storage;
 
-use crate::{auth::DelegatedIdentityWire, consts::ACCOUNT_CONNECTED_STORE};
+use crate::{
+    auth::{refresh_identity, DelegatedIdentityWire},
+    consts::ACCOUNT_CONNECTED_STORE,
+    state::canisters::Canisters,
+    utils::current_epoch,
+};
 
 pub type AuthState = RwSignal<Option<DelegatedIdentityWire>>;
 
@@ -10,6 +18,115 @@ pub fn auth_state() -> AuthState {
     expect_context()
 }
 
+fn delegation_is_valid(auth: AuthState) -> bool {
+    auth.with_untracked(|id| id.as_ref().map_or(true, |id| id.is_valid(current_epoch())))
+}
+
+/// Replace the delegation with a fresh one
+/// returns the new delegation, `None` if there is no session to refresh
+async fn refresh_delegation(auth: AuthState) -> Option<DelegatedIdentityWire> {
+    log::warn!("delegation is no longer valid, re-authenticating");
+    let id = match refresh_identity().await {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            log::warn!("no session to re-authenticate with");
+            return None;
+        }
+        Err(e) => {
+            log::warn!("failed to re-authenticate: {e}");
+            return None;
+        }
+    };
+    _ = auth.try_set(Some(id.clone()));
+    Some(id)
+}
+
+/// Check the delegation before authenticated reads
+/// an expired or broken delegation is silently replaced by a fresh one
+/// returns false while it's being replaced
+pub fn ensure_valid_delegation() -> bool {
+    let auth = auth_state();
+    if delegation_is_valid(auth) {
+        return true;
+    }
+    spawn_local(async move {
+        refresh_delegation(auth).await;
+    });
+    false
+}
+
+/// Run an authenticated action with `canisters`
+/// if the delegation expired or is broken, it's silently replaced by a fresh one
+/// and `action` runs with the refreshed canisters instead
+pub fn with_valid_delegation(
+    canisters: Canisters<true>,
+    action: impl FnOnce(Canisters<true>) + 'static,
+) {
+    let auth = auth_state();
+    if delegation_is_valid(auth) {
+        action(canisters);
+        return;
+    }
+    spawn_local(async move {
+        let Some(id) = refresh_delegation(auth).await else {
+            return;
+        };
+        match canisters.refreshed(id) {
+            Ok(canisters) => action(canisters),
+            Err(e) => log::warn!("refreshed delegation is unusable: {e}"),
+        }
+    });
+}
+
+/// Anonymous browsing state
+/// First time visitors browse with an ephemeral identity which is only
+/// persisted (to KV + refresh cookie) once an authenticated action is performed
+#[derive(Clone, Copy)]
+pub struct AnonymousBrowse {
+    temp_identity: StoredValue<Option<JwkEcKey>>,
+    requested: RwSignal<bool>,
+}
+
+impl AnonymousBrowse {
+    pub fn new(temp_identity: Option<JwkEcKey>) -> Self {
+        Self {
+            temp_identity: store_value(temp_identity),
+            requested: create_rw_signal(false),
+        }
+    }
+
+    pub fn temp_identity(&self) -> Option<JwkEcKey> {
+        self.temp_identity.get_value()
+    }
+
+    /// Whether the identity has been requested by an authenticated action
+    pub fn requested(&self) -> bool {
+        self.requested.get()
+    }
+
+    /// Whether the user is currently browsing without a persisted identity
+    pub fn is_active(&self) -> bool {
+        self.temp_identity.with_value(|id| id.is_some()) && !self.requested.get()
+    }
+
+    /// Same as [Self::is_active] but does not track the signal
+    pub fn is_active_untracked(&self) -> bool {
+        self.temp_identity.with_value(|id| id.is_some()) && !self.requested.get_untracked()
+    }
+
+    /// Persist the ephemeral identity
+    /// must be called before performing any authenticated action
+    pub fn request_identity(&self) {
+        if self.is_active_untracked() {
+            self.requested.set(true);
+        }
+    }
+}
+
+pub fn anonymous_browse() -> AnonymousBrowse {
+    expect_context()
+}
+
 /// Prevents hydration bugs if the value in store is used to conditionally show views
 /// this is because the server will always get a `false` value and do rendering based on that
 pub fn account_connected_reader() -> (ReadSignal<bool>, Effect<()>) {
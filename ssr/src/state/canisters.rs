@@ -8,7 +8,7 @@ use yral_metadata_client::MetadataClient;
 use yral_metadata_types::UserMetadata;
 
 use crate::{
-    auth::DelegatedIdentityWire,
+    auth::{DelegatedIdentityWire, InvalidIdentityWire},
     canister::{
         individual_user_template::{IndividualUserTemplate, Result9, UserCanisterDetails},
         platform_orchestrator::PlatformOrchestrator,
@@ -17,6 +17,7 @@ use crate::{
         PLATFORM_ORCHESTRATOR_ID, POST_CACHE_ID,
     },
     consts::METADATA_API_BASE,
+    state::dev_mode::DevMode,
     utils::{ic::AgentWrapper, profile::ProfileDetails, MockPartialEq},
 };
 
@@ -29,7 +30,7 @@ pub struct CanistersAuthWire {
 }
 
 impl CanistersAuthWire {
-    pub fn canisters(self) -> Result<Canisters<true>, k256::elliptic_curve::Error> {
+    pub fn canisters(self) -> Result<Canisters<true>, InvalidIdentityWire> {
         let unauth = unauth_canisters();
 
         let id: DelegatedIdentity = self.id.try_into()?;
@@ -92,6 +93,17 @@ impl Canisters<true> {
         }
     }
 
+    /// Same canisters, authenticated with a refreshed delegation of the same principal
+    pub fn refreshed(
+        &self,
+        id: DelegatedIdentityWire,
+    ) -> Result<Canisters<true>, InvalidIdentityWire> {
+        let mut canisters = Canisters::authenticated(id.try_into()?);
+        canisters.user_canister = self.user_canister;
+        canisters.profile_details.clone_from(&self.profile_details);
+        Ok(canisters)
+    }
+
     pub fn expiry_ns(&self) -> u64 {
         self.expiry
     }
@@ -150,18 +162,28 @@ impl<const A: bool> Canisters<A> {
         Ok(PlatformOrchestrator(PLATFORM_ORCHESTRATOR_ID, agent))
     }
 
-    pub async fn get_individual_canister_by_user_principal(
+    pub async fn get_user_metadata(
         &self,
         user_principal: Principal,
-    ) -> Result<Option<Principal>, ServerFnError> {
-        let meta = self
+    ) -> Result<Option<UserMetadata>, ServerFnError> {
+        Ok(self
             .metadata_client
             .get_user_metadata(user_principal)
-            .await?;
+            .await?)
+    }
+
+    pub async fn get_individual_canister_by_user_principal(
+        &self,
+        user_principal: Principal,
+    ) -> Result<Option<Principal>, ServerFnError> {
+        let meta = self.get_user_metadata(user_principal).await?;
         Ok(meta.map(|m| m.user_canister_id))
     }
 
-    async fn subnet_indexes(&self) -> Result<Vec<Principal>, AgentError> {
+    pub async fn subnet_indexes(&self) -> Result<Vec<Principal>, AgentError> {
+        if let Some(dev) = DevMode::get() {
+            return Ok(vec![dev.user_index]);
+        }
         #[cfg(any(feature = "local-bin", feature = "local-lib"))]
         {
             use crate::canister::USER_INDEX_ID;
@@ -273,13 +295,58 @@ pub async fn do_canister_auth(
 
 pub type AuthCansResource = Resource<
     MockPartialEq<Option<DelegatedIdentityWire>>,
-    Result<CanistersAuthWire, ServerFnError>,
+    Result<Option<CanistersAuthWire>, ServerFnError>,
 >;
 
-/// The Authenticated Canisters helper resource
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AuthCansError {
+    #[error(transparent)]
+    Server(#[from] ServerFnError),
+    #[error(transparent)]
+    InvalidIdentity(#[from] InvalidIdentityWire),
+}
+
+type AuthCansResolution = Option<Result<Option<Canisters<true>>, AuthCansError>>;
+
+/// Authenticated canisters resolved from [AuthCansResource]
+/// the identity is only rebuilt when the resource changes
+/// and shared by every reader
+#[derive(Clone, Copy)]
+pub struct AuthCans {
+    res: AuthCansResource,
+    // Canisters can't be compared, every resolution notifies the readers
+    resolved: Memo<MockPartialEq<AuthCansResolution>>,
+}
+
+impl AuthCans {
+    pub fn new(res: AuthCansResource) -> Self {
+        let resolved = create_memo(move |_| {
+            MockPartialEq(res.get().map(|wire| {
+                let Some(wire) = wire? else {
+                    return Ok(None);
+                };
+                Ok(Some(wire.canisters()?))
+            }))
+        });
+        Self { res, resolved }
+    }
+
+    /// `None` while loading, `Ok(None)` while the user is browsing anonymously
+    /// must be read within a `Suspense` to wait for the resolution
+    pub fn resolved(&self) -> AuthCansResolution {
+        // Memos are computed outside the reader's Suspense
+        // so the resource is read here to register with it
+        self.res.with(|_| ());
+        self.resolved.with(|r| r.0.clone())
+    }
+}
+
+/// The Authenticated Canisters helper
+/// resolves to `None` while the user is browsing anonymously
+/// see [crate::state::auth::AnonymousBrowse]
 /// prefer using helpers from [crate::component::canisters_prov]
 /// instead
-pub fn authenticated_canisters() -> AuthCansResource {
+pub fn authenticated_canisters() -> AuthCans {
     expect_context()
 }
 
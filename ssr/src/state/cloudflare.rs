@@ -0,0 +1,180 @@
+use std::{ops::Deref, sync::Arc};
+
+use leptos::*;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::consts::{CF_BASE_URL, CF_IMAGES_BASE, CF_STREAM_BASE, CF_WATERMARK_UID};
+
+#[cfg(feature = "ssr")]
+use super::dev_mode::DevMode;
+
+/// Id of the element shipping the server's configuration to the client
+const CONFIG_ELEMENT_ID: &str = "cf-config";
+
+/// Cloudflare account specific configuration, cheap to clone
+///
+/// Read from the environment on the server, falling back to the production
+/// account for unset variables, the stream base is replaced in [DevMode]
+/// The server ships it to the client with [CloudflareConfigScript],
+/// so that both render the same URLs
+#[derive(Clone, Debug, Default)]
+pub struct CloudflareConfig(Arc<CloudflareSettings>);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CloudflareSettings {
+    pub stream_base: String,
+    /// Delivery base of copies uploaded without the watermark, if any
+    pub clean_stream_base: Option<String>,
+    /// Variant played in the app, see [StreamVariant]
+    #[serde(default)]
+    pub in_app_variant: StreamVariant,
+    pub images_base: String,
+    /// Watermark profile applied to every upload
+    pub watermark_uid: String,
+    /// Only used by the server
+    #[serde(skip, default = "default_api_base")]
+    pub api_base: Url,
+}
+
+fn default_api_base() -> Url {
+    CF_BASE_URL.clone()
+}
+
+impl Default for CloudflareSettings {
+    fn default() -> Self {
+        Self {
+            stream_base: CF_STREAM_BASE.into(),
+            clean_stream_base: None,
+            in_app_variant: StreamVariant::default(),
+            images_base: CF_IMAGES_BASE.into(),
+            watermark_uid: CF_WATERMARK_UID.into(),
+            api_base: default_api_base(),
+        }
+    }
+}
+
+impl CloudflareSettings {
+    /// Delivery base of the given variant
+    /// the clean variant falls back to the watermarked stream
+    pub fn stream_base_for(&self, variant: StreamVariant) -> &str {
+        match (variant, &self.clean_stream_base) {
+            (StreamVariant::Clean, Some(base)) => base,
+            _ => &self.stream_base,
+        }
+    }
+}
+
+impl Deref for CloudflareConfig {
+    type Target = CloudflareSettings;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl CloudflareConfig {
+    #[cfg(feature = "ssr")]
+    pub fn from_env() -> Self {
+        use std::env;
+
+        let default = CloudflareSettings::default();
+        Self(Arc::new(CloudflareSettings {
+            stream_base: DevMode::get()
+                .map(|dev| dev.stream_base.clone())
+                .or_else(|| env::var("CF_STREAM_BASE").ok())
+                .unwrap_or(default.stream_base),
+            clean_stream_base: env::var("CF_CLEAN_STREAM_BASE").ok(),
+            in_app_variant: env::var("CF_IN_APP_STREAM_VARIANT")
+                .map(|variant| match variant.trim() {
+                    "watermarked" => StreamVariant::Watermarked,
+                    "clean" => StreamVariant::Clean,
+                    _ => panic!(
+                        "Invalid `CF_IN_APP_STREAM_VARIANT`, expected `watermarked` or `clean`"
+                    ),
+                })
+                .unwrap_or(default.in_app_variant),
+            images_base: env::var("CF_IMAGES_BASE").unwrap_or(default.images_base),
+            watermark_uid: env::var("CF_WATERMARK_UID").unwrap_or(default.watermark_uid),
+            api_base: env::var("CF_BASE_URL")
+                .map(|url| Url::parse(&url).expect("Invalid `CF_BASE_URL`"))
+                .unwrap_or(default.api_base),
+        }))
+    }
+
+    /// Configuration shipped by the server with [CloudflareConfigScript]
+    /// defaults if it's missing
+    #[cfg(not(feature = "ssr"))]
+    pub fn from_document() -> Self {
+        #[cfg(feature = "hydrate")]
+        {
+            let shipped = document()
+                .get_element_by_id(CONFIG_ELEMENT_ID)
+                .and_then(|el| el.text_content())
+                .and_then(|raw| serde_json::from_str(&raw).ok());
+            if let Some(settings) = shipped {
+                return Self(Arc::new(settings));
+            }
+            log::warn!("missing cloudflare configuration, using the defaults");
+        }
+        Self::default()
+    }
+
+    /// Configuration provided in context
+    /// defaults outside a reactive owner
+    pub fn get() -> Self {
+        use_context().unwrap_or_default()
+    }
+}
+
+/// Credentials of the Cloudflare account, never sent to the client
+/// back [gob_cloudflare::CloudflareAuth] and the Images API calls it doesn't cover
+#[cfg(all(feature = "ssr", feature = "cloudflare"))]
+#[derive(Clone)]
+pub struct CloudflareCredentials {
+    pub account_id: String,
+    pub token: String,
+}
+
+#[cfg(all(feature = "ssr", feature = "cloudflare"))]
+impl CloudflareCredentials {
+    pub fn from_env() -> Self {
+        use std::env;
+
+        Self {
+            account_id: env::var("CF_ACCOUNT_ID").expect("`CF_ACCOUNT_ID` is required!"),
+            token: env::var("CF_TOKEN").expect("`CF_TOKEN` is required!"),
+        }
+    }
+}
+
+/// Stream variant played in the app
+/// provided in context from [CloudflareSettings::in_app_variant]
+///
+/// Shared and downloaded copies always use [StreamVariant::Watermarked]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamVariant {
+    #[default]
+    Watermarked,
+    Clean,
+}
+
+impl StreamVariant {
+    /// Variant provided in context
+    /// defaults to watermarked outside a reactive owner
+    pub fn get() -> Self {
+        use_context().unwrap_or_default()
+    }
+}
+
+/// Ships the configuration in context to the client, see [CloudflareConfig::from_document]
+#[component]
+pub fn CloudflareConfigScript() -> impl IntoView {
+    let config = CloudflareConfig::get();
+    // `<` can't be escaped inside a script element, keep `</script>` out of the JSON instead
+    let settings = serde_json::to_string(&*config)
+        .expect("cloudflare configuration must serialize")
+        .replace('<', "\\u003c");
+    view! { <script id=CONFIG_ELEMENT_ID type="application/json" inner_html=settings></script> }
+}
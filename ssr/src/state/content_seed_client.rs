@@ -7,6 +7,7 @@ use std::error::Error;
 
 use crate::auth::DelegatedIdentityWire;
 use crate::consts::DOWNLOAD_UPLOAD_SERVICE;
+use crate::utils::http::http_client;
 
 #[derive(Deserialize)]
 pub struct AllowPrincpalRes {
@@ -33,7 +34,7 @@ pub struct ContentSeedClient {
 impl Default for ContentSeedClient {
     fn default() -> Self {
         Self {
-            client: Default::default(),
+            client: http_client().client().clone(),
             base_url: DOWNLOAD_UPLOAD_SERVICE.clone(),
         }
     }
@@ -42,7 +43,7 @@ impl Default for ContentSeedClient {
 impl ContentSeedClient {
     pub fn with_base_url(url: Url) -> Self {
         ContentSeedClient {
-            client: Default::default(),
+            client: http_client().client().clone(),
             base_url: url,
         }
     }
@@ -54,8 +55,7 @@ impl ContentSeedClient {
             .expect("url error")
             .join(&principal.to_string())
             .expect("url error");
-        let res = self.client.get(api_url).send().await?;
-        let res_json: AllowPrincpalRes = res.json().await?;
+        let res_json: AllowPrincpalRes = http_client().get_json(api_url.as_str()).await?;
         Ok(res_json.allowed)
     }
 
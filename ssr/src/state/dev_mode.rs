@@ -0,0 +1,158 @@
+//! Developer mode, points a regular build at a local replica instead of mainnet
+//!
+//! Enabled on the server with `DEV_MODE=true`, which requires `DEV_AGENT_URL`,
+//! `DEV_STREAM_BASE` and `DEV_USER_INDEX`, startup fails listing any that are missing
+//! The server ships the settings to the client with [DevModeScript]
+use candid::Principal;
+use leptos::*;
+use once_cell::sync::OnceCell;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// Id of the element shipping the server's settings to the client
+const CONFIG_ELEMENT_ID: &str = "dev-mode";
+
+static DEV_MODE: OnceCell<Option<DevMode>> = OnceCell::new();
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DevMode {
+    /// Replica the IC agent talks to
+    pub agent_url: String,
+    /// Replaces the Cloudflare stream base
+    pub stream_base: String,
+    /// Only user index queried, replaces the orchestrator's subnet indexes
+    pub user_index: Principal,
+}
+
+impl DevMode {
+    /// Settings of the developer mode, `None` outside of it
+    ///
+    /// # Panics
+    /// On the server, if `DEV_MODE` is set but the configuration is incomplete
+    pub fn get() -> Option<&'static DevMode> {
+        DEV_MODE.get_or_init(Self::load).as_ref()
+    }
+
+    #[cfg(feature = "ssr")]
+    fn load() -> Option<Self> {
+        Self::from_vars(|var| std::env::var(var).ok()).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Settings shipped by the server with [DevModeScript]
+    #[cfg(not(feature = "ssr"))]
+    fn load() -> Option<Self> {
+        #[cfg(feature = "hydrate")]
+        {
+            document()
+                .get_element_by_id(CONFIG_ELEMENT_ID)
+                .and_then(|el| el.text_content())
+                .and_then(|raw| serde_json::from_str(&raw).ok())
+                .flatten()
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            None
+        }
+    }
+
+    /// Settings from the given environment
+    #[cfg_attr(not(feature = "ssr"), allow(dead_code))]
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, String> {
+        let enabled = var("DEV_MODE").is_some_and(|v| matches!(v.trim(), "1" | "true"));
+        if !enabled {
+            return Ok(None);
+        }
+
+        let required = ["DEV_AGENT_URL", "DEV_STREAM_BASE", "DEV_USER_INDEX"];
+        let missing: Vec<_> = required
+            .into_iter()
+            .filter(|name| var(name).map_or(true, |v| v.trim().is_empty()))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "`DEV_MODE` is enabled but {} not set",
+                missing
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let var = |name: &str| var(name).unwrap_or_default().trim().to_string();
+
+        let agent_url = var("DEV_AGENT_URL");
+        Url::parse(&agent_url).map_err(|e| format!("Invalid `DEV_AGENT_URL`: {e}"))?;
+        let stream_base = var("DEV_STREAM_BASE");
+        Url::parse(&stream_base).map_err(|e| format!("Invalid `DEV_STREAM_BASE`: {e}"))?;
+        let user_index = Principal::from_text(var("DEV_USER_INDEX"))
+            .map_err(|e| format!("Invalid `DEV_USER_INDEX`: {e}"))?;
+
+        Ok(Some(Self {
+            agent_url,
+            stream_base,
+            user_index,
+        }))
+    }
+}
+
+/// Ships the developer mode settings to the client, see [DevMode::get]
+#[component]
+pub fn DevModeScript() -> impl IntoView {
+    let settings = serde_json::to_string(&DevMode::get())
+        .expect("developer mode settings must serialize")
+        .replace('<', "\\u003c");
+    view! { <script id=CONFIG_ELEMENT_ID type="application/json" inner_html=settings></script> }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::DevMode;
+
+    fn from(vars: &[(&str, &str)]) -> Result<Option<DevMode>, String> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        DevMode::from_vars(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    const COMPLETE: [(&str, &str); 4] = [
+        ("DEV_MODE", "true"),
+        ("DEV_AGENT_URL", "http://localhost:4943"),
+        ("DEV_STREAM_BASE", "http://localhost:8080"),
+        ("DEV_USER_INDEX", "rrkah-fqaaa-aaaaa-aaaaq-cai"),
+    ];
+
+    #[test]
+    fn disabled_without_dev_mode() {
+        assert_eq!(from(&[]), Ok(None));
+        assert_eq!(from(&[("DEV_MODE", "false")]), Ok(None));
+        assert_eq!(from(&COMPLETE[1..]), Ok(None));
+    }
+
+    #[test]
+    fn complete_config_is_used() {
+        let dev = from(&COMPLETE).unwrap().unwrap();
+        assert_eq!(dev.agent_url, "http://localhost:4943");
+        assert_eq!(dev.stream_base, "http://localhost:8080");
+        assert_eq!(dev.user_index.to_text(), "rrkah-fqaaa-aaaaa-aaaaq-cai");
+    }
+
+    #[test]
+    fn missing_vars_are_listed() {
+        let err = from(&[("DEV_MODE", "1"), ("DEV_STREAM_BASE", " ")]).unwrap_err();
+        assert!(err.contains("`DEV_AGENT_URL`"), "{err}");
+        assert!(err.contains("`DEV_STREAM_BASE`"), "{err}");
+        assert!(err.contains("`DEV_USER_INDEX`"), "{err}");
+    }
+
+    #[test]
+    fn invalid_vars_are_rejected() {
+        let mut vars = COMPLETE;
+        vars[3] = ("DEV_USER_INDEX", "not-a-principal");
+        assert!(from(&vars).unwrap_err().contains("`DEV_USER_INDEX`"));
+
+        let mut vars = COMPLETE;
+        vars[1] = ("DEV_AGENT_URL", "localhost");
+        assert!(from(&vars).unwrap_err().contains("`DEV_AGENT_URL`"));
+    }
+}
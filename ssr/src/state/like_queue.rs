@@ -0,0 +1,160 @@
+//! Like toggles that failed to sync due to connectivity issues
+//! persisted and retried once the browser is back online
+use candid::Principal;
+use codee::string::JsonSerdeCodec;
+use leptos::*;
+use leptos_use::{storage::use_local_storage, use_event_listener, use_window};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    consts::PENDING_LIKES_STORE,
+    utils::{
+        call_limit::CallPriority,
+        ic::{canister_call, CallError},
+    },
+};
+
+use super::{
+    app_events::{AppEvent, AppEvents},
+    canisters::{auth_canisters_store, Canisters},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct PendingLike {
+    pub canister_id: Principal,
+    pub post_id: u64,
+    /// Intended like status
+    pub liked: bool,
+}
+
+/// Persistent queue of like toggles, provided in context
+///
+/// Only the latest intent per post is kept, the queue is flushed
+/// whenever the browser comes back online or the user's canisters are (re)loaded
+#[derive(Clone, Copy)]
+pub struct LikeQueue {
+    store: Signal<Vec<PendingLike>>,
+    set_store: WriteSignal<Vec<PendingLike>>,
+    flushing: StoredValue<bool>,
+}
+
+impl LikeQueue {
+    pub fn new() -> Self {
+        let (store, set_store, _) =
+            use_local_storage::<Vec<PendingLike>, JsonSerdeCodec>(PENDING_LIKES_STORE);
+        let this = Self {
+            store,
+            set_store,
+            flushing: store_value(false),
+        };
+
+        let canisters = auth_canisters_store();
+        _ = use_event_listener(use_window(), ev::online, move |_| {
+            if let Some(cans) = canisters.get_untracked() {
+                this.flush(cans);
+            }
+        });
+        create_effect(move |_| {
+            if let Some(cans) = canisters() {
+                this.flush(cans);
+            }
+        });
+
+        this
+    }
+
+    pub fn get() -> Option<Self> {
+        use_context()
+    }
+
+    /// Queued intent for the post, tracked
+    pub fn pending(&self, canister_id: Principal, post_id: u64) -> Option<bool> {
+        self.store.with(|q| {
+            q.iter()
+                .find(|p| p.canister_id == canister_id && p.post_id == post_id)
+                .map(|p| p.liked)
+        })
+    }
+
+    pub fn enqueue(&self, like: PendingLike) {
+        self.set_store.update(|q| {
+            q.retain(|p| p.canister_id != like.canister_id || p.post_id != like.post_id);
+            q.push(like);
+        });
+    }
+
+    pub fn remove(&self, canister_id: Principal, post_id: u64) {
+        if self.pending(canister_id, post_id).is_none() {
+            return;
+        }
+        self.set_store
+            .update(|q| q.retain(|p| p.canister_id != canister_id || p.post_id != post_id));
+    }
+
+    /// Apply the queued intent, reconciling against the canister state
+    /// returns the confirmed (liked, likes)
+    async fn apply(
+        canisters: &Canisters<true>,
+        like: PendingLike,
+    ) -> Result<(bool, u64), CallError> {
+        let individual = canisters.individual_user(like.canister_id).await?;
+        let post = canister_call(
+            CallPriority::Background,
+            individual.get_individual_post_details_by_id(like.post_id),
+        )
+        .await?;
+        if post.liked_by_me == like.liked {
+            return Ok((post.liked_by_me, post.like_count));
+        }
+
+        let liked = canister_call(
+            CallPriority::Background,
+            individual.update_post_toggle_like_status_by_caller(like.post_id),
+        )
+        .await?;
+        let likes = if liked {
+            post.like_count.saturating_add(1)
+        } else {
+            post.like_count.saturating_sub(1)
+        };
+        Ok((liked, likes))
+    }
+
+    pub fn flush(self, canisters: Canisters<true>) {
+        if self.flushing.get_value() || self.store.with_untracked(|q| q.is_empty()) {
+            return;
+        }
+        self.flushing.set_value(true);
+        let events = AppEvents::get();
+        spawn_local(async move {
+            let queued = self.store.get_untracked();
+            for like in queued {
+                match Self::apply(&canisters, like).await {
+                    Ok((liked, likes)) => {
+                        // Only drop the entry if it wasn't superseded in the meantime
+                        if self.pending(like.canister_id, like.post_id) == Some(like.liked) {
+                            self.remove(like.canister_id, like.post_id);
+                        }
+                        events.publish(AppEvent::Liked {
+                            canister_id: like.canister_id,
+                            post_id: like.post_id,
+                            liked,
+                            likes,
+                        });
+                    }
+                    // Still offline, retried on the next flush
+                    Err(CallError::Replica(e)) => {
+                        log::warn!("failed to sync queued like: {e}");
+                        break;
+                    }
+                    // The post is gone, the intent can never be applied
+                    Err(e) => {
+                        log::warn!("dropping queued like: {e}");
+                        self.remove(like.canister_id, like.post_id);
+                    }
+                }
+            }
+            _ = self.flushing.try_set_value(false);
+        });
+    }
+}
@@ -1,9 +1,15 @@
+use std::collections::HashMap;
+
 use candid::Principal;
 use codee::string::JsonSerdeCodec;
 use leptos::{Signal, WriteSignal};
-use leptos_use::storage::use_local_storage;
+use leptos_use::storage::{use_local_storage, use_session_storage};
+use serde::{Deserialize, Serialize};
 
-use crate::consts::REFERRER_STORE;
+use crate::consts::{
+    AUDIO_UNMUTED_STORE, DATA_SAVER_STORE, FEED_POSITION_STORE, LIST_SCROLL_STORE,
+    ONBOARDING_TOUR_STORE, REFERRER_STORE, REPORTED_POSTS_STORE,
+};
 
 pub fn use_referrer_store() -> (
     Signal<Option<Principal>>,
@@ -12,3 +18,67 @@ pub fn use_referrer_store() -> (
 ) {
     use_local_storage::<Option<Principal>, JsonSerdeCodec>(REFERRER_STORE)
 }
+
+/// Last viewed position in the feed
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FeedPosition {
+    pub canister_id: Principal,
+    pub post_id: u64,
+    /// index of the post in the feed queue
+    pub idx: usize,
+    /// identifier of the feed the position belongs to
+    pub feed: String,
+}
+
+/// Session scoped store for the last viewed feed position
+pub fn use_feed_position_store() -> (
+    Signal<Option<FeedPosition>>,
+    WriteSignal<Option<FeedPosition>>,
+    impl Fn() + Clone,
+) {
+    use_session_storage::<Option<FeedPosition>, JsonSerdeCodec>(FEED_POSITION_STORE)
+}
+
+/// Whether the onboarding tour was completed (or skipped)
+pub fn use_onboarding_tour_store() -> (Signal<bool>, WriteSignal<bool>, impl Fn() + Clone) {
+    use_local_storage::<bool, JsonSerdeCodec>(ONBOARDING_TOUR_STORE)
+}
+
+/// Whether the user unmuted videos
+/// stored as "unmuted" so that first visits default to muted, as required for autoplay
+pub fn use_audio_unmuted_store() -> (Signal<bool>, WriteSignal<bool>, impl Fn() + Clone) {
+    use_local_storage::<bool, JsonSerdeCodec>(AUDIO_UNMUTED_STORE)
+}
+
+/// Whether the user opted into reduced data usage
+pub fn use_data_saver_store() -> (Signal<bool>, WriteSignal<bool>, impl Fn() + Clone) {
+    use_local_storage::<bool, JsonSerdeCodec>(DATA_SAVER_STORE)
+}
+
+/// Posts (canister id, post id) reported in this session
+/// used to prevent duplicate reports
+pub fn use_reported_posts_store() -> (
+    Signal<Vec<(Principal, u64)>>,
+    WriteSignal<Vec<(Principal, u64)>>,
+    impl Fn() + Clone,
+) {
+    use_session_storage::<Vec<(Principal, u64)>, JsonSerdeCodec>(REPORTED_POSTS_STORE)
+}
+
+/// Scroll position of an infinite list
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ListScroll {
+    /// Vertical scroll offset of the page
+    pub offset: f64,
+    /// Number of items that were loaded, required to scroll back to `offset`
+    pub items: usize,
+}
+
+/// Scroll positions of infinite lists in this session, keyed by route
+pub fn use_list_scroll_store() -> (
+    Signal<HashMap<String, ListScroll>>,
+    WriteSignal<HashMap<String, ListScroll>>,
+    impl Fn() + Clone,
+) {
+    use_session_storage::<HashMap<String, ListScroll>, JsonSerdeCodec>(LIST_SCROLL_STORE)
+}
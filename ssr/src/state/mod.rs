@@ -1,11 +1,16 @@
 #[cfg(feature = "backend-admin")]
 pub mod admin_canisters;
+pub mod app_events;
 pub mod audio_state;
 pub mod auth;
 pub mod canisters;
+pub mod cloudflare;
 pub mod content_seed_client;
+pub mod dev_mode;
 pub mod history;
+pub mod like_queue;
 pub mod local_storage;
+pub mod profile_details;
 
 #[cfg(feature = "ssr")]
 pub mod server {
@@ -26,7 +31,12 @@ pub mod server {
         pub admin_canisters: super::admin_canisters::AdminCanisters,
         #[cfg(feature = "cloudflare")]
         pub cloudflare: gob_cloudflare::CloudflareAuth,
+        #[cfg(feature = "cloudflare")]
+        pub cloudflare_credentials: super::cloudflare::CloudflareCredentials,
         pub kv: KVStoreImpl,
+        pub cloudflare_config: super::cloudflare::CloudflareConfig,
+        pub auth_config: super::auth::config::AuthConfig,
+        pub trusted_proxies: crate::auth::server_impl::client_ip::TrustedProxies,
         pub routes: Vec<RouteListing>,
         pub cookie_key: Key,
         #[cfg(feature = "oauth-ssr")]
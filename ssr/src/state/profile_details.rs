@@ -0,0 +1,74 @@
+use leptos::{web_sys::VisibilityState, *};
+use leptos_use::{use_document_visibility, use_window_focus};
+
+use crate::{
+    consts::PROFILE_DETAILS_STALE_AFTER,
+    state::canisters::Canisters,
+    utils::{current_epoch, profile::ProfileDetails},
+};
+
+/// Explicit invalidation of the authenticated user's profile details
+#[derive(Clone, Copy)]
+pub struct ProfileDetailsInvalidator(Trigger);
+
+impl Default for ProfileDetailsInvalidator {
+    fn default() -> Self {
+        Self(create_trigger())
+    }
+}
+
+/// Refetch the profile details of the authenticated user
+/// must be called after the profile is edited
+pub fn invalidate_profile_details() {
+    let ProfileDetailsInvalidator(trigger) = expect_context();
+    trigger.notify();
+}
+
+/// Profile details of the authenticated user
+/// revalidated when the page regains focus/visibility after [PROFILE_DETAILS_STALE_AFTER]
+/// or immediately on [invalidate_profile_details]
+pub fn use_profile_details(canisters: Canisters<true>) -> ReadSignal<ProfileDetails> {
+    let (details, set_details) = create_signal(canisters.profile_details());
+    let fetched_at = store_value(current_epoch());
+
+    let refetch = create_action(move |&()| {
+        let canisters = canisters.clone();
+        async move {
+            let res = async {
+                let user = canisters.authenticated_user().await?;
+                user.get_profile_details().await
+            }
+            .await;
+            match res {
+                Ok(profile) => {
+                    _ = fetched_at.try_set_value(current_epoch());
+                    _ = set_details.try_set(profile.into());
+                }
+                Err(e) => log::warn!("failed to refresh profile details: {e}"),
+            }
+        }
+    });
+
+    let ProfileDetailsInvalidator(invalidated) = expect_context();
+    create_effect(move |prev: Option<()>| {
+        invalidated.track();
+        // Details are fresh on the first run
+        if prev.is_some() {
+            refetch.dispatch(());
+        }
+    });
+
+    let visibility = use_document_visibility();
+    let focused = use_window_focus();
+    create_effect(move |_| {
+        if visibility() != VisibilityState::Visible || !focused() {
+            return;
+        }
+        let age = current_epoch().saturating_sub(fetched_at.get_value());
+        if age > PROFILE_DETAILS_STALE_AFTER && !refetch.pending().get_untracked() {
+            refetch.dispatch(());
+        }
+    });
+
+    details
+}
@@ -0,0 +1,244 @@
+//! Global concurrency limit for outbound canister calls
+//! Excess calls are queued, user initiated calls are always dequeued
+//! before background (prefetch) calls
+//!
+//! The limit is applied by the agent's transport ([LimitedTransport]), so every
+//! canister call is covered, [limit_call] only sets the priority of the calls it makes
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    future::Future,
+    pin::{pin, Pin},
+    sync::{Arc, Mutex},
+};
+
+use candid::Principal;
+use futures::channel::oneshot;
+use ic_agent::{agent::Transport, AgentError};
+use once_cell::sync::Lazy;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CallPriority {
+    /// Calls made in response to a user action (like, bet, etc)
+    User,
+    /// Prefetching and other background calls
+    #[default]
+    Background,
+}
+
+struct LimiterState {
+    available: usize,
+    user_queue: VecDeque<oneshot::Sender<CallPermit>>,
+    background_queue: VecDeque<oneshot::Sender<CallPermit>>,
+}
+
+type SharedState = Arc<Mutex<LimiterState>>;
+
+/// Permit for a single in-flight call
+/// released on drop
+pub struct CallPermit(Option<SharedState>);
+
+impl Drop for CallPermit {
+    fn drop(&mut self) {
+        if let Some(state) = self.0.take() {
+            release(state);
+        }
+    }
+}
+
+fn release(state: SharedState) {
+    loop {
+        let mut guard = state.lock().unwrap();
+        let Some(waiter) = guard
+            .user_queue
+            .pop_front()
+            .or_else(|| guard.background_queue.pop_front())
+        else {
+            guard.available += 1;
+            return;
+        };
+        drop(guard);
+
+        match waiter.send(CallPermit(Some(state.clone()))) {
+            Ok(()) => return,
+            // Waiter was cancelled, hand the permit to the next one
+            Err(mut permit) => _ = permit.0.take(),
+        }
+    }
+}
+
+pub struct CallLimiter(SharedState);
+
+impl CallLimiter {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self(Arc::new(Mutex::new(LimiterState {
+            available: max_concurrent,
+            user_queue: VecDeque::new(),
+            background_queue: VecDeque::new(),
+        })))
+    }
+
+    pub async fn acquire(&self, priority: CallPriority) -> CallPermit {
+        let rx = {
+            let mut guard = self.0.lock().unwrap();
+            if guard.available > 0 {
+                guard.available -= 1;
+                return CallPermit(Some(self.0.clone()));
+            }
+            let (tx, rx) = oneshot::channel();
+            match priority {
+                CallPriority::User => guard.user_queue.push_back(tx),
+                CallPriority::Background => guard.background_queue.push_back(tx),
+            }
+            rx
+        };
+
+        rx.await.expect("call limiter must outlive queued calls")
+    }
+}
+
+static CALL_LIMITER: Lazy<CallLimiter> = Lazy::new(|| {
+    // Server side calls are already bounded by the incoming requests
+    #[cfg(feature = "hydrate")]
+    {
+        CallLimiter::new(crate::consts::MAX_CONCURRENT_CANISTER_CALLS)
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        CallLimiter::new(usize::MAX)
+    }
+});
+
+thread_local! {
+    /// Priority of the calls made by the future being polled
+    static CURRENT_PRIORITY: Cell<CallPriority> = Cell::new(CallPriority::default());
+}
+
+/// Restores the previous priority once the poll returns (or unwinds)
+struct PriorityGuard(CallPriority);
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        CURRENT_PRIORITY.with(|p| p.set(self.0));
+    }
+}
+
+fn current_priority() -> CallPriority {
+    CURRENT_PRIORITY.with(Cell::get)
+}
+
+/// Make the canister calls of `call` with the given priority
+/// calls made outside of [limit_call] are [CallPriority::Background]
+pub async fn limit_call<F: Future>(priority: CallPriority, call: F) -> F::Output {
+    let mut call = pin!(call);
+    std::future::poll_fn(|cx| {
+        let _guard = PriorityGuard(CURRENT_PRIORITY.with(|p| p.replace(priority)));
+        call.as_mut().poll(cx)
+    })
+    .await
+}
+
+#[cfg(not(target_family = "wasm"))]
+type AgentFuture<'a, V> = Pin<Box<dyn Future<Output = Result<V, AgentError>> + Send + 'a>>;
+#[cfg(target_family = "wasm")]
+type AgentFuture<'a, V> = Pin<Box<dyn Future<Output = Result<V, AgentError>> + 'a>>;
+
+/// Transport making every request within the global canister call limit
+pub struct LimitedTransport<T>(pub T);
+
+impl<T> LimitedTransport<T> {
+    fn limited<'a, V: 'a>(request: AgentFuture<'a, V>) -> AgentFuture<'a, V> {
+        // The priority is only set while the caller is polled, read it right away
+        let priority = current_priority();
+        Box::pin(async move {
+            let _permit = CALL_LIMITER.acquire(priority).await;
+            request.await
+        })
+    }
+}
+
+impl<T: Transport> Transport for LimitedTransport<T> {
+    fn call(&self, effective_canister_id: Principal, envelope: Vec<u8>) -> AgentFuture<()> {
+        Self::limited(self.0.call(effective_canister_id, envelope))
+    }
+
+    fn read_state(
+        &self,
+        effective_canister_id: Principal,
+        envelope: Vec<u8>,
+    ) -> AgentFuture<Vec<u8>> {
+        Self::limited(self.0.read_state(effective_canister_id, envelope))
+    }
+
+    fn read_subnet_state(&self, subnet_id: Principal, envelope: Vec<u8>) -> AgentFuture<Vec<u8>> {
+        Self::limited(self.0.read_subnet_state(subnet_id, envelope))
+    }
+
+    fn query(&self, effective_canister_id: Principal, envelope: Vec<u8>) -> AgentFuture<Vec<u8>> {
+        Self::limited(self.0.query(effective_canister_id, envelope))
+    }
+
+    fn status(&self) -> AgentFuture<Vec<u8>> {
+        Self::limited(self.0.status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Poll;
+
+    use futures::{executor::block_on, future::join};
+
+    use super::*;
+
+    /// Yield to the executor once
+    async fn yield_now() {
+        let mut yielded = false;
+        std::future::poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    #[test]
+    fn limit_call_sets_priority_while_polled() {
+        assert_eq!(current_priority(), CallPriority::Background);
+        let seen = block_on(limit_call(CallPriority::User, async {
+            yield_now().await;
+            current_priority()
+        }));
+        assert_eq!(seen, CallPriority::User);
+        assert_eq!(current_priority(), CallPriority::Background);
+    }
+
+    #[test]
+    fn queued_user_calls_go_first() {
+        let limiter = CallLimiter::new(1);
+        let order = Mutex::new(vec![]);
+        block_on(async {
+            let held = limiter.acquire(CallPriority::Background).await;
+            let background = async {
+                let _permit = limiter.acquire(CallPriority::Background).await;
+                order.lock().unwrap().push(CallPriority::Background);
+            };
+            let user = async {
+                let _permit = limiter.acquire(CallPriority::User).await;
+                order.lock().unwrap().push(CallPriority::User);
+            };
+            let release = async {
+                yield_now().await;
+                drop(held);
+            };
+            join(join(background, user), release).await;
+        });
+        assert_eq!(
+            *order.lock().unwrap(),
+            [CallPriority::User, CallPriority::Background]
+        );
+    }
+}
@@ -8,6 +8,8 @@ use wasm_bindgen::prelude::*;
 use crate::consts::GTAG_MEASUREMENT_ID;
 
 pub mod events;
+#[cfg(feature = "ssr")]
+pub mod trending;
 
 #[cfg(not(clippy))]
 #[cfg(feature = "ssr")]
@@ -90,6 +92,21 @@ pub async fn stream_to_offchain_agent(event: String, params: String) -> Result<(
 
     let channel: Channel = expect_context();
 
+    let params = match serde_json::from_str::<serde_json::Value>(&params) {
+        Ok(mut params_json) => {
+            let kv: crate::auth::server_impl::store::KVStoreImpl = expect_context();
+            let caller = crate::auth::server_impl::caller_principal()
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("failed to extract the caller: {e}");
+                    None
+                });
+            trending::process_event(&kv, caller, &event, &mut params_json).await;
+            params_json.to_string()
+        }
+        Err(_) => params,
+    };
+
     let mut off_chain_agent_grpc_auth_token = env::var("GRPC_AUTH_TOKEN").expect("GRPC_AUTH_TOKEN");
     // removing whitespaces and new lines for proper parsing
     off_chain_agent_grpc_auth_token.retain(|c| !c.is_whitespace());
@@ -0,0 +1,148 @@
+//! Weighting of likes toward trending
+//! a like only counts toward the trending score if the liker watched
+//! the video for at least [min_watch_secs], raw like counts are unaffected
+//!
+//! Watch times are only recorded for events sent by the user themselves
+//! and are forgotten after [WATCH_TIME_TTL]
+use candid::Principal;
+use serde_json::{json, Value};
+use web_time::Duration;
+
+use crate::{
+    auth::server_impl::store::{KVStore, KVStoreImpl},
+    consts::TRENDING_LIKE_MIN_WATCH_SECS,
+};
+
+/// `video_viewed` is sent once this much of the video (in seconds) is watched
+const VIDEO_VIEWED_AT_SECS: f64 = 3.0;
+/// Watch times are only kept this long, likes after that don't count toward trending
+const WATCH_TIME_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Minimum watch time (in seconds) for a like to count toward trending
+/// configurable via the `TRENDING_LIKE_MIN_WATCH_SECS` env var
+fn min_watch_secs() -> f64 {
+    std::env::var("TRENDING_LIKE_MIN_WATCH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(TRENDING_LIKE_MIN_WATCH_SECS)
+}
+
+/// `None` unless the event was sent by `caller` about a specific post
+fn watch_time_key(caller: Option<Principal>, params: &Value) -> Option<String> {
+    let user = params["user_id"].as_str()?;
+    if caller?.to_text() != user {
+        return None;
+    }
+    let canister = params["publisher_canister_id"].as_str()?;
+    let post_id = params["post_id"].as_u64()?;
+    Some(format!("watch-time-{user}-{canister}-{post_id}"))
+}
+
+/// Watched seconds reported by a `video_duration_watched` event
+/// capped to the video's duration, `None` if they're not a valid duration
+fn reported_watch_secs(params: &Value) -> Option<f64> {
+    let secs = params["absolute_watched"].as_f64()?;
+    if !secs.is_finite() || secs < 0.0 {
+        return None;
+    }
+    let duration = params["video_duration"].as_f64()?;
+    if !duration.is_finite() || duration <= 0.0 {
+        return None;
+    }
+    Some(secs.min(duration))
+}
+
+async fn watched_secs(kv: &KVStoreImpl, key: String) -> f64 {
+    match kv.read(key).await {
+        Ok(watched) => watched.and_then(|w| w.parse().ok()).unwrap_or_default(),
+        Err(e) => {
+            log::warn!("failed to read watch time: {e}");
+            0.0
+        }
+    }
+}
+
+async fn record_watch(kv: &KVStoreImpl, key: String, secs: f64) {
+    if secs <= watched_secs(kv, key.clone()).await {
+        return;
+    }
+    if let Err(e) = kv
+        .write_with_ttl(key, secs.to_string(), WATCH_TIME_TTL)
+        .await
+    {
+        log::warn!("failed to record watch time: {e}");
+    }
+}
+
+/// Record watch time events and annotate like events with their trending weight
+/// `caller` is the principal of the user sending the event
+pub async fn process_event(
+    kv: &KVStoreImpl,
+    caller: Option<Principal>,
+    event: &str,
+    params: &mut Value,
+) {
+    let key = watch_time_key(caller, params);
+    match (event, key) {
+        ("video_viewed", Some(key)) => record_watch(kv, key, VIDEO_VIEWED_AT_SECS).await,
+        ("video_duration_watched", Some(key)) => {
+            if let Some(secs) = reported_watch_secs(params) {
+                record_watch(kv, key, secs).await;
+            }
+        }
+        ("like_video", key) => {
+            let watched = match key {
+                Some(key) => watched_secs(kv, key).await,
+                None => 0.0,
+            };
+            params["watched_secs"] = json!(watched);
+            params["counts_toward_trending"] = json!(watched >= min_watch_secs());
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> Principal {
+        Principal::self_authenticating([1u8; 32])
+    }
+
+    fn event(user_id: Principal) -> Value {
+        json!({
+            "user_id": user_id.to_text(),
+            "publisher_canister_id": "canister",
+            "post_id": 7,
+        })
+    }
+
+    #[test]
+    fn watch_time_is_keyed_by_caller_and_post() {
+        let key = watch_time_key(Some(user()), &event(user())).unwrap();
+        assert_eq!(key, format!("watch-time-{}-canister-7", user()));
+    }
+
+    #[test]
+    fn events_for_other_users_are_ignored() {
+        let other = Principal::self_authenticating([2u8; 32]);
+        assert_eq!(watch_time_key(Some(other), &event(user())), None);
+        assert_eq!(watch_time_key(None, &event(user())), None);
+    }
+
+    #[test]
+    fn reported_watch_time_is_capped_to_the_duration() {
+        let watched = |secs: f64, duration: f64| {
+            reported_watch_secs(&json!({ "absolute_watched": secs, "video_duration": duration }))
+        };
+        assert_eq!(watched(4.0, 10.0), Some(4.0));
+        assert_eq!(watched(400.0, 10.0), Some(10.0));
+        assert_eq!(watched(-1.0, 10.0), None);
+        assert_eq!(watched(4.0, 0.0), None);
+        assert_eq!(
+            reported_watch_secs(&json!({ "absolute_watched": 4.0 })),
+            None
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use leptos::{server, server_fn::codec::GetUrl, ServerFnError};
+use serde::{Deserialize, Serialize};
+
+/// Reachability of the server's dependencies
+/// cheap enough to be polled every few seconds by load balancers and monitors
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// KV store (identities, refresh token rotation) is reachable
+    pub kv: bool,
+    /// Server time, in milliseconds since the unix epoch
+    pub epoch_ms: u128,
+}
+
+impl HealthStatus {
+    pub fn healthy(&self) -> bool {
+        self.kv
+    }
+}
+
+#[cfg(feature = "ssr")]
+mod server_impl {
+    use http::StatusCode;
+    use leptos::{expect_context, use_context, ServerFnError};
+    use leptos_axum::ResponseOptions;
+    use web_time::Duration;
+
+    use super::HealthStatus;
+    use crate::{
+        auth::server_impl::store::{KVStore, KVStoreImpl},
+        utils::current_epoch,
+    };
+
+    /// Key read to check KV connectivity, never written
+    const HEALTH_PROBE_KEY: &str = "health-probe";
+    /// A store that's slower than this is treated as unreachable
+    const KV_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+    async fn kv_reachable(kv: &KVStoreImpl) -> bool {
+        match tokio::time::timeout(KV_PROBE_TIMEOUT, kv.read(HEALTH_PROBE_KEY.into())).await {
+            Ok(Ok(_)) => true,
+            Ok(Err(e)) => {
+                log::warn!("health check: KV store unreachable: {e}");
+                false
+            }
+            Err(_) => {
+                log::warn!("health check: KV store timed out");
+                false
+            }
+        }
+    }
+
+    pub async fn health_check_impl() -> Result<HealthStatus, ServerFnError> {
+        let kv: KVStoreImpl = expect_context();
+        let status = HealthStatus {
+            kv: kv_reachable(&kv).await,
+            epoch_ms: current_epoch().as_millis(),
+        };
+        // Load balancers only look at the status code
+        if !status.healthy() {
+            if let Some(res) = use_context::<ResponseOptions>() {
+                res.set_status(StatusCode::SERVICE_UNAVAILABLE);
+            }
+        }
+        Ok(status)
+    }
+}
+
+/// Health of the server and its dependencies
+/// served over GET at `/api/health`, with a 503 status if any dependency is unhealthy
+#[server(endpoint = "health", input = GetUrl)]
+pub async fn health_check() -> Result<HealthStatus, ServerFnError> {
+    server_impl::health_check_impl().await
+}
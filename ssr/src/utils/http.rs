@@ -0,0 +1,320 @@
+//! Shared HTTP client for calls to external services
+//! Reuses connections, applies timeouts and retries idempotent requests
+use std::future::Future;
+
+use once_cell::sync::Lazy;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use web_time::Duration;
+
+use crate::consts::{HTTP_MAX_RETRIES, HTTP_RETRY_BACKOFF, HTTP_TIMEOUT};
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("invalid url {0}")]
+    InvalidUrl(String),
+    #[error("request failed {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("unexpected status {status} from {url}")]
+    Status { status: StatusCode, url: Url },
+}
+
+impl HttpError {
+    /// Whether retrying the same request may succeed
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::InvalidUrl(_) => false,
+            Self::Request(e) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                if e.is_connect() {
+                    return true;
+                }
+                e.is_timeout()
+            }
+            Self::Status { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct HttpConfig {
+    /// Timeout for a single attempt
+    /// only applied on the server, browsers enforce their own
+    pub timeout: Duration,
+    /// Retries for idempotent requests (GET/HEAD)
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every retry
+    pub retry_backoff: Duration,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout: HTTP_TIMEOUT,
+            max_retries: HTTP_MAX_RETRIES,
+            retry_backoff: HTTP_RETRY_BACKOFF,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpClient {
+    client: Client,
+    config: HttpConfig,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(HttpConfig::default())
+    }
+}
+
+async fn sleep(duration: Duration) {
+    #[cfg(feature = "ssr")]
+    {
+        tokio::time::sleep(duration).await;
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        gloo::timers::future::TimeoutFuture::new(duration.as_millis() as u32).await;
+    }
+}
+
+impl HttpClient {
+    pub fn new(config: HttpConfig) -> Self {
+        #[cfg(feature = "ssr")]
+        let client = Client::builder()
+            .timeout(config.timeout)
+            .connect_timeout(config.timeout)
+            .build()
+            .expect("failed to build http client");
+        #[cfg(not(feature = "ssr"))]
+        let client = Client::new();
+
+        Self { client, config }
+    }
+
+    /// The underlying client, for requests that can't be retried
+    /// prefer [HttpClient::send] to get consistent error handling
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Send a request, non-success statuses are mapped to [HttpError::Status]
+    /// the request is never retried
+    pub async fn send(&self, req: RequestBuilder) -> Result<Response, HttpError> {
+        let res = req.send().await?;
+        check_status(res)
+    }
+
+    async fn with_retries<F, Fut>(&self, mut attempt: F) -> Result<Response, HttpError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Response, HttpError>>,
+    {
+        let mut backoff = self.config.retry_backoff;
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Err(e) if e.is_transient() && retries < self.config.max_retries => {
+                    log::debug!("retrying http request after error: {e}");
+                    sleep(backoff).await;
+                    backoff *= 2;
+                    retries += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn idempotent(&self, method: Method, url: &str) -> Result<Response, HttpError> {
+        let url = Url::parse(url).map_err(|_| HttpError::InvalidUrl(url.to_string()))?;
+        self.with_retries(|| self.send(self.client.request(method.clone(), url.clone())))
+            .await
+    }
+
+    /// GET request, retried on transient failures
+    pub async fn get(&self, url: &str) -> Result<Response, HttpError> {
+        self.idempotent(Method::GET, url).await
+    }
+
+    /// HEAD request, retried on transient failures
+    pub async fn head(&self, url: &str) -> Result<Response, HttpError> {
+        self.idempotent(Method::HEAD, url).await
+    }
+
+    /// GET request with a JSON response, retried on transient failures
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, HttpError> {
+        Ok(self.get(url).await?.json().await?)
+    }
+}
+
+fn check_status(res: Response) -> Result<Response, HttpError> {
+    let status = res.status();
+    if status.is_success() {
+        return Ok(res);
+    }
+    Err(HttpError::Status {
+        status,
+        url: res.url().clone(),
+    })
+}
+
+static HTTP_CLIENT: Lazy<HttpClient> = Lazy::new(HttpClient::default);
+
+/// The shared client for external services
+pub fn http_client() -> &'static HttpClient {
+    &HTTP_CLIENT
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use web_time::Duration;
+
+    use super::{HttpClient, HttpConfig, HttpError};
+
+    /// Response of the mock server, `None` never responds
+    type Reply = Option<(u16, &'static str)>;
+
+    /// Local server answering requests with the given replies in order
+    struct MockServer {
+        url: String,
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        async fn start(replies: impl IntoIterator<Item = Reply>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            let hits = Arc::new(AtomicUsize::new(0));
+            let replies = Arc::new(Mutex::new(replies.into_iter().collect::<VecDeque<_>>()));
+
+            let server_hits = hits.clone();
+            tokio::spawn(async move {
+                loop {
+                    let Ok((mut socket, _)) = listener.accept().await else {
+                        return;
+                    };
+                    let hits = server_hits.clone();
+                    let replies = replies.clone();
+                    tokio::spawn(async move {
+                        let mut req = Vec::new();
+                        let mut buf = [0; 1024];
+                        while !req.windows(4).any(|w| w == b"\r\n\r\n") {
+                            match socket.read(&mut buf).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => req.extend_from_slice(&buf[..n]),
+                            }
+                        }
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        let reply = replies.lock().unwrap().pop_front().flatten();
+                        let Some((status, body)) = reply else {
+                            // Hold the connection open without responding
+                            std::future::pending::<()>().await;
+                            return;
+                        };
+                        let res = format!(
+                            "HTTP/1.1 {status} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        );
+                        _ = socket.write_all(res.as_bytes()).await;
+                    });
+                }
+            });
+
+            Self { url, hits }
+        }
+
+        fn hits(&self) -> usize {
+            self.hits.load(Ordering::SeqCst)
+        }
+    }
+
+    fn client(max_retries: u32) -> HttpClient {
+        HttpClient::new(HttpConfig {
+            timeout: Duration::from_millis(200),
+            max_retries,
+            retry_backoff: Duration::from_millis(1),
+        })
+    }
+
+    fn status_of(e: HttpError) -> u16 {
+        match e {
+            HttpError::Status { status, .. } => status.as_u16(),
+            e => panic!("expected a status error, got {e}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn transient_statuses_are_retried() {
+        let server = MockServer::start([Some((503, "")), Some((429, "")), Some((200, ""))]).await;
+        let res = client(2).get(&server.url).await.unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_are_bounded() {
+        let server = MockServer::start([Some((500, "")); 4]).await;
+        let err = client(2).get(&server.url).await.unwrap_err();
+        assert_eq!(status_of(err), 500);
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let server = MockServer::start([Some((404, "")), Some((200, ""))]).await;
+        let err = client(2).head(&server.url).await.unwrap_err();
+        assert_eq!(status_of(err), 404);
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn timeouts_are_retried() {
+        let server = MockServer::start([None, Some((200, r#"{"ok":true}"#))]).await;
+        let res: serde_json::Value = client(1).get_json(&server.url).await.unwrap();
+        assert_eq!(res["ok"], true);
+        assert_eq!(server.hits(), 2);
+
+        let server = MockServer::start([None, None]).await;
+        let err = client(1).get(&server.url).await.unwrap_err();
+        assert!(
+            matches!(&err, HttpError::Request(e) if e.is_timeout()),
+            "{err}"
+        );
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[tokio::test]
+    async fn send_is_never_retried() {
+        let server = MockServer::start([Some((503, "")), Some((200, ""))]).await;
+        let client = client(2);
+        let err = client
+            .send(client.client().post(&server.url))
+            .await
+            .unwrap_err();
+        assert_eq!(status_of(err), 503);
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn invalid_urls_are_rejected() {
+        let err = client(2).get("not a url").await.unwrap_err();
+        assert!(matches!(err, HttpError::InvalidUrl(_)), "{err}");
+    }
+}
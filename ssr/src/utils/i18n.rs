@@ -0,0 +1,304 @@
+//! Lightweight localization of UI strings
+//! strings are looked up by key in the active language, falling back to english
+//! and then to the key itself
+//!
+//! The active language is resolved from `Accept-Language` on the server and rendered as
+//! the document's `lang`, which the client hydrates from so both render the same strings
+//! `UserMetadata` can't hold a preference yet
+use std::collections::HashMap;
+
+use leptos::*;
+use once_cell::sync::Lazy;
+
+type Strings = Lazy<HashMap<&'static str, &'static str>>;
+
+static EN: Strings = Lazy::new(|| {
+    HashMap::from([
+        ("wallet.welcome", "Welcome!"),
+        ("wallet.balance", "Your Coyns Balance"),
+        ("wallet.recent_txns", "Recent Transactions"),
+        ("wallet.see_all", "See All"),
+        ("wallet.refresh", "Refresh"),
+        ("wallet.pull_to_refresh", "Pull to refresh"),
+        ("wallet.release_to_refresh", "Release to refresh"),
+        ("wallet.tap_to_retry", "Tap to retry"),
+        ("wallet.principal_copied", "Your principal was copied"),
+        ("wallet.connect_title", "Connect to view your wallet"),
+        (
+            "wallet.connect_subtitle",
+            "Login to see your COYNs balance and transactions",
+        ),
+        ("wallet.connect_cta", "Login to claim your COYNs"),
+        ("wallet.no_txns", "No transactions yet"),
+        ("wallet.no_txns_of_type", "No transactions of this type"),
+        ("wallet.txns_failed", "Failed to load transactions"),
+        ("wallet.end_of_history", "End of history"),
+        ("txn.direction.minted", "Minted"),
+        ("txn.direction.received", "Received"),
+        ("txn.direction.sent", "Sent"),
+        ("txn.tag.bet_placed", "Vote Placement"),
+        ("txn.tag.signup_bonus", "Joining Bonus"),
+        ("txn.tag.referral", "Referral Reward"),
+        ("txn.tag.winnings", "Vote Winnings"),
+        ("txn.tag.commission", "Vote Commission"),
+        ("txn.tag.transfer", "Transfer"),
+        ("txn.tag.hon_transfer", "HotorNot Account Transfer"),
+        ("txn.filter.all", "All"),
+        ("txn.filter.sent", "Sent"),
+        ("txn.filter.received", "Received"),
+        ("txn.filter.rewards", "Rewards"),
+        ("txn.filter.mints", "Mints"),
+        ("txn.period.week", "7 days"),
+        ("txn.period.month", "30 days"),
+        ("txn.period.all", "All time"),
+        ("txn.today", "Today"),
+        ("txn.yesterday", "Yesterday"),
+        ("txn.amount", "Amount"),
+        ("txn.token", "Token"),
+        ("txn.direction", "Direction"),
+        ("txn.time", "Time"),
+        ("txn.counterparty", "Counterparty"),
+        ("txn.view_post", "View post"),
+        ("txn.copy_link", "Copy link"),
+        ("txn.details", "Details"),
+        ("txn.copy", "Copy"),
+        ("txn.link_copied", "Link copied"),
+        ("txn.link_copy_failed", "Couldn't copy the link"),
+        ("txn.counterparty_copied", "Counterparty copied"),
+        (
+            "txn.counterparty_copy_failed",
+            "Couldn't copy the counterparty",
+        ),
+        ("overlay.mute", "Mute"),
+        ("overlay.unmute", "Unmute"),
+        ("overlay.share", "Share"),
+        ("overlay.show_qr", "Show QR"),
+        ("overlay.hide_qr", "Hide QR"),
+        ("overlay.link_copied", "Link copied"),
+        ("overlay.link_copy_failed", "Couldn't copy the link"),
+        ("overlay.principal_copied", "Principal copied"),
+        ("overlay.report_post", "Report Post"),
+        ("overlay.report_reason", "Please select a reason:"),
+        ("overlay.report_submit", "Submit"),
+        ("overlay.report_submitting", "Submitting..."),
+        ("overlay.report_thanks", "Thanks, we will review this post"),
+    ])
+});
+
+static HI: Strings = Lazy::new(|| {
+    HashMap::from([
+        ("wallet.welcome", "स्वागत है!"),
+        ("wallet.balance", "आपका कॉइन बैलेंस"),
+        ("wallet.recent_txns", "हाल के लेन-देन"),
+        ("wallet.see_all", "सभी देखें"),
+        ("wallet.refresh", "रीफ़्रेश करें"),
+        ("wallet.pull_to_refresh", "रीफ़्रेश करने के लिए खींचें"),
+        ("wallet.release_to_refresh", "रीफ़्रेश करने के लिए छोड़ें"),
+        ("wallet.tap_to_retry", "फिर से कोशिश करने के लिए टैप करें"),
+        ("wallet.principal_copied", "आपका प्रिंसिपल कॉपी हो गया"),
+        ("wallet.connect_title", "अपना वॉलेट देखने के लिए कनेक्ट करें"),
+        (
+            "wallet.connect_subtitle",
+            "अपना COYNs बैलेंस और लेन-देन देखने के लिए लॉगिन करें",
+        ),
+        ("wallet.connect_cta", "अपने COYNs पाने के लिए लॉगिन करें"),
+        ("wallet.no_txns", "अभी तक कोई लेन-देन नहीं"),
+        ("wallet.no_txns_of_type", "इस प्रकार का कोई लेन-देन नहीं"),
+        ("wallet.txns_failed", "लेन-देन लोड नहीं हो सके"),
+        ("wallet.end_of_history", "इतिहास का अंत"),
+        ("txn.direction.minted", "मिंट किए गए"),
+        ("txn.direction.received", "प्राप्त"),
+        ("txn.direction.sent", "भेजे गए"),
+        ("txn.tag.bet_placed", "वोट लगाया"),
+        ("txn.tag.signup_bonus", "जॉइनिंग बोनस"),
+        ("txn.tag.referral", "रेफ़रल इनाम"),
+        ("txn.tag.winnings", "वोट की जीत"),
+        ("txn.tag.commission", "वोट कमीशन"),
+        ("txn.tag.transfer", "ट्रांसफ़र"),
+        ("txn.tag.hon_transfer", "HotorNot खाता ट्रांसफ़र"),
+        ("txn.filter.all", "सभी"),
+        ("txn.filter.sent", "भेजे गए"),
+        ("txn.filter.received", "प्राप्त"),
+        ("txn.filter.rewards", "इनाम"),
+        ("txn.filter.mints", "मिंट"),
+        ("txn.period.week", "7 दिन"),
+        ("txn.period.month", "30 दिन"),
+        ("txn.period.all", "अब तक"),
+        ("txn.today", "आज"),
+        ("txn.yesterday", "कल"),
+        ("txn.amount", "राशि"),
+        ("txn.token", "टोकन"),
+        ("txn.direction", "दिशा"),
+        ("txn.time", "समय"),
+        ("txn.counterparty", "दूसरा पक्ष"),
+        ("txn.view_post", "पोस्ट देखें"),
+        ("txn.copy_link", "लिंक कॉपी करें"),
+        ("txn.details", "विवरण"),
+        ("txn.copy", "कॉपी करें"),
+        ("txn.link_copied", "लिंक कॉपी हो गया"),
+        ("txn.link_copy_failed", "लिंक कॉपी नहीं हो सका"),
+        ("txn.counterparty_copied", "दूसरा पक्ष कॉपी हो गया"),
+        ("txn.counterparty_copy_failed", "दूसरा पक्ष कॉपी नहीं हो सका"),
+        ("overlay.mute", "म्यूट करें"),
+        ("overlay.unmute", "अनम्यूट करें"),
+        ("overlay.share", "शेयर करें"),
+        ("overlay.show_qr", "QR दिखाएं"),
+        ("overlay.hide_qr", "QR छिपाएं"),
+        ("overlay.link_copied", "लिंक कॉपी हो गया"),
+        ("overlay.link_copy_failed", "लिंक कॉपी नहीं हो सका"),
+        ("overlay.principal_copied", "प्रिंसिपल कॉपी हो गया"),
+        ("overlay.report_post", "पोस्ट रिपोर्ट करें"),
+        ("overlay.report_reason", "कृपया एक कारण चुनें:"),
+        ("overlay.report_submit", "सबमिट करें"),
+        ("overlay.report_submitting", "सबमिट हो रहा है..."),
+        ("overlay.report_thanks", "धन्यवाद, हम इस पोस्ट की समीक्षा करेंगे"),
+    ])
+});
+
+/// Supported languages, provided in context
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Hi,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Hi];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Hi => "hi",
+        }
+    }
+
+    fn strings(self) -> &'static Strings {
+        match self {
+            Lang::En => &EN,
+            Lang::Hi => &HI,
+        }
+    }
+
+    /// Supported language of a language tag (e.g "hi-IN")
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+
+    /// Most preferred supported language of an `Accept-Language` header
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut langs: Vec<_> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let lang = Self::from_tag(parts.next()?)?;
+                let weight = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                // `q=0` marks a language as not acceptable
+                (weight > 0.0).then_some((weight, lang))
+            })
+            .collect();
+        // Stable, languages with equal weights keep the header's order
+        langs.sort_by(|a, b| b.0.total_cmp(&a.0));
+        langs.first().map(|(_, lang)| *lang)
+    }
+
+    /// Language of the current request (server)
+    /// or the language the server rendered the document in (client)
+    pub fn detect() -> Self {
+        #[cfg(feature = "ssr")]
+        {
+            use axum::http::{header::ACCEPT_LANGUAGE, request::Parts};
+
+            use_context::<Parts>()
+                .and_then(|parts| {
+                    let header = parts.headers.get(ACCEPT_LANGUAGE)?.to_str().ok()?;
+                    Self::from_accept_language(header)
+                })
+                .unwrap_or_default()
+        }
+        #[cfg(not(feature = "ssr"))]
+        {
+            document()
+                .document_element()
+                .and_then(|html| html.get_attribute("lang"))
+                .as_deref()
+                .and_then(Self::from_tag)
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Localized string for `key` in the active language
+pub fn t(key: &'static str) -> &'static str {
+    let lang = use_context::<Lang>().unwrap_or_default();
+    lang.strings()
+        .get(key)
+        .or_else(|| EN.get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lang, EN, HI};
+
+    #[test]
+    fn tags_match_primary_subtag() {
+        assert_eq!(Lang::from_tag("hi-IN"), Some(Lang::Hi));
+        assert_eq!(Lang::from_tag(" EN_us "), Some(Lang::En));
+        assert_eq!(Lang::from_tag("fr"), None);
+        assert_eq!(Lang::from_tag(""), None);
+    }
+
+    #[test]
+    fn accept_language_ranks_by_weight() {
+        assert_eq!(
+            Lang::from_accept_language("en;q=0.5, hi-IN;q=0.9"),
+            Some(Lang::Hi)
+        );
+        assert_eq!(
+            Lang::from_accept_language("fr-FR, fr;q=0.9, en;q=0.8, hi;q=0.7"),
+            Some(Lang::En)
+        );
+    }
+
+    #[test]
+    fn accept_language_ties_keep_header_order() {
+        assert_eq!(Lang::from_accept_language("hi, en"), Some(Lang::Hi));
+        assert_eq!(
+            Lang::from_accept_language("en;q=0.8, hi;q=0.8"),
+            Some(Lang::En)
+        );
+    }
+
+    #[test]
+    fn accept_language_skips_unacceptable_and_unsupported() {
+        assert_eq!(
+            Lang::from_accept_language("hi;q=0, en;q=0.1"),
+            Some(Lang::En)
+        );
+        assert_eq!(Lang::from_accept_language("de, fr;q=0.5"), None);
+        assert_eq!(Lang::from_accept_language("*"), None);
+        assert_eq!(Lang::from_accept_language(""), None);
+    }
+
+    #[test]
+    fn accept_language_ignores_malformed_weights() {
+        assert_eq!(
+            Lang::from_accept_language("hi;q=abc, en;q=0.5"),
+            Some(Lang::Hi)
+        );
+    }
+
+    #[test]
+    fn every_string_is_translated() {
+        let mut missing: Vec<_> = EN.keys().filter(|key| !HI.contains_key(*key)).collect();
+        missing.sort();
+        assert!(missing.is_empty(), "missing hindi strings: {missing:?}");
+        assert_eq!(EN.len(), HI.len(), "hindi strings without an english key");
+    }
+}
@@ -1,23 +1,34 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use ic_agent::{agent::AgentBuilder, Agent, AgentError, Identity};
+use ic_agent::{
+    agent::{http_transport::ReqwestTransport, AgentBuilder, RejectCode},
+    Agent, AgentError, Identity,
+};
+use thiserror::Error;
 
-use crate::consts::AGENT_URL;
+use crate::{consts::AGENT_URL, state::dev_mode::DevMode};
+
+use super::{
+    call_limit::{limit_call, CallPriority, LimitedTransport},
+    support::record_error,
+};
 
 #[derive(Clone)]
 pub struct AgentWrapper(Agent);
 
 impl AgentWrapper {
     pub fn build(builder_func: impl FnOnce(AgentBuilder) -> AgentBuilder) -> Self {
-        let mut builder = Agent::builder().with_url(AGENT_URL);
+        let url = DevMode::get().map_or(AGENT_URL, |dev| dev.agent_url.as_str());
+        let transport = ReqwestTransport::create(url).expect("invalid agent url");
+        let mut builder = Agent::builder().with_transport(LimitedTransport(transport));
         builder = builder_func(builder);
         Self(builder.build().unwrap())
     }
 
     pub async fn get_agent(&self) -> Result<&Agent, AgentError> {
         let agent = &self.0;
-        #[cfg(any(feature = "local-bin", feature = "local-lib"))]
-        {
+        // Local replicas aren't signed by the mainnet root key
+        if cfg!(any(feature = "local-bin", feature = "local-lib")) || DevMode::get().is_some() {
             agent.fetch_root_key().await?;
         }
         Ok(agent)
@@ -27,3 +38,54 @@ impl AgentWrapper {
         self.0.set_arc_identity(id);
     }
 }
+
+/// Failure of a canister call
+#[derive(Debug, Clone, Error)]
+pub enum CallError {
+    /// The call was rejected by the replica or never reached it
+    /// (network issues, overloaded subnet), retrying later may succeed
+    #[error("replica error: {0}")]
+    Replica(String),
+    /// The canister rejected the call or trapped
+    #[error("canister error: {0}")]
+    Canister(String),
+}
+
+impl From<AgentError> for CallError {
+    fn from(e: AgentError) -> Self {
+        let reject = match &e {
+            AgentError::CertifiedReject(reject) | AgentError::UncertifiedReject(reject) => reject,
+            _ => return Self::Replica(e.to_string()),
+        };
+        match reject.reject_code {
+            RejectCode::CanisterReject
+            | RejectCode::CanisterError
+            | RejectCode::DestinationInvalid => Self::Canister(reject.reject_message.clone()),
+            RejectCode::SysFatal | RejectCode::SysTransient => Self::Replica(e.to_string()),
+        }
+    }
+}
+
+impl CallError {
+    /// Message to show to the user
+    /// `canister_message` describes a canister failure in the context of the call
+    pub fn user_message(&self, canister_message: &'static str) -> &'static str {
+        match self {
+            Self::Replica(_) => "Something went wrong, please try again later",
+            Self::Canister(_) => canister_message,
+        }
+    }
+}
+
+/// Make a canister call with the given priority within the global call limit
+/// failures are classified into [CallError]
+pub async fn canister_call<T>(
+    priority: CallPriority,
+    call: impl Future<Output = Result<T, AgentError>>,
+) -> Result<T, CallError> {
+    limit_call(priority, call).await.map_err(|e| {
+        let e = CallError::from(e);
+        record_error(&e);
+        e
+    })
+}
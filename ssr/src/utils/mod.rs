@@ -2,16 +2,24 @@ use serde::{Deserialize, Serialize};
 use web_time::{Duration, SystemTime};
 
 pub mod ab_testing;
+pub mod call_limit;
 pub mod event_streaming;
+pub mod health;
+pub mod http;
+pub mod i18n;
 pub mod ic;
 pub mod icon;
 pub mod ml_feed;
 pub mod notifications;
+pub mod platform_stats;
 pub mod posts;
+pub mod preview;
 pub mod profile;
 pub mod report;
 pub mod route;
+pub mod support;
 pub mod timestamp;
+pub mod token;
 pub mod types;
 pub mod user;
 pub mod web;
@@ -22,6 +30,25 @@ pub fn current_epoch() -> Duration {
         .unwrap()
 }
 
+/// Compact representation of a count, e.g `1.2M`, `34.5K`
+/// counts below 1000 are returned as is
+/// truncated instead of rounded, so 999_999 is `999.9K` rather than `1000K`
+pub fn format_count(n: u64) -> String {
+    let (unit, suffix) = match n {
+        0..=999 => return n.to_string(),
+        1_000..=999_999 => (1_000, "K"),
+        1_000_000..=999_999_999 => (1_000_000, "M"),
+        _ => (1_000_000_000, "B"),
+    };
+    let whole = n / unit;
+    let tenths = (n % unit) * 10 / unit;
+    if tenths == 0 {
+        format!("{whole}{suffix}")
+    } else {
+        format!("{whole}.{tenths}{suffix}")
+    }
+}
+
 /// Wrapper for PartialEq that always returns false
 /// this is currently only used for resources
 /// this does not provide a sane implementation of PartialEq
@@ -39,3 +66,31 @@ impl<T> PartialEq for MockPartialEq<T> {
 pub mod off_chain {
     tonic::include_proto!("off_chain");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::format_count;
+
+    #[test]
+    fn small_counts_are_unchanged() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+    }
+
+    #[test]
+    fn counts_switch_units_at_boundaries() {
+        assert_eq!(format_count(1_000), "1K");
+        assert_eq!(format_count(999_999), "999.9K");
+        assert_eq!(format_count(1_000_000), "1M");
+        assert_eq!(format_count(1_000_000_000), "1B");
+    }
+
+    #[test]
+    fn counts_are_truncated_to_tenths() {
+        assert_eq!(format_count(1_050), "1K");
+        assert_eq!(format_count(1_099), "1K");
+        assert_eq!(format_count(1_100), "1.1K");
+        assert_eq!(format_count(1_999), "1.9K");
+        assert_eq!(format_count(12_345_678), "12.3M");
+    }
+}
@@ -0,0 +1,107 @@
+use leptos::{server, ServerFnError};
+use serde::{Deserialize, Serialize};
+
+/// Aggregate platform stats for public pages
+///
+/// Total likes are not included, canisters don't aggregate them
+/// and summing them per post is too expensive
+#[derive(Clone, Copy, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PlatformStats {
+    /// Users with an individual canister, across all subnets
+    pub total_users: u64,
+    /// Posts indexed in the home feed
+    pub total_videos: u64,
+}
+
+#[cfg(feature = "ssr")]
+pub mod cache {
+    use std::sync::RwLock;
+
+    use once_cell::sync::Lazy;
+
+    use super::PlatformStats;
+    use crate::{
+        canister::post_cache::{self, TopPostsFetchError},
+        consts::PLATFORM_STATS_REFRESH_INTERVAL,
+        state::canisters::Canisters,
+    };
+
+    /// Page size used while counting posts in the post cache
+    const POST_COUNT_PAGE_SIZE: u64 = 100;
+
+    /// Last known stats, zero until the first refresh completes
+    static PLATFORM_STATS: Lazy<RwLock<PlatformStats>> = Lazy::new(Default::default);
+
+    pub fn cached_platform_stats() -> PlatformStats {
+        *PLATFORM_STATS.read().unwrap()
+    }
+
+    async fn total_users(canisters: &Canisters<false>) -> Result<u64, ic_agent::AgentError> {
+        let mut total = 0u64;
+        for subnet in canisters.subnet_indexes().await? {
+            let user_index = canisters.user_index_with(subnet).await?;
+            total = total.saturating_add(user_index.get_user_index_canister_count().await?);
+        }
+        Ok(total)
+    }
+
+    async fn total_videos(canisters: &Canisters<false>) -> Result<u64, ic_agent::AgentError> {
+        let post_cache = canisters.post_cache().await?;
+        let mut total = 0u64;
+        loop {
+            let res = post_cache
+                .get_top_posts_aggregated_from_canisters_on_this_network_for_home_feed_cursor(
+                    total,
+                    POST_COUNT_PAGE_SIZE,
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+            let page = match res {
+                post_cache::Result_::Ok(page) => page.len() as u64,
+                post_cache::Result_::Err(TopPostsFetchError::ReachedEndOfItemsList) => 0,
+                post_cache::Result_::Err(e) => {
+                    log::warn!("failed to count posts: {e:?}");
+                    0
+                }
+            };
+            total += page;
+            if page < POST_COUNT_PAGE_SIZE {
+                return Ok(total);
+            }
+        }
+    }
+
+    async fn refresh(canisters: &Canisters<false>) {
+        let mut stats = cached_platform_stats();
+        match total_users(canisters).await {
+            Ok(users) => stats.total_users = users,
+            Err(e) => log::warn!("failed to refresh total users: {e}"),
+        }
+        match total_videos(canisters).await {
+            Ok(videos) => stats.total_videos = videos,
+            Err(e) => log::warn!("failed to refresh total videos: {e}"),
+        }
+        *PLATFORM_STATS.write().unwrap() = stats;
+    }
+
+    /// Periodically refresh the platform stats in the background
+    /// failed refreshes keep the last known values
+    pub fn spawn_refresher(canisters: Canisters<false>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PLATFORM_STATS_REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                refresh(&canisters).await;
+            }
+        });
+    }
+}
+
+/// Cached aggregate platform stats
+/// zeros are returned until the stats are fetched for the first time
+#[server]
+pub async fn get_platform_stats() -> Result<PlatformStats, ServerFnError> {
+    Ok(cache::cached_platform_stats())
+}
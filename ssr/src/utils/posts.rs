@@ -1,12 +1,25 @@
 use candid::Principal;
+use leptos::{server, ServerFnError};
 use serde::{Deserialize, Serialize};
 use web_time::Duration;
 
+#[cfg(feature = "ssr")]
+use crate::state::canisters::unauth_canisters;
 use crate::{
-    canister::individual_user_template::PostDetailsForFrontend, state::canisters::Canisters,
+    canister::{
+        individual_user_template::PostDetailsForFrontend,
+        utils::{normalize_stream_uid, stream_url},
+    },
+    consts::{CANONICAL_HOST, FEED_PAGE_SIZE},
+    state::{canisters::Canisters, cloudflare::StreamVariant},
 };
 
-use super::{profile::propic_from_principal, types::PostStatus};
+use super::{
+    call_limit::{limit_call, CallPriority},
+    http::http_client,
+    profile::propic_from_principal,
+    types::PostStatus,
+};
 
 use ic_agent::AgentError;
 use thiserror::Error;
@@ -18,7 +31,7 @@ pub enum PostViewError {
     #[error("Canister error {0}")]
     Canister(String),
     #[error("http fetch error {0}")]
-    HttpFetch(#[from] reqwest::Error),
+    HttpFetch(#[from] super::http::HttpError),
     #[error("ml feed error {0}")]
     MLFeedError(String),
 }
@@ -41,7 +54,7 @@ impl Default for FetchCursor {
 impl FetchCursor {
     pub fn advance(&mut self) {
         self.start += self.limit;
-        self.limit = 25;
+        self.limit = FEED_PAGE_SIZE;
     }
 
     pub fn set_limit(&mut self, limit: u64) {
@@ -75,15 +88,23 @@ pub struct PostDetails {
 }
 
 impl PostDetails {
+    /// `None` if the post's video can't be played, i.e its stream UID is invalid
     pub fn from_canister_post(
         authenticated: bool,
         canister_id: Principal,
         details: PostDetailsForFrontend,
-    ) -> Self {
-        Self {
+    ) -> Option<Self> {
+        let uid = match normalize_stream_uid(&details.video_uid) {
+            Ok(uid) => uid,
+            Err(e) => {
+                log::warn!("post {canister_id} {} has {e}, skipping", details.id);
+                return None;
+            }
+        };
+        Some(Self {
             canister_id,
             post_id: details.id,
-            uid: details.video_uid,
+            uid,
             description: details.description,
             views: details.total_view_count,
             likes: details.like_count,
@@ -103,7 +124,7 @@ impl PostDetails {
                 details.created_at.secs_since_epoch,
                 details.created_at.nanos_since_epoch,
             ),
-        }
+        })
     }
 
     pub fn is_hot_or_not(&self) -> bool {
@@ -117,9 +138,11 @@ pub async fn get_post_uid<const AUTH: bool>(
     post_id: u64,
 ) -> Result<Option<PostDetails>, PostViewError> {
     let post_creator_can = canisters.individual_user(user_canister).await?;
-    let post_details = match post_creator_can
-        .get_individual_post_details_by_id(post_id)
-        .await
+    let post_details = match limit_call(
+        CallPriority::Background,
+        post_creator_can.get_individual_post_details_by_id(post_id),
+    )
+    .await
     {
         Ok(p) => p,
         Err(e) => {
@@ -138,21 +161,39 @@ pub async fn get_post_uid<const AUTH: bool>(
         return Ok(None);
     }
 
-    let post_uuid = &post_details.video_uid;
-    let req_url = format!(
-        "https://customer-2p3jflss4r4hmpnz.cloudflarestream.com/{}/manifest/video.m3u8",
-        post_uuid,
-    );
-    let res = reqwest::Client::default().head(req_url).send().await;
-    if res.is_err() || (res.is_ok() && res.unwrap().status() != 200) {
+    let req_url = match stream_url(&post_details.video_uid, StreamVariant::Watermarked) {
+        Ok(url) => url,
+        Err(e) => {
+            log::warn!(
+                "post {} {} has {e}, skipping",
+                user_canister.to_string(),
+                post_id
+            );
+            return Ok(None);
+        }
+    };
+    if let Err(e) = http_client().head(&req_url).await {
+        log::warn!("video for post {post_id} is unavailable: {e}");
         return Ok(None);
     }
 
-    Ok(Some(PostDetails::from_canister_post(
+    Ok(PostDetails::from_canister_post(
         AUTH,
         user_canister,
         post_details,
-    )))
+    ))
+}
+
+/// Details of a single post, for opening shared links cold
+/// `None` if the post was deleted, banned or doesn't exist
+/// Like status depends on the caller and is not included
+#[server]
+pub async fn get_post_details(
+    canister_id: Principal,
+    post_id: u64,
+) -> Result<Option<PostDetails>, ServerFnError> {
+    let canisters = unauth_canisters();
+    Ok(get_post_uid(&canisters, canister_id, post_id).await?)
 }
 
 pub fn get_feed_component_identifier() -> impl Fn() -> Option<&'static str> {
@@ -175,17 +216,232 @@ pub fn get_host() -> String {
     #[cfg(feature = "hydrate")]
     {
         use leptos::window;
-        window().location().host().unwrap().to_string()
+        window()
+            .location()
+            .host()
+            .unwrap_or_else(|_| CANONICAL_HOST.to_string())
     }
 
     #[cfg(not(feature = "hydrate"))]
     {
         use axum::http::request::Parts;
-        use http::header::HeaderMap;
-        use leptos::expect_context;
+        use leptos::use_context;
+
+        // Not rendering a request (e.g. background tasks)
+        let Some(parts) = use_context::<Parts>() else {
+            return CANONICAL_HOST.to_string();
+        };
+        parts
+            .headers
+            .get("Host")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or(CANONICAL_HOST)
+            .to_string()
+    }
+}
+
+/// Origin (scheme + host) of the app, for building absolute links
+/// works during SSR as well, where `window` is not available
+pub fn get_base_url() -> String {
+    #[cfg(feature = "hydrate")]
+    {
+        use leptos::window;
+        window()
+            .location()
+            .origin()
+            .unwrap_or_else(|_| format!("https://{CANONICAL_HOST}"))
+    }
+
+    #[cfg(not(feature = "hydrate"))]
+    {
+        use axum::http::request::Parts;
+        use leptos::use_context;
+
+        let host = get_host();
+        let forwarded_proto = use_context::<Parts>().and_then(|parts| {
+            let proto = parts.headers.get("X-Forwarded-Proto")?.to_str().ok()?;
+            Some(proto.to_string())
+        });
+        let proto = forwarded_proto.unwrap_or_else(|| {
+            if host.starts_with("localhost") || host.starts_with("127.0.0.1") {
+                "http".into()
+            } else {
+                "https".into()
+            }
+        });
+        format!("{proto}://{host}")
+    }
+}
+
+/// Stable cursor into a user's posts, see [get_user_posts]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct UserPostsCursor {
+    /// Approximate offset into the canister's listing
+    offset: u64,
+    /// Only posts older than this are returned
+    before_post_id: u64,
+}
+
+impl UserPostsCursor {
+    /// Position of the next page in the canister's listing
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+/// Maximum number of posts returned by [get_user_posts] in one call
+pub const USER_POSTS_MAX_COUNT: u64 = 50;
+
+/// Offsets shift when posts are deleted between pages
+/// refetch this many posts before the cursor's offset to avoid skipping any
+#[cfg(feature = "ssr")]
+const USER_POSTS_OVERLAP: u64 = 10;
+
+/// List the posts of `principal`, newest first
+/// pass the returned cursor to fetch the next page, `None` once the list ends
+///
+/// Posts are ordered by their (monotonic) id, new uploads and deletions
+/// in between pages don't cause duplicates or gaps
+/// Deleted and banned posts are omitted
+#[server]
+pub async fn get_user_posts(
+    principal: Principal,
+    cursor: Option<UserPostsCursor>,
+    count: u64,
+) -> Result<(Vec<PostDetails>, Option<UserPostsCursor>), ServerFnError> {
+    use crate::canister::individual_user_template::{GetPostsOfUserProfileError, Result5};
+
+    let count = count.min(USER_POSTS_MAX_COUNT) as usize;
+    let canisters = unauth_canisters();
+    let Some(user_canister) = canisters
+        .get_individual_canister_by_user_principal(principal)
+        .await?
+    else {
+        return Err(ServerFnError::new("User not found"));
+    };
+    let user = canisters.individual_user(user_canister).await?;
+
+    let mut offset = cursor.map_or(0, |c| c.offset.saturating_sub(USER_POSTS_OVERLAP));
+    let mut before = cursor.map(|c| c.before_post_id);
+    let mut posts = Vec::with_capacity(count);
+    while posts.len() < count {
+        let batch = (count - posts.len()) as u64 + USER_POSTS_OVERLAP;
+        let res = user
+            .get_posts_of_this_user_profile_with_pagination_cursor(offset, batch)
+            .await?;
+        let mut page = match res {
+            Result5::Ok(page) => page,
+            Result5::Err(GetPostsOfUserProfileError::ReachedEndOfItemsList) => vec![],
+            Result5::Err(e) => {
+                return Err(ServerFnError::new(format!("failed to get posts: {e:?}")))
+            }
+        };
+        let fetched = page.len() as u64;
+        page.sort_unstable_by(|a, b| b.id.cmp(&a.id));
+
+        let mut consumed = 0;
+        for post in page {
+            if posts.len() == count {
+                break;
+            }
+            consumed += 1;
+            // Already returned in a previous page
+            if before.is_some_and(|before| post.id >= before) {
+                continue;
+            }
+            before = Some(post.id);
+            if matches!(
+                PostStatus::from(&post.status),
+                PostStatus::Deleted
+                    | PostStatus::BannedDueToUserReporting
+                    | PostStatus::BannedForExplicitness
+            ) {
+                continue;
+            }
+            posts.extend(PostDetails::from_canister_post(false, user_canister, post));
+        }
+        offset += consumed;
+
+        if fetched < batch && consumed == fetched {
+            return Ok((posts, None));
+        }
+    }
+
+    let next = before.map(|before_post_id| UserPostsCursor {
+        offset,
+        before_post_id,
+    });
+    Ok((posts, next))
+}
+
+/// Counters of a single post, see [get_posts_stats]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PostStats {
+    Found {
+        likes: u64,
+        views: u64,
+        /// Like status of the caller
+        /// `None` for anonymous callers (e.g the server)
+        liked_by_me: Option<bool>,
+    },
+    /// The post does not exist, was deleted or banned
+    NotFound,
+}
+
+/// Maximum number of posts accepted by [get_posts_stats] in one call
+pub const POSTS_STATS_MAX_COUNT: usize = 50;
+
+/// Fetch the stats of `posts` concurrently, in the same order
+/// like status is only resolved for authenticated canisters
+pub async fn fetch_posts_stats<const AUTH: bool>(
+    canisters: &Canisters<AUTH>,
+    posts: &[(Principal, u64)],
+) -> Vec<PostStats> {
+    use futures::{stream::FuturesOrdered, StreamExt};
+
+    posts
+        .iter()
+        .map(|&(post_canister, post_id)| async move {
+            let individual = canisters.individual_user(post_canister).await.ok()?;
+            // Traps for missing posts
+            let post = limit_call(
+                CallPriority::Background,
+                individual.get_individual_post_details_by_id(post_id),
+            )
+            .await
+            .ok()?;
+            if matches!(
+                PostStatus::from(&post.status),
+                PostStatus::Deleted
+                    | PostStatus::BannedDueToUserReporting
+                    | PostStatus::BannedForExplicitness
+            ) {
+                return None;
+            }
+            Some(PostStats::Found {
+                likes: post.like_count,
+                views: post.total_view_count,
+                liked_by_me: AUTH.then_some(post.liked_by_me),
+            })
+        })
+        .collect::<FuturesOrdered<_>>()
+        .map(|stats| stats.unwrap_or(PostStats::NotFound))
+        .collect()
+        .await
+}
 
-        let parts: Parts = expect_context();
-        let headers = parts.headers;
-        headers.get("Host").unwrap().to_str().unwrap().to_string()
+/// Bulk like and view counts of `posts`, in the same order
+/// Like status depends on the caller, use [fetch_posts_stats]
+/// with authenticated canisters on the client for it
+#[server]
+pub async fn get_posts_stats(
+    posts: Vec<(Principal, u64)>,
+) -> Result<Vec<PostStats>, ServerFnError> {
+    if posts.len() > POSTS_STATS_MAX_COUNT {
+        return Err(ServerFnError::Args(format!(
+            "at most {POSTS_STATS_MAX_COUNT} posts can be queried at once"
+        )));
     }
+    let canisters = unauth_canisters();
+    Ok(fetch_posts_stats(&canisters, &posts).await)
 }
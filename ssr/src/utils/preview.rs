@@ -0,0 +1,39 @@
+//! Preview mode, renders curated sample data instead of live canister data
+//! for screenshots and demos
+//! Requires both the `?preview=true` query param and `PREVIEW_MODE=1` on the server
+//!
+//! Only the wallet has a preview, the feed, post and profile pages always render live data
+//! Sample posts would need real Cloudflare stream and thumbnail uids to render at all,
+//! so those pages are better captured from a curated account
+use leptos::*;
+use leptos_router::*;
+
+/// Whether the server allows preview mode
+#[server]
+pub async fn preview_mode_allowed() -> Result<bool, ServerFnError> {
+    Ok(std::env::var("PREVIEW_MODE").is_ok_and(|v| v == "1"))
+}
+
+#[derive(Params, PartialEq, Clone)]
+struct PreviewQuery {
+    preview: bool,
+}
+
+/// Resolves to true if the current page should render sample data
+pub fn use_preview_mode() -> Resource<bool, bool> {
+    let query = use_query::<PreviewQuery>();
+    let requested = move || query.with(|q| q.as_ref().is_ok_and(|q| q.preview));
+
+    create_resource(requested, |requested| async move {
+        if !requested {
+            return false;
+        }
+        match preview_mode_allowed().await {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                log::warn!("failed to check preview mode: {e}");
+                false
+            }
+        }
+    })
+}
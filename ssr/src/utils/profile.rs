@@ -2,20 +2,22 @@ use web_time::Duration;
 
 use candid::Principal;
 use ic_agent::AgentError;
-use leptos::{RwSignal, SignalUpdateUntracked};
+use leptos::{store_value, RwSignal, ServerFnError, SignalUpdateUntracked, StoredValue};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     canister::individual_user_template::{
-        BetDirection, BetOutcomeForBetMaker, PlacedBetDetail, Result5,
-        UserProfileDetailsForFrontend,
+        BetDirection, BetOutcomeForBetMaker, PlacedBetDetail, UserProfileDetailsForFrontend,
     },
     component::infinite_scroller::{CursoredDataProvider, KeyedData, PageEntry},
-    consts::{GOBGOB_PROPIC_URL, GOBGOB_TOTAL_COUNT},
+    consts::{DICEBEAR_BASE, GOBGOB_PROPIC_URL, GOBGOB_TOTAL_COUNT},
     state::canisters::Canisters,
 };
 
-use super::{current_epoch, posts::PostDetails};
+use super::{
+    current_epoch,
+    posts::{get_user_posts, PostDetails, UserPostsCursor},
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ProfileDetails {
@@ -72,6 +74,16 @@ impl ProfileDetails {
 
         propic_from_principal(self.principal)
     }
+
+    /// Profile picture, or a generated avatar in the given style
+    pub fn profile_pic_or_generated(&self, style: AvatarStyle) -> String {
+        let propic = self.profile_pic.clone().unwrap_or_default();
+        if !propic.is_empty() {
+            return propic;
+        }
+
+        fallback_propic_url(&avatar_seed(self.principal), style)
+    }
 }
 
 pub fn propic_from_principal(principal: Principal) -> String {
@@ -79,6 +91,39 @@ pub fn propic_from_principal(principal: Principal) -> String {
     format!("{GOBGOB_PROPIC_URL}{}/public", index)
 }
 
+/// Dicebear styles for generated avatars
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AvatarStyle {
+    #[default]
+    BigSmile,
+    Adventurer,
+    Bottts,
+    Identicon,
+    Thumbs,
+}
+
+impl AvatarStyle {
+    fn as_str(self) -> &'static str {
+        match self {
+            AvatarStyle::BigSmile => "big-smile",
+            AvatarStyle::Adventurer => "adventurer",
+            AvatarStyle::Bottts => "bottts",
+            AvatarStyle::Identicon => "identicon",
+            AvatarStyle::Thumbs => "thumbs",
+        }
+    }
+}
+
+/// Seed for generated avatars, stable for a principal
+pub fn avatar_seed(principal: Principal) -> String {
+    format!("{:08x}", crc32fast::hash(principal.as_slice()))
+}
+
+/// Generated avatar for `seed`, the same seed always yields the same avatar
+pub fn fallback_propic_url(seed: &str, style: AvatarStyle) -> String {
+    format!("{DICEBEAR_BASE}/{}/svg?seed={seed}", style.as_str())
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum BetOutcome {
     Won(u64),
@@ -177,24 +222,24 @@ pub const PROFILE_CHUNK_SZ: usize = 10;
 
 #[derive(Clone)]
 pub struct PostsProvider {
-    canisters: Canisters<false>,
     video_queue: RwSignal<Vec<PostDetails>>,
     start_index: RwSignal<usize>,
     user: Principal,
+    /// Cursor of the next page, see [get_user_posts]
+    cursor: StoredValue<Option<UserPostsCursor>>,
 }
 
 impl PostsProvider {
     pub fn new(
-        canisters: Canisters<false>,
         video_queue: RwSignal<Vec<PostDetails>>,
         start_index: RwSignal<usize>,
         user: Principal,
     ) -> Self {
         Self {
-            canisters,
             video_queue,
             start_index,
             user,
+            cursor: store_value(None),
         }
     }
 }
@@ -209,40 +254,32 @@ impl KeyedData for PostDetails {
 
 impl CursoredDataProvider for PostsProvider {
     type Data = PostDetails;
-    type Error = AgentError;
+    type Error = ServerFnError;
 
     async fn get_by_cursor(
         &self,
         start: usize,
         end: usize,
-    ) -> Result<PageEntry<PostDetails>, AgentError> {
-        let user = self.canisters.individual_user(self.user).await?;
-        let limit = end - start;
-        let posts = user
-            .get_posts_of_this_user_profile_with_pagination_cursor(start as u64, limit as u64)
-            .await?;
-        let posts = match posts {
-            Result5::Ok(v) => v,
-            Result5::Err(_) => {
-                log::warn!("failed to get posts");
-                return Ok(PageEntry {
-                    data: vec![],
-                    end: true,
-                });
-            }
+    ) -> Result<PageEntry<PostDetails>, ServerFnError> {
+        // Pages are fetched in order, the first page starts a new listing
+        let cursor = if start == 0 {
+            None
+        } else {
+            self.cursor.get_value()
         };
-        let list_end = posts.len() < (end - start);
-        self.start_index.update_untracked(|c| *c = end);
-        let post_details: Vec<PostDetails> = posts
-            .into_iter()
-            .map(|details| PostDetails::from_canister_post(false, self.user, details))
-            .collect();
+        let (post_details, next) = get_user_posts(self.user, cursor, (end - start) as u64).await?;
+        _ = self.cursor.try_set_value(next);
+        if let Some(next) = next {
+            // The post view continues from the same position of the canister's listing
+            self.start_index
+                .update_untracked(|c| *c = next.offset() as usize);
+        }
         self.video_queue.update_untracked(|vq| {
             vq.extend_from_slice(&post_details);
         });
         Ok(PageEntry {
             data: post_details,
-            end: list_end,
+            end: next.is_none(),
         })
     }
 }
@@ -31,6 +31,7 @@ macro_rules! try_or_redirect_opt {
 }
 
 pub fn failure_redirect<E: Display>(err: E) {
+    super::support::record_error(&err);
     let nav = use_navigate();
     nav(&format!("/error?err={err}"), Default::default());
 }
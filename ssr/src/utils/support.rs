@@ -0,0 +1,105 @@
+//! Support bundle, non-sensitive context attached to bug reports
+//! NEVER add secret keys, JWKs, delegations or cookies here
+use std::{fmt::Display, sync::Mutex};
+
+use circular_buffer::CircularBuffer;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::state::canisters::Canisters;
+
+use super::current_epoch;
+
+/// Number of recent errors kept for the support bundle
+const RECENT_ERRORS_CNT: usize = 20;
+
+#[derive(Serialize, Clone)]
+pub struct RecentError {
+    /// Epoch (in milliseconds) at which the error occured
+    at_ms: u128,
+    message: String,
+}
+
+static RECENT_ERRORS: Lazy<Mutex<CircularBuffer<RECENT_ERRORS_CNT, RecentError>>> =
+    Lazy::new(|| Mutex::new(CircularBuffer::new()));
+
+/// Record an error for the support bundle
+/// no-op on the server, as it is shared by all users
+pub fn record_error(err: impl Display) {
+    #[cfg(feature = "hydrate")]
+    {
+        /// Error messages are truncated to this many characters
+        const MAX_ERROR_LEN: usize = 200;
+
+        let message = err.to_string().chars().take(MAX_ERROR_LEN).collect();
+        let error = RecentError {
+            at_ms: current_epoch().as_millis(),
+            message,
+        };
+        RECENT_ERRORS.lock().unwrap().push_back(error);
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        _ = err;
+    }
+}
+
+fn enabled_features() -> Vec<&'static str> {
+    [
+        ("cloudflare", cfg!(feature = "cloudflare")),
+        ("redis-kv", cfg!(feature = "redis-kv")),
+        ("backend-admin", cfg!(feature = "backend-admin")),
+        ("oauth-hydrate", cfg!(feature = "oauth-hydrate")),
+        ("local-auth", cfg!(feature = "local-auth")),
+        ("local-lib", cfg!(feature = "local-lib")),
+        ("ga4", cfg!(feature = "ga4")),
+        ("dev-tools", cfg!(feature = "dev-tools")),
+        ("mock-wallet-history", cfg!(feature = "mock-wallet-history")),
+        (
+            "mock-referral-history",
+            cfg!(feature = "mock-referral-history"),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+#[derive(Serialize)]
+pub struct SupportBundle {
+    app_version: &'static str,
+    features: Vec<&'static str>,
+    /// Recently visited routes (paths only, query parameters are never included)
+    recent_routes: Vec<String>,
+    principal: Option<String>,
+    user_canister: Option<String>,
+    delegation_expiry_ns: Option<u64>,
+    refresh_token_expiry_ms: Option<u128>,
+    /// Epoch (in milliseconds) at which the bundle was created
+    created_at_ms: u128,
+    recent_errors: Vec<RecentError>,
+}
+
+impl SupportBundle {
+    pub fn new(
+        canisters: Option<&Canisters<true>>,
+        refresh_token_expiry_ms: Option<u128>,
+        recent_routes: Vec<String>,
+    ) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION"),
+            features: enabled_features(),
+            recent_routes,
+            principal: canisters.map(|c| c.user_principal().to_text()),
+            user_canister: canisters.map(|c| c.user_canister().to_text()),
+            delegation_expiry_ns: canisters.map(|c| c.expiry_ns()),
+            refresh_token_expiry_ms,
+            created_at_ms: current_epoch().as_millis(),
+            recent_errors: RECENT_ERRORS.lock().unwrap().iter().cloned().collect(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
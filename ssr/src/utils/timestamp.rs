@@ -24,6 +24,45 @@ pub fn get_day_month(epoch_secs: u64) -> String {
     format!("{:02} {month}", ts.day)
 }
 
+/// Get day, month & year -> DD MMM YYYY format
+pub fn get_day_month_year(epoch_secs: u64) -> String {
+    let ts = uts2ts(epoch_secs as i64);
+    format!("{} {}", get_day_month(epoch_secs), ts.year)
+}
+
+/// Full date and time (UTC) -> DD MMM YYYY, HH:MM:SS format
+pub fn get_date_time(epoch_secs: u64) -> String {
+    let ts = uts2ts(epoch_secs as i64);
+    format!(
+        "{}, {:02}:{:02}:{:02} UTC",
+        get_day_month_year(epoch_secs),
+        ts.hour,
+        ts.minute,
+        ts.second
+    )
+}
+
+/// Offset (in seconds) of the user's local timezone from UTC at `epoch_secs`
+/// always 0 on the server
+pub fn local_utc_offset_secs(epoch_secs: u64) -> i64 {
+    #[cfg(feature = "hydrate")]
+    {
+        let date = js_sys::Date::new(&((epoch_secs as f64) * 1000.0).into());
+        // minutes from local time to UTC
+        -(date.get_timezone_offset() as i64) * 60
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        _ = epoch_secs;
+        0
+    }
+}
+
+/// `epoch_secs` shifted to the user's local timezone
+pub fn to_local_epoch_secs(epoch_secs: u64) -> u64 {
+    epoch_secs.saturating_add_signed(local_utc_offset_secs(epoch_secs))
+}
+
 pub fn to_hh_mm_ss(duration: Duration) -> String {
     let secs = duration.as_secs();
     let hh = secs / 3600;
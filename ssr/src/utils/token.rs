@@ -0,0 +1,120 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::consts::COYNS_DECIMALS;
+
+/// Token amount in its smallest unit along with the token's decimals
+///
+/// Displayed with thousands separators, fractional digits have
+/// their trailing zeros trimmed (`1234500` with 3 decimals is `1,234.5`)
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Balance {
+    raw: u128,
+    decimals: u8,
+}
+
+impl Balance {
+    pub fn new(raw: impl Into<u128>, decimals: u8) -> Self {
+        Self {
+            raw: raw.into(),
+            decimals,
+        }
+    }
+
+    /// Balance of the utility token (COYNs)
+    pub fn coyns(raw: impl Into<u128>) -> Self {
+        Self::new(raw, COYNS_DECIMALS)
+    }
+
+    pub fn raw(&self) -> u128 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// `None` past 38 decimals, where a single unit doesn't fit in a u128
+    /// any raw amount is then less than one unit
+    fn unit(&self) -> Option<u128> {
+        10u128.checked_pow(self.decimals as u32)
+    }
+
+    /// Whole units, fractional part is discarded
+    pub fn whole(&self) -> u128 {
+        self.unit().map_or(0, |unit| self.raw / unit)
+    }
+
+    /// Fractional part in the smallest unit
+    pub fn fraction(&self) -> u128 {
+        self.unit().map_or(self.raw, |unit| self.raw % unit)
+    }
+}
+
+impl Display for Balance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.whole().to_string();
+        let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+        for (i, c) in whole.chars().enumerate() {
+            if i != 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        f.write_str(&grouped)?;
+
+        let fraction = self.fraction();
+        if fraction == 0 {
+            return Ok(());
+        }
+        let fraction = format!("{fraction:0width$}", width = self.decimals as usize);
+        write!(f, ".{}", fraction.trim_end_matches('0'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Balance;
+
+    #[test]
+    fn zero_decimals_are_whole_units() {
+        assert_eq!(Balance::new(0u8, 0).to_string(), "0");
+        assert_eq!(Balance::new(42u8, 0).to_string(), "42");
+        assert_eq!(Balance::new(1_000u16, 0).fraction(), 0);
+    }
+
+    #[test]
+    fn fractions_are_trimmed() {
+        assert_eq!(Balance::new(1_234_500u32, 3).to_string(), "1,234.5");
+        assert_eq!(Balance::new(1_000u16, 3).to_string(), "1");
+        assert_eq!(Balance::new(5u8, 3).to_string(), "0.005");
+        assert_eq!(Balance::new(100_000_001u32, 8).to_string(), "1.00000001");
+    }
+
+    #[test]
+    fn thousands_are_separated() {
+        assert_eq!(Balance::new(999u16, 0).to_string(), "999");
+        assert_eq!(Balance::new(1_000u16, 0).to_string(), "1,000");
+        assert_eq!(Balance::new(123_456u32, 0).to_string(), "123,456");
+        assert_eq!(Balance::new(1_234_567_000u32, 3).to_string(), "1,234,567");
+    }
+
+    #[test]
+    fn large_decimals_do_not_overflow() {
+        let max_unit = Balance::new(10u128.pow(38), 38);
+        assert_eq!(max_unit.to_string(), "1");
+
+        let balance = Balance::new(u128::MAX, 39);
+        assert_eq!(balance.whole(), 0);
+        assert_eq!(balance.fraction(), u128::MAX);
+        assert_eq!(
+            balance.to_string(),
+            "0.340282366920938463463374607431768211455"
+        );
+
+        assert_eq!(Balance::new(1u8, u8::MAX).whole(), 0);
+    }
+}
@@ -1,27 +1,107 @@
 use leptos_use::use_window;
 
+/// Outcome of [share_url]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShareOutcome {
+    Shared,
+    /// User dismissed the share sheet
+    Dismissed,
+    Failed,
+}
+
+impl ShareOutcome {
+    /// Outcome of a rejected share, from the name of the rejection error
+    #[cfg_attr(not(feature = "hydrate"), allow(dead_code))]
+    fn from_rejection(name: Option<&str>) -> Self {
+        match name {
+            Some("AbortError") => Self::Dismissed,
+            _ => Self::Failed,
+        }
+    }
+
+    /// Whether the URL should be shared some other way (e.g copied)
+    pub fn needs_fallback(self) -> bool {
+        self == Self::Failed
+    }
+}
+
 /// Share a URL with the Web Share API
-/// returns None if the API is not available
-pub fn share_url(url: &str) -> Option<()> {
+///
+/// The share is started immediately, so this must be called synchronously
+/// from the click handler, Safari rejects shares started after an `await`
+/// returns None if the Web Share API is not available, otherwise
+/// a future that resolves once the share sheet is closed
+pub fn share_url(url: &str) -> Option<impl std::future::Future<Output = ShareOutcome>> {
     #[cfg(not(feature = "hydrate"))]
     {
         _ = url;
-        None
+        None::<std::future::Ready<ShareOutcome>>
+    }
+    #[cfg(feature = "hydrate")]
+    {
+        use leptos::window;
+        use wasm_bindgen::{JsCast, JsValue};
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::{
+            js_sys::{Function, Promise, Reflect},
+            ShareData,
+        };
+
+        let start = || {
+            let nav = window().navigator();
+            // Some embedded webviews throw synchronously when `share` is accessed or called
+            // `Navigator::share_with_data` doesn't catch JS exceptions,
+            // so it is invoked through `Reflect` and `Function::call1` which do
+            let share = Reflect::get(&nav, &JsValue::from_str("share"))
+                .ok()?
+                .dyn_into::<Function>()
+                .ok()?;
+            let share_data = ShareData::new();
+            share_data.set_url(url);
+            let res = share.call1(&nav, &share_data).ok()?;
+            Some(res.dyn_into::<Promise>().ok())
+        };
+        let started = start()?;
+
+        Some(async move {
+            let Some(promise) = started else {
+                return ShareOutcome::Shared;
+            };
+            match JsFuture::from(promise).await {
+                Ok(_) => ShareOutcome::Shared,
+                Err(e) => {
+                    let name = Reflect::get(&e, &JsValue::from_str("name"))
+                        .ok()
+                        .and_then(|n| n.as_string());
+                    let outcome = ShareOutcome::from_rejection(name.as_deref());
+                    if outcome == ShareOutcome::Failed {
+                        log::warn!("failed to share url: {e:?}");
+                    }
+                    outcome
+                }
+            }
+        })
+    }
+}
+
+/// Whether the browser requested reduced data usage (`navigator.connection.saveData`)
+pub fn connection_save_data() -> bool {
+    #[cfg(not(feature = "hydrate"))]
+    {
+        false
     }
     #[cfg(feature = "hydrate")]
     {
         use leptos::window;
         use wasm_bindgen::JsValue;
-        use web_sys::{js_sys::Reflect, ShareData};
-        let window = window();
-        let nav = window.navigator();
-        if !Reflect::has(&nav, &JsValue::from_str("share")).unwrap_or_default() {
-            return None;
-        }
-        let share_data = ShareData::new();
-        share_data.set_url(url);
-        _ = nav.share_with_data(&share_data);
-        Some(())
+        use web_sys::js_sys::Reflect;
+
+        Reflect::get(&window().navigator(), &JsValue::from_str("connection"))
+            .ok()
+            .filter(|c| c.is_object())
+            .and_then(|c| Reflect::get(&c, &JsValue::from_str("saveData")).ok())
+            .and_then(|s| s.as_bool())
+            .unwrap_or_default()
     }
 }
 
@@ -32,3 +112,52 @@ pub fn copy_to_clipboard(text: &str) -> Option<()> {
     _ = navigator.clipboard().write_text(text);
     Some(())
 }
+
+/// Copy text to clipboard, the returned future waits for the write to complete
+/// resolves to None if the API is not available or the write failed
+///
+/// Like [share_url], the write is started immediately and must be started
+/// from the click handler for Safari to allow it
+pub fn copy_to_clipboard_checked(text: &str) -> impl std::future::Future<Output = Option<()>> {
+    let write = use_window()
+        .navigator()
+        .map(|navigator| navigator.clipboard().write_text(text));
+    async move {
+        let write = write?;
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen_futures::JsFuture;
+            JsFuture::from(write)
+                .await
+                .inspect_err(|e| log::warn!("failed to copy to clipboard: {e:?}"))
+                .ok()?;
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            _ = write;
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShareOutcome;
+
+    #[test]
+    fn dismissed_shares_are_not_retried() {
+        let outcome = ShareOutcome::from_rejection(Some("AbortError"));
+        assert_eq!(outcome, ShareOutcome::Dismissed);
+        assert!(!outcome.needs_fallback());
+        assert!(!ShareOutcome::Shared.needs_fallback());
+    }
+
+    #[test]
+    fn failed_shares_fall_back() {
+        for name in [Some("NotAllowedError"), Some("DataError"), None] {
+            let outcome = ShareOutcome::from_rejection(name);
+            assert_eq!(outcome, ShareOutcome::Failed);
+            assert!(outcome.needs_fallback());
+        }
+    }
+}
@@ -17,7 +17,23 @@ module.exports = {
           900: 'rgb(var(--color-primary-900))',
           950: 'rgb(var(--color-primary-950))',
         }
-      }
+      },
+      keyframes: {
+        'coin-drop': {
+          '0%': { transform: 'translateY(-150%)', opacity: '0' },
+          '30%': { opacity: '1' },
+          '80%': { transform: 'translateY(100%)', opacity: '1' },
+          '100%': { transform: 'translateY(120%)', opacity: '0' },
+        },
+        'reward-highlight': {
+          '0%': { backgroundColor: 'rgb(var(--color-primary-600) / 0.3)' },
+          '100%': { backgroundColor: 'transparent' },
+        },
+      },
+      animation: {
+        'coin-drop': 'coin-drop 1.2s ease-in forwards',
+        'reward-highlight': 'reward-highlight 3s ease-out forwards',
+      },
     },
   },
   plugins: [],